@@ -9,10 +9,12 @@ abi FRC20 {
 
     /// Get the name of the token
     /// Example (with trailing padding): "MY_TOKEN                                                        "
+    #[storage(read)]
     fn name() -> str[64];
 
     /// Get the symbol of the token
     /// Example (with trailing padding): "TKN                             "
+    #[storage(read)]
     fn symbol() -> str[32];
 
     /// Get the decimals of the token
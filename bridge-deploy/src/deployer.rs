@@ -0,0 +1,122 @@
+use crate::{
+    manifest::{Manifest, ManifestError},
+    registry::{Registry, RegistryEntry},
+};
+
+use std::path::PathBuf;
+
+use bridge_fungible_token_sdk::{
+    abi::BridgeFungibleTokenContractConfigurables, token_config::parse_address, BridgeClient,
+    TokenConfig, TokenConfigError,
+};
+use fuels::{
+    accounts::wallet::WalletUnlocked,
+    prelude::{Bech32ContractId, Contract, ContractId, LoadConfiguration, Provider, TxParameters},
+    tx::Bytes32,
+    types::errors::Error as FuelsError,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeployError {
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+    #[error(transparent)]
+    TokenConfig(#[from] TokenConfigError),
+    #[error(transparent)]
+    Fuels(#[from] FuelsError),
+}
+
+/// A token from the manifest together with the contract it is bridged to
+#[derive(Debug, Clone)]
+pub struct PlannedToken {
+    pub config: TokenConfig,
+    pub configurables: BridgeFungibleTokenContractConfigurables,
+    pub contract_id: ContractId,
+    /// Whether the contract already exists on chain
+    pub deployed: bool,
+}
+
+/// Deploys bridge contracts from a compiled contract binary
+pub struct Deployer {
+    provider: Provider,
+    binary: PathBuf,
+}
+
+impl Deployer {
+    pub fn new(provider: Provider, binary: impl Into<PathBuf>) -> Self {
+        Self {
+            provider,
+            binary: binary.into(),
+        }
+    }
+
+    /// Compute the contract id of every token in the manifest and check which are deployed
+    pub async fn plan(&self, manifest: &Manifest) -> Result<Vec<PlannedToken>, DeployError> {
+        let mut planned = vec![];
+        for config in manifest.token_configs()? {
+            let configurables = config.configurables()?;
+            let contract_id =
+                BridgeClient::precompute_contract_id(&self.binary, Some(configurables.clone()))?;
+            let deployed = self
+                .provider
+                .contract_exists(&Bech32ContractId::from(contract_id))
+                .await?;
+
+            planned.push(PlannedToken {
+                config,
+                configurables,
+                contract_id,
+                deployed,
+            });
+        }
+        Ok(planned)
+    }
+
+    /// Deploy a single planned token, doing nothing if it is already deployed
+    pub async fn deploy(
+        &self,
+        wallet: &WalletUnlocked,
+        token: &mut PlannedToken,
+        tx_params: TxParameters,
+    ) -> Result<(), DeployError> {
+        if token.deployed {
+            return Ok(());
+        }
+
+        Contract::load_from(
+            &self.binary,
+            LoadConfiguration::default().set_configurables(token.configurables.clone()),
+        )?
+        .deploy(wallet, tx_params)
+        .await?;
+        token.deployed = true;
+        Ok(())
+    }
+
+    /// Deploy every token of the manifest that is missing and return the resulting registry
+    pub async fn deploy_manifest(
+        &self,
+        wallet: &WalletUnlocked,
+        manifest: &Manifest,
+        tx_params: TxParameters,
+    ) -> Result<Registry, DeployError> {
+        let mut planned = self.plan(manifest).await?;
+        for token in &mut planned {
+            self.deploy(wallet, token, tx_params).await?;
+        }
+        Ok(registry(manifest, &planned)?)
+    }
+}
+
+/// Build the registry for planned tokens, in manifest order
+pub fn registry(manifest: &Manifest, planned: &[PlannedToken]) -> Result<Registry, ManifestError> {
+    let gateway = parse_address(&manifest.gateway).map_err(ManifestError::Gateway)?;
+
+    Ok(Registry {
+        gateway: format!("{:#x}", Bytes32::new(gateway.0)),
+        tokens: planned
+            .iter()
+            .map(|token| RegistryEntry::new(&token.config, token.contract_id))
+            .collect(),
+    })
+}
@@ -0,0 +1,10 @@
+//! Deploys bridge fungible token contracts, either one at a time or for every
+//! token listed in a manifest.
+
+pub mod deployer;
+pub mod manifest;
+pub mod registry;
+
+pub use deployer::{DeployError, Deployer, PlannedToken};
+pub use manifest::{Manifest, ManifestError, ManifestToken};
+pub use registry::{Registry, RegistryEntry};
@@ -0,0 +1,309 @@
+use std::{path::PathBuf, str::FromStr};
+
+use bridge_deploy::{deployer, Deployer, Manifest};
+use bridge_fungible_token_sdk::{
+    token_config::parse_address, BridgeClient, TokenConfig, TokenConfigError, TokenRegistryClient,
+};
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use fuels::{
+    accounts::{fuel_crypto::SecretKey, wallet::WalletUnlocked, ViewOnlyAccount},
+    prelude::{Address, AssetId, Contract, ContractId, LoadConfiguration, Provider, TxParameters},
+    tx::Bytes32,
+};
+
+/// Deploys bridge fungible token contracts
+#[derive(Parser)]
+struct Cli {
+    /// URL of the fuel-core node
+    #[arg(long, global = true, default_value = "http://127.0.0.1:4000")]
+    node_url: String,
+    /// Secret key of the deploying wallet
+    #[arg(
+        long,
+        global = true,
+        env = "DEPLOYER_SECRET_KEY",
+        hide_env_values = true
+    )]
+    secret_key: Option<String>,
+    /// The compiled bridge fungible token contract
+    #[arg(
+        long,
+        global = true,
+        default_value = "bridge-fungible-token/out/release/bridge_fungible_token.bin"
+    )]
+    binary: PathBuf,
+    #[arg(long, global = true, default_value_t = 1)]
+    gas_price: u64,
+    /// Only print the contract ids, without deploying
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy the contract for a single base layer token
+    Token(TokenArgs),
+    /// Deploy the contracts for every token in a manifest that are not deployed yet
+    Manifest(ManifestArgs),
+    /// Deploy the on-chain token registry
+    DeployRegistry(DeployRegistryArgs),
+    /// Approve the binary built for a single base layer token in the registry
+    Approve(ApproveArgs),
+    /// Add a deployed bridge contract to the registry, which checks it against the approved root
+    Register(RegisterArgs),
+    /// Look up a registry entry by base layer token, bridge contract or proxy asset
+    Lookup(LookupArgs),
+}
+
+#[derive(Args)]
+struct TokenArgs {
+    /// Address of the gateway contract on the base layer
+    #[arg(long)]
+    gateway: String,
+    /// Address of the token contract on the base layer
+    #[arg(long)]
+    token: String,
+    /// Decimals of the proxy token on Fuel
+    #[arg(long, default_value_t = 9)]
+    decimals: u8,
+    /// Decimals of the token contract on the base layer
+    #[arg(long)]
+    bridged_token_decimals: u8,
+    /// Token name, at most 64 ASCII characters
+    #[arg(long)]
+    name: String,
+    /// Token symbol, at most 32 ASCII characters
+    #[arg(long)]
+    symbol: String,
+}
+
+#[derive(Args)]
+struct ManifestArgs {
+    /// The manifest listing the tokens, a .toml or .json file
+    manifest: PathBuf,
+    /// Where to write the registry of deployed contracts
+    #[arg(long, default_value = "registry.json")]
+    registry: PathBuf,
+}
+
+#[derive(Args)]
+struct DeployRegistryArgs {
+    /// The compiled token registry contract
+    #[arg(
+        long,
+        default_value = "bridge-token-registry/out/release/bridge_token_registry.bin"
+    )]
+    registry_binary: PathBuf,
+    /// Address allowed to register bridge contracts, defaults to the deploying wallet
+    #[arg(long)]
+    owner: Option<String>,
+}
+
+#[derive(Args)]
+struct ApproveArgs {
+    /// The token registry contract
+    #[arg(long)]
+    registry: String,
+    #[command(flatten)]
+    token: TokenArgs,
+}
+
+#[derive(Args)]
+struct RegisterArgs {
+    /// The token registry contract
+    #[arg(long)]
+    registry: String,
+    /// The bridge contract to register
+    bridge: String,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("key").required(true).args(["token", "bridge", "asset_id"])))]
+struct LookupArgs {
+    /// The token registry contract
+    #[arg(long)]
+    registry: String,
+    /// Address of the token contract on the base layer
+    #[arg(long)]
+    token: Option<String>,
+    /// The bridge contract on Fuel
+    #[arg(long)]
+    bridge: Option<String>,
+    /// The proxy token on Fuel
+    #[arg(long)]
+    asset_id: Option<String>,
+}
+
+impl TokenArgs {
+    fn config(&self) -> Result<TokenConfig, TokenConfigError> {
+        Ok(TokenConfig {
+            gateway: parse_address(&self.gateway)?,
+            token: parse_address(&self.token)?,
+            decimals: self.decimals,
+            bridged_token_decimals: self.bridged_token_decimals,
+            name: self.name.clone(),
+            symbol: self.symbol.clone(),
+        })
+    }
+}
+
+impl Cli {
+    fn wallet(&self, provider: Provider) -> Result<WalletUnlocked, Box<dyn std::error::Error>> {
+        let secret_key = self
+            .secret_key
+            .as_ref()
+            .ok_or("a secret key is needed to deploy, set DEPLOYER_SECRET_KEY")?;
+        Ok(WalletUnlocked::new_from_private_key(
+            SecretKey::from_str(secret_key)?,
+            Some(provider),
+        ))
+    }
+
+    fn tx_params(&self) -> TxParameters {
+        TxParameters::default().set_gas_price(self.gas_price)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    match &cli.command {
+        Command::Token(args) => deploy_token(&cli, args).await,
+        Command::Manifest(args) => deploy_manifest(&cli, args).await,
+        Command::DeployRegistry(args) => deploy_registry(&cli, args).await,
+        Command::Approve(args) => approve(&cli, args).await,
+        Command::Register(args) => register(&cli, args).await,
+        Command::Lookup(args) => lookup(&cli, args).await,
+    }
+}
+
+async fn deploy_token(cli: &Cli, args: &TokenArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let configurables = args.config()?.configurables()?;
+
+    let contract_id =
+        BridgeClient::precompute_contract_id(&cli.binary, Some(configurables.clone()))?;
+    println!("contract id: {contract_id}");
+    if cli.dry_run {
+        return Ok(());
+    }
+
+    let provider = Provider::connect(&cli.node_url).await?;
+    let wallet = cli.wallet(provider)?;
+    let deployed = Contract::load_from(
+        &cli.binary,
+        LoadConfiguration::default().set_configurables(configurables),
+    )?
+    .deploy(&wallet, cli.tx_params())
+    .await?;
+    println!("deployed: {}", deployed.hash());
+    Ok(())
+}
+
+async fn deploy_manifest(cli: &Cli, args: &ManifestArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::load(&args.manifest)?;
+    let provider = Provider::connect(&cli.node_url).await?;
+    let deployer = Deployer::new(provider.clone(), &cli.binary);
+
+    let mut planned = deployer.plan(&manifest).await?;
+    let wallet = if cli.dry_run {
+        None
+    } else {
+        Some(cli.wallet(provider)?)
+    };
+
+    for token in &mut planned {
+        let symbol = token.config.symbol.trim();
+        match &wallet {
+            _ if token.deployed => println!("{symbol}: {} already deployed", token.contract_id),
+            None => println!("{symbol}: {} not deployed", token.contract_id),
+            Some(wallet) => {
+                deployer.deploy(wallet, token, cli.tx_params()).await?;
+                println!("{symbol}: {} deployed", token.contract_id);
+            }
+        }
+    }
+
+    if wallet.is_some() {
+        deployer::registry(&manifest, &planned)?.save(&args.registry)?;
+        println!("registry written to {}", args.registry.display());
+    }
+    Ok(())
+}
+
+async fn deploy_registry(
+    cli: &Cli,
+    args: &DeployRegistryArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let provider = Provider::connect(&cli.node_url).await?;
+    let wallet = cli.wallet(provider)?;
+    let owner = match &args.owner {
+        Some(owner) => Address::from_str(owner)?,
+        None => wallet.address().into(),
+    };
+
+    let registry =
+        TokenRegistryClient::deploy(wallet, &args.registry_binary, owner, cli.tx_params()).await?;
+    println!("registry: {}", registry.contract_id());
+    Ok(())
+}
+
+async fn approve(cli: &Cli, args: &ApproveArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = args.token.config()?;
+    let root = BridgeClient::precompute_bytecode_root(&cli.binary, Some(config.configurables()?))?;
+    println!("bytecode root: {root}");
+    if cli.dry_run {
+        return Ok(());
+    }
+
+    let provider = Provider::connect(&cli.node_url).await?;
+    let registry =
+        TokenRegistryClient::new(ContractId::from_str(&args.registry)?, cli.wallet(provider)?);
+    registry
+        .approve(&cli.binary, &config, cli.tx_params())
+        .await?;
+    println!("approved {root} for {:#x}", Bytes32::new(config.token.0));
+    Ok(())
+}
+
+async fn register(cli: &Cli, args: &RegisterArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let provider = Provider::connect(&cli.node_url).await?;
+    let registry =
+        TokenRegistryClient::new(ContractId::from_str(&args.registry)?, cli.wallet(provider)?);
+    let bridge = ContractId::from_str(&args.bridge)?;
+
+    let root = registry.expected_bytecode_root(&cli.binary, bridge).await?;
+    println!("expected bytecode root: {root}");
+    if cli.dry_run {
+        return Ok(());
+    }
+    registry.register(bridge, cli.tx_params()).await?;
+    println!("registered {bridge}");
+    Ok(())
+}
+
+async fn lookup(cli: &Cli, args: &LookupArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let provider = Provider::connect(&cli.node_url).await?;
+    // lookups only simulate calls, so any wallet will do
+    let wallet = WalletUnlocked::new_random(Some(provider));
+    let registry = TokenRegistryClient::new(ContractId::from_str(&args.registry)?, wallet);
+
+    let entry = match (&args.token, &args.bridge, &args.asset_id) {
+        (Some(token), _, _) => registry.by_token(parse_address(token)?).await?,
+        (_, Some(bridge), _) => registry.by_bridge(ContractId::from_str(bridge)?).await?,
+        (_, _, Some(asset_id)) => registry.by_asset(AssetId::from_str(asset_id)?).await?,
+        _ => unreachable!("clap requires one of the keys"),
+    };
+    match entry {
+        Some(entry) => {
+            println!("token: {:#x}", Bytes32::new(entry.token.0));
+            println!("bridge: {}", entry.bridge);
+            println!("asset id: {:#x}", Bytes32::new(entry.asset_id.0));
+            println!("decimals: {}", entry.decimals);
+            println!("bridged token decimals: {}", entry.bridged_token_decimals);
+        }
+        None => println!("not registered"),
+    }
+    Ok(())
+}
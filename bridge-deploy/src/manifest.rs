@@ -0,0 +1,106 @@
+use std::{collections::HashSet, fs, io, path::Path};
+
+use bridge_fungible_token_sdk::{
+    token_config::{parse_address, TokenConfigError},
+    TokenConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Decimals of the proxy tokens when the manifest does not say otherwise
+pub const DEFAULT_DECIMALS: u8 = 9;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ManifestError {
+    #[error("failed to read the manifest: {0}")]
+    Io(#[from] io::Error),
+    #[error("invalid TOML manifest: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid JSON manifest: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unknown manifest format {0:?}, expected a .toml or .json file")]
+    UnknownFormat(String),
+    #[error("invalid gateway: {0}")]
+    Gateway(TokenConfigError),
+    #[error("invalid token {token}: {error}")]
+    Token {
+        token: String,
+        error: TokenConfigError,
+    },
+    #[error("token {0} is listed more than once")]
+    DuplicateToken(String),
+}
+
+/// A list of base layer tokens to deploy bridge contracts for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The base layer gateway contract shared by every token
+    pub gateway: String,
+    /// Decimals of the proxy tokens, unless set per token
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    pub tokens: Vec<ManifestToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestToken {
+    /// The base layer token contract
+    pub token: String,
+    /// Decimals of the base layer token
+    pub bridged_token_decimals: u8,
+    /// Decimals of the proxy token, overriding the manifest default
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    pub name: String,
+    pub symbol: String,
+}
+
+fn default_decimals() -> u8 {
+    DEFAULT_DECIMALS
+}
+
+impl Manifest {
+    /// Read a manifest, picking the format from the file extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(toml::from_str(&contents)?),
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Err(ManifestError::UnknownFormat(path.display().to_string())),
+        }
+    }
+
+    /// Validate every token, in manifest order
+    pub fn token_configs(&self) -> Result<Vec<TokenConfig>, ManifestError> {
+        let gateway = parse_address(&self.gateway).map_err(ManifestError::Gateway)?;
+
+        let mut seen = HashSet::new();
+        self.tokens
+            .iter()
+            .map(|entry| {
+                let invalid = |error| ManifestError::Token {
+                    token: entry.token.clone(),
+                    error,
+                };
+                let token = parse_address(&entry.token).map_err(invalid)?;
+                // the same token under a different name would get a second contract
+                if !seen.insert(token.0) {
+                    return Err(ManifestError::DuplicateToken(entry.token.clone()));
+                }
+
+                let config = TokenConfig {
+                    gateway,
+                    token,
+                    decimals: entry.decimals.unwrap_or(self.decimals),
+                    bridged_token_decimals: entry.bridged_token_decimals,
+                    name: entry.name.clone(),
+                    symbol: entry.symbol.clone(),
+                };
+                config.validate().map_err(invalid)?;
+                Ok(config)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,57 @@
+use std::{fs, io, path::Path};
+
+use bridge_fungible_token_sdk::TokenConfig;
+use fuels::{
+    prelude::{AssetId, ContractId},
+    tx::Bytes32,
+};
+use serde::{Deserialize, Serialize};
+
+/// Where each base layer token is bridged to on Fuel
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registry {
+    pub gateway: String,
+    pub tokens: Vec<RegistryEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    /// The base layer token contract, left padded to 32 bytes
+    pub token: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub bridged_token_decimals: u8,
+    /// The bridge contract on Fuel
+    pub contract_id: String,
+    /// The proxy token minted by the bridge contract
+    pub asset_id: String,
+}
+
+impl RegistryEntry {
+    pub fn new(config: &TokenConfig, contract_id: ContractId) -> Self {
+        Self {
+            token: format!("{:#x}", Bytes32::new(config.token.0)),
+            name: config.name.trim().to_string(),
+            symbol: config.symbol.trim().to_string(),
+            decimals: config.decimals,
+            bridged_token_decimals: config.bridged_token_decimals,
+            contract_id: format!("{contract_id:#x}"),
+            asset_id: format!("{:#x}", AssetId::new(*contract_id)),
+        }
+    }
+}
+
+impl Registry {
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Write the registry as JSON, replacing the previous file atomically
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, path)
+    }
+}
@@ -0,0 +1,181 @@
+use bridge_deploy::{Deployer, Manifest, ManifestError, ManifestToken, Registry};
+use bridge_fungible_token_sdk::{BridgeClient, RelayBytecode, TokenConfigError};
+use fuels::{
+    accounts::ViewOnlyAccount,
+    prelude::{launch_provider_and_get_wallet, AssetId, TxParameters},
+    types::Bits256,
+};
+use std::path::PathBuf;
+
+const BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+    "../bridge-fungible-token/out/debug/bridge_fungible_token.bin";
+const BRIDGED_TOKEN: &str = "0x00000000000000000000000000000000000000000000000000000000deadbeef";
+
+fn manifest() -> Manifest {
+    Manifest {
+        gateway: "0x96c53cd98B7297564716a8f2E1de2C83928Af2fe".to_string(),
+        decimals: 9,
+        tokens: vec![
+            ManifestToken {
+                token: "0x00000000000000000000000000000000deadbeef".to_string(),
+                bridged_token_decimals: 18,
+                decimals: None,
+                name: "Dead Beef".to_string(),
+                symbol: "BEEF".to_string(),
+            },
+            ManifestToken {
+                token: "0x000000000000000000000000000000000000cafe".to_string(),
+                bridged_token_decimals: 6,
+                decimals: Some(6),
+                name: "Cafe".to_string(),
+                symbol: "CAFE".to_string(),
+            },
+        ],
+    }
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{}-{name}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn loads_toml_and_json_manifests() {
+    let toml_path = temp_path("manifest.toml");
+    std::fs::write(
+        &toml_path,
+        r#"
+gateway = "0x96c53cd98B7297564716a8f2E1de2C83928Af2fe"
+
+[[tokens]]
+token = "0x00000000000000000000000000000000deadbeef"
+bridged_token_decimals = 18
+name = "Dead Beef"
+symbol = "BEEF"
+
+[[tokens]]
+token = "0x000000000000000000000000000000000000cafe"
+bridged_token_decimals = 6
+decimals = 6
+name = "Cafe"
+symbol = "CAFE"
+"#,
+    )
+    .unwrap();
+    let json_path = temp_path("manifest.json");
+    std::fs::write(
+        &json_path,
+        r#"{
+  "gateway": "0x96c53cd98B7297564716a8f2E1de2C83928Af2fe",
+  "tokens": [
+{
+  "token": "0x00000000000000000000000000000000deadbeef",
+  "bridged_token_decimals": 18,
+  "name": "Dead Beef",
+  "symbol": "BEEF"
+},
+{
+  "token": "0x000000000000000000000000000000000000cafe",
+  "bridged_token_decimals": 6,
+  "decimals": 6,
+  "name": "Cafe",
+  "symbol": "CAFE"
+}
+  ]
+}"#,
+    )
+    .unwrap();
+
+    assert_eq!(Manifest::load(&toml_path).unwrap(), manifest());
+    assert_eq!(Manifest::load(&json_path).unwrap(), manifest());
+
+    let configs = manifest().token_configs().unwrap();
+    assert_eq!(configs[0].decimals, 9);
+    assert_eq!(configs[1].decimals, 6);
+    assert_eq!(
+        configs[0].token,
+        Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+    );
+}
+
+#[test]
+fn rejects_invalid_manifests() {
+    let mut duplicate = manifest();
+    let mut token = duplicate.tokens[0].clone();
+    token.name = "Another Name".to_string();
+    duplicate.tokens.push(token);
+    assert!(matches!(
+        duplicate.token_configs(),
+        Err(ManifestError::DuplicateToken(_))
+    ));
+
+    let mut invalid = manifest();
+    invalid.tokens[1].symbol = "S".repeat(33);
+    assert!(matches!(
+        invalid.token_configs(),
+        Err(ManifestError::Token {
+            error: TokenConfigError::TooLong { .. },
+            ..
+        })
+    ));
+
+    let path = temp_path("manifest.yaml");
+    std::fs::write(&path, "").unwrap();
+    assert!(matches!(
+        Manifest::load(&path),
+        Err(ManifestError::UnknownFormat(_))
+    ));
+}
+
+#[tokio::test]
+async fn deploys_manifest_idempotently() {
+    let wallet = launch_provider_and_get_wallet().await;
+    let provider = wallet.provider().unwrap().clone();
+    let deployer = Deployer::new(provider, BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY);
+    let manifest = manifest();
+
+    let planned = deployer.plan(&manifest).await.unwrap();
+    assert_eq!(planned.len(), 2);
+    assert!(planned.iter().all(|token| !token.deployed));
+
+    let registry = deployer
+        .deploy_manifest(&wallet, &manifest, TxParameters::default())
+        .await
+        .unwrap();
+    for (token, entry) in planned.iter().zip(&registry.tokens) {
+        assert_eq!(
+            token.contract_id,
+            BridgeClient::precompute_contract_id(
+                BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+                Some(token.configurables.clone())
+            )
+            .unwrap()
+        );
+        assert_eq!(entry.contract_id, format!("{:#x}", token.contract_id));
+        assert_eq!(
+            entry.asset_id,
+            format!("{:#x}", AssetId::new(*token.contract_id))
+        );
+    }
+
+    let client = BridgeClient::new(
+        planned[1].contract_id,
+        wallet.clone(),
+        RelayBytecode::new(vec![], vec![]),
+    );
+    let config = client.config().await.unwrap();
+    assert_eq!(config.symbol, "CAFE");
+    assert_eq!(config.decimals, 6);
+
+    // running again deploys nothing and produces the same registry
+    let replanned = deployer.plan(&manifest).await.unwrap();
+    assert!(replanned.iter().all(|token| token.deployed));
+    let path = temp_path("registry.json");
+    registry.save(&path).unwrap();
+    let rerun = deployer
+        .deploy_manifest(&wallet, &manifest, TxParameters::default())
+        .await
+        .unwrap();
+    assert_eq!(rerun, Registry::load(&path).unwrap());
+}
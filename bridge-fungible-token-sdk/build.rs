@@ -0,0 +1,46 @@
+//! Generates the contract bindings from the ABIs vendored in `abi/`, so the crate
+//! builds on its own once published, without the Sway packages of this repository.
+
+use std::{env, fs, path::PathBuf};
+
+/// The bindings to generate and the vendored ABI each one is generated from
+const CONTRACTS: [(&str, &str); 2] = [
+    (
+        "BridgeFungibleTokenContract",
+        "bridge_fungible_token-abi.json",
+    ),
+    ("TokenRegistryContract", "bridge_token_registry-abi.json"),
+];
+
+fn main() {
+    // only the client talks to the contracts
+    if env::var_os("CARGO_FEATURE_CLIENT").is_none() {
+        return;
+    }
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut targets = String::new();
+    for (name, file) in CONTRACTS {
+        let path = manifest_dir.join("abi").join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        // the vendored ABI is the only source, so the bindings never come from a stale
+        // or missing Sway build output
+        let abi = fs::read_to_string(&path).unwrap_or_else(|e| {
+            panic!(
+                "cannot read the vendored ABI {}: {e}, run scripts/vendor_abis.sh after forc build",
+                path.display()
+            )
+        });
+
+        // abigen takes the JSON itself as well as a path, which it would resolve
+        // against the directory cargo runs rustc from rather than this crate
+        targets.push_str(&format!(
+            "    Contract(\n        name = \"{name}\",\n        abi = r####\"{}\"####,\n    ),\n",
+            abi.trim()
+        ));
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("abigen.rs");
+    fs::write(out, format!("abigen!(\n{targets});\n")).unwrap();
+}
@@ -0,0 +1,4 @@
+use fuels::prelude::abigen;
+
+// Bindings generated by `build.rs` from the ABIs vendored in `abi/`
+include!(concat!(env!("OUT_DIR"), "/abigen.rs"));
@@ -0,0 +1,314 @@
+use fuel_core_types::fuel_tx::{
+    input::Input, Bytes32, Chargeable, ConsensusParameters, Output, Receipt, Script,
+    ScriptExecutionResult, Transaction, TransactionFee, TxPointer,
+};
+/**
+ * TODO: This module contains functions that should eventually
+ * be made part of the fuels-rs sdk repo as part of the Provider
+ * implementation, similar to functions like 'build_transfer_tx'
+ */
+use fuels::{
+    accounts::{predicate::Predicate, ViewOnlyAccount},
+    prelude::*,
+    types::{coin_type::CoinType, errors::Error},
+};
+
+/// Gas added on top of the gas used by the dry run, in percent
+pub const GAS_LIMIT_MARGIN_PERCENT: u64 = 20;
+
+/// How the fee of a message-to-contract transaction is paid
+#[derive(Debug, Clone)]
+pub enum RelayGas {
+    /// Coins or message coins, unspent gas is returned to the owner of the first one
+    Coins(Vec<Input>),
+    /// Coins owned by a sponsor predicate, unspent gas goes to `change_to`
+    /// note: whoever builds the transaction picks `change_to`, so the predicate must pin the
+    /// change output itself. The contract message predicate does not and can be drained
+    Sponsor {
+        coins: Vec<Input>,
+        change_to: Address,
+    },
+    /// No gas inputs, the fee is taken from the value of the relayed message
+    /// note: the contract message script forwards the whole message value to the contract,
+    /// so nothing is left to pay a fee with unless the gas price is zero
+    MessageValue { change_to: Address },
+}
+
+impl RelayGas {
+    pub fn inputs(&self) -> &[Input] {
+        match self {
+            RelayGas::Coins(coins) | RelayGas::Sponsor { coins, .. } => coins,
+            RelayGas::MessageValue { .. } => &[],
+        }
+    }
+
+    /// The address receiving unspent gas, if there is anything to return
+    pub fn change_to(&self) -> Result<Option<Address>> {
+        match self {
+            RelayGas::Coins(coins) => match coins.first() {
+                None => Ok(None),
+                Some(Input::CoinSigned(coin)) => Ok(Some(coin.owner)),
+                Some(Input::CoinPredicate(coin)) => Ok(Some(coin.owner)),
+                Some(Input::MessageCoinSigned(message)) => Ok(Some(message.recipient)),
+                Some(Input::MessageCoinPredicate(message)) => Ok(Some(message.recipient)),
+                Some(input) => Err(Error::InvalidData(format!(
+                    "input cannot pay for gas: {input:?}"
+                ))),
+            },
+            RelayGas::Sponsor { change_to, .. } | RelayGas::MessageValue { change_to } => {
+                Ok(Some(*change_to))
+            }
+        }
+    }
+}
+
+impl From<Vec<Input>> for RelayGas {
+    fn from(coins: Vec<Input>) -> Self {
+        RelayGas::Coins(coins)
+    }
+}
+
+impl From<&Vec<Input>> for RelayGas {
+    fn from(coins: &Vec<Input>) -> Self {
+        RelayGas::Coins(coins.clone())
+    }
+}
+
+impl From<&[Input]> for RelayGas {
+    fn from(coins: &[Input]) -> Self {
+        RelayGas::Coins(coins.to_vec())
+    }
+}
+
+/// Spendable base asset owned by a sponsor predicate, to pay gas with [`RelayGas::Sponsor`]
+pub async fn predicate_gas_coins(
+    provider: &Provider,
+    predicate_code: &[u8],
+    predicate_data: &[u8],
+    amount: u64,
+) -> Result<Vec<Input>> {
+    let mut predicate = Predicate::from_code(predicate_code.to_vec());
+    predicate.set_provider(provider.clone());
+
+    let resources = predicate
+        .get_spendable_resources(AssetId::default(), amount)
+        .await?;
+    Ok(resources
+        .into_iter()
+        .filter_map(|resource| match resource {
+            CoinType::Coin(coin) => Some(Input::coin_predicate(
+                coin.utxo_id,
+                coin.owner.into(),
+                coin.amount,
+                coin.asset_id,
+                TxPointer::default(),
+                coin.maturity.into(),
+                predicate_code.to_vec(),
+                predicate_data.to_vec(),
+            )),
+            // messages with data are relayed, not spent on gas
+            CoinType::Message(message) if message.data.is_empty() => {
+                Some(Input::message_coin_predicate(
+                    message.sender.into(),
+                    message.recipient.into(),
+                    message.amount,
+                    message.nonce,
+                    predicate_code.to_vec(),
+                    predicate_data.to_vec(),
+                ))
+            }
+            CoinType::Message(_) => None,
+        })
+        .collect())
+}
+
+/// Gas used by a dry run of a transaction and the limit derived from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub gas_used: u64,
+    pub gas_limit: u64,
+    pub gas_price: u64,
+    /// The most the transaction can cost, paid up front and partly returned as change.
+    /// Includes the fee for its size and is scaled by the chain's gas price factor
+    pub max_fee: u64,
+}
+
+/// The fee a transaction pays when it uses `gas_used`, under the chain's consensus parameters
+pub fn transaction_fee(
+    params: &ConsensusParameters,
+    tx: &ScriptTransaction,
+    gas_used: u64,
+) -> Result<u64> {
+    let script = Script::from(tx.clone());
+    TransactionFee::checked_from_values(
+        params,
+        script.metered_bytes_size() as u64,
+        gas_used,
+        script.price(),
+    )
+    .map(|fee| fee.total())
+    .ok_or_else(|| Error::InvalidData("the transaction fee overflows".to_string()))
+}
+
+/// Build a message-to-contract transaction paying gas as given and with the given outputs,
+/// its gas limit and price are taken from `params`
+pub fn build_contract_message_tx(
+    script_bytecode: &[u8],
+    message: Input,
+    contracts: Vec<Input>,
+    gas: &RelayGas,
+    optional_outputs: &[Output],
+    params: TxParameters,
+) -> Result<ScriptTransaction> {
+    contract_message_tx(
+        script_bytecode,
+        message,
+        contracts,
+        gas,
+        optional_outputs,
+        params.gas_price(),
+        params.gas_limit(),
+        params,
+    )
+}
+
+/// Build a message-to-contract transaction like [`build_contract_message_tx`], with the gas
+/// limit set from a dry run against the node plus [`GAS_LIMIT_MARGIN_PERCENT`]
+pub async fn build_estimated_contract_message_tx(
+    provider: &Provider,
+    script_bytecode: &[u8],
+    message: Input,
+    contracts: Vec<Input>,
+    gas: &RelayGas,
+    optional_outputs: &[Output],
+    params: TxParameters,
+) -> Result<(ScriptTransaction, GasEstimate)> {
+    let consensus_parameters = provider.chain_info().await?.consensus_parameters;
+    let max_gas_per_tx = consensus_parameters.max_gas_per_tx;
+
+    // Dry run with all the gas a transaction may use and no fee to pay
+    let dry_run_tx = contract_message_tx(
+        script_bytecode,
+        message.clone(),
+        contracts.clone(),
+        gas,
+        optional_outputs,
+        0,
+        max_gas_per_tx,
+        params,
+    )?;
+    let receipts = provider.dry_run_no_validation(&dry_run_tx).await?;
+    let script_result = receipts.iter().find_map(|receipt| match receipt {
+        Receipt::ScriptResult { result, gas_used } => Some((result, *gas_used)),
+        _ => None,
+    });
+    let gas_used = match script_result {
+        Some((ScriptExecutionResult::Success, gas_used)) => gas_used,
+        Some((result, _)) => {
+            let revert_id = receipts
+                .iter()
+                .find_map(|receipt| match receipt {
+                    Receipt::Revert { ra, .. } => Some(*ra),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            return Err(Error::RevertTransactionError {
+                reason: format!("dry run of the relay failed: {result:?}"),
+                revert_id,
+                receipts,
+            });
+        }
+        None => {
+            return Err(Error::InvalidData(
+                "dry run returned no script result".to_string(),
+            ))
+        }
+    };
+
+    let gas_limit = (gas_used + gas_used * GAS_LIMIT_MARGIN_PERCENT / 100).min(max_gas_per_tx);
+    let tx = contract_message_tx(
+        script_bytecode,
+        message,
+        contracts,
+        gas,
+        optional_outputs,
+        params.gas_price(),
+        gas_limit,
+        params,
+    )?;
+    let max_fee = TransactionFee::checked_from_tx(&consensus_parameters, &Script::from(tx.clone()))
+        .ok_or_else(|| Error::InvalidData("the transaction fee overflows".to_string()))?
+        .total();
+    if matches!(gas, RelayGas::MessageValue { .. }) && max_fee > 0 {
+        return Err(Error::InvalidData(
+            "the message value is forwarded to the contract and cannot pay a fee, \
+             use a zero gas price or other gas inputs"
+                .to_string(),
+        ));
+    }
+
+    let estimate = GasEstimate {
+        gas_used,
+        gas_limit,
+        gas_price: params.gas_price(),
+        max_fee,
+    };
+    Ok((tx, estimate))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn contract_message_tx(
+    script_bytecode: &[u8],
+    message: Input,
+    contracts: Vec<Input>,
+    gas: &RelayGas,
+    optional_outputs: &[Output],
+    gas_price: u64,
+    gas_limit: u64,
+    params: TxParameters,
+) -> Result<ScriptTransaction> {
+    let gas_coins = gas.inputs();
+    let number_of_contracts = contracts.len();
+    let mut tx_inputs: Vec<Input> = Vec::with_capacity(1 + number_of_contracts + gas_coins.len());
+    let mut tx_outputs: Vec<Output> = Vec::new();
+
+    // Start building tx list of inputs
+    tx_inputs.push(message);
+    for contract in contracts {
+        tx_inputs.push(contract);
+    }
+
+    // Start building tx list of outputs, one for each contract input following the message.
+    // More than 1 contract input means this is a deposit to contract.
+    for index in 1..=number_of_contracts {
+        tx_outputs.push(Output::contract(
+            index as u8,
+            Bytes32::zeroed(),
+            Bytes32::zeroed(),
+        ));
+    }
+
+    // Build a change output for unspent gas
+    if let Some(change_to) = gas.change_to()? {
+        tx_outputs.push(Output::change(change_to, 0, AssetId::default()));
+    }
+
+    // Append provided inputs
+    tx_inputs.extend_from_slice(gas_coins);
+
+    // Append provided outputs
+    tx_outputs.append(&mut optional_outputs.to_vec());
+
+    // Create a new transaction
+    Ok(Transaction::script(
+        gas_price,
+        gas_limit,
+        params.maturity().into(),
+        script_bytecode.to_vec(),
+        vec![],
+        tx_inputs,
+        tx_outputs,
+        vec![],
+    )
+    .into())
+}
@@ -0,0 +1,371 @@
+use crate::{
+    abi::{BridgeFungibleTokenContract, BridgeFungibleTokenContractConfigurables, TokenMetadata},
+    builder::{self, GasEstimate, RelayGas},
+    events::BridgeEvents,
+    revert::RevertReason,
+};
+
+use std::path::Path;
+
+use fuel_core_types::fuel_tx::{Bytes32, Input, Output, Receipt, TxPointer, UtxoId};
+use fuels::{
+    accounts::{predicate::Predicate, wallet::WalletUnlocked, Signer, ViewOnlyAccount},
+    prelude::{
+        Address, AssetId, Bech32Address, Bech32ContractId, CallParameters, Contract, ContractId,
+        Error, LoadConfiguration, Result, ScriptTransaction, TxParameters,
+    },
+    programs::call_response::FuelCallResponse,
+    types::{message::Message, Bits256, U256},
+};
+
+/// Gas forwarded to the bridge contract when calling `withdraw` or `lock`
+pub const WITHDRAW_FORWARDED_GAS: u64 = 5000;
+
+/// Bytecode of the general contract message predicate and script used to relay deposits
+#[derive(Debug, Clone)]
+pub struct RelayBytecode {
+    pub predicate: Vec<u8>,
+    pub script: Vec<u8>,
+}
+
+impl RelayBytecode {
+    pub fn new(predicate: Vec<u8>, script: Vec<u8>) -> Self {
+        Self { predicate, script }
+    }
+
+    /// Read the predicate and script bytecode from the given binary files
+    pub fn load_from(
+        predicate_binary: impl AsRef<Path>,
+        script_binary: impl AsRef<Path>,
+    ) -> std::io::Result<Self> {
+        Ok(Self {
+            predicate: std::fs::read(predicate_binary)?,
+            script: std::fs::read(script_binary)?,
+        })
+    }
+
+    /// The address of the contract message predicate, deposit messages are sent to it
+    pub fn predicate_root(&self) -> Bech32Address {
+        Predicate::from_code(self.predicate.clone())
+            .address()
+            .clone()
+    }
+
+    /// Build the input spending the given message with the contract message predicate
+    pub fn message_input(&self, message: &Message) -> Input {
+        if message.data.is_empty() {
+            Input::message_coin_predicate(
+                message.sender.clone().into(),
+                message.recipient.clone().into(),
+                message.amount,
+                message.nonce,
+                self.predicate.clone(),
+                vec![],
+            )
+        } else {
+            Input::message_data_predicate(
+                message.sender.clone().into(),
+                message.recipient.clone().into(),
+                message.amount,
+                message.nonce,
+                message.data.clone(),
+                self.predicate.clone(),
+                vec![],
+            )
+        }
+    }
+}
+
+/// The configuration the bridge contract was deployed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeConfig {
+    /// The configured name, the contract reports the one in `metadata` once the gateway has sent it
+    pub name: String,
+    /// The configured symbol, the contract reports the one in `metadata` once the gateway has sent it
+    pub symbol: String,
+    pub decimals: u8,
+    pub bridged_token: Bits256,
+    pub bridged_token_decimals: u8,
+    pub bridged_token_gateway: Bits256,
+    /// The token metadata registered by the gateway, if it has sent it yet
+    pub metadata: Option<TokenMetadata>,
+}
+
+impl BridgeConfig {
+    /// Read the configuration of a deployed bridge contract
+    pub async fn read(contract: &BridgeFungibleTokenContract<WalletUnlocked>) -> Result<Self> {
+        let methods = contract.methods();
+        Ok(Self {
+            name: methods
+                .configured_name()
+                .simulate()
+                .await?
+                .value
+                .to_trimmed_str()
+                .to_string(),
+            symbol: methods
+                .configured_symbol()
+                .simulate()
+                .await?
+                .value
+                .to_trimmed_str()
+                .to_string(),
+            decimals: methods.decimals().simulate().await?.value,
+            bridged_token: methods.bridged_token().simulate().await?.value,
+            bridged_token_decimals: methods.bridged_token_decimals().simulate().await?.value,
+            bridged_token_gateway: methods.bridged_token_gateway().simulate().await?.value,
+            metadata: methods.bridged_token_metadata().simulate().await?.value,
+        })
+    }
+}
+
+/// Client for a deployed bridge fungible token contract
+pub struct BridgeClient {
+    contract: BridgeFungibleTokenContract<WalletUnlocked>,
+    wallet: WalletUnlocked,
+    relay: RelayBytecode,
+}
+
+impl BridgeClient {
+    /// Connect to an already deployed bridge contract
+    pub fn new(
+        contract_id: impl Into<Bech32ContractId>,
+        wallet: WalletUnlocked,
+        relay: RelayBytecode,
+    ) -> Self {
+        Self {
+            contract: BridgeFungibleTokenContract::new(contract_id.into(), wallet.clone()),
+            wallet,
+            relay,
+        }
+    }
+
+    /// Deploy the given bridge contract binary and connect to it
+    pub async fn deploy(
+        wallet: WalletUnlocked,
+        binary: impl AsRef<Path>,
+        configurables: Option<BridgeFungibleTokenContractConfigurables>,
+        tx_params: TxParameters,
+        relay: RelayBytecode,
+    ) -> Result<Self> {
+        let contract_id = Contract::load_from(binary, load_configuration(configurables))?
+            .deploy(&wallet, tx_params)
+            .await?;
+
+        Ok(Self::new(contract_id, wallet, relay))
+    }
+
+    /// Compute the id the given bridge contract binary will be deployed at
+    pub fn precompute_contract_id(
+        binary: impl AsRef<Path>,
+        configurables: Option<BridgeFungibleTokenContractConfigurables>,
+    ) -> Result<ContractId> {
+        Ok(Contract::load_from(binary, load_configuration(configurables))?.contract_id())
+    }
+
+    /// Compute the bytecode root of the given bridge contract binary with the given configurables
+    pub fn precompute_bytecode_root(
+        binary: impl AsRef<Path>,
+        configurables: Option<BridgeFungibleTokenContractConfigurables>,
+    ) -> Result<Bytes32> {
+        Ok(Contract::load_from(binary, load_configuration(configurables))?.code_root())
+    }
+
+    pub fn contract(&self) -> &BridgeFungibleTokenContract<WalletUnlocked> {
+        &self.contract
+    }
+
+    pub fn contract_id(&self) -> ContractId {
+        self.contract.contract_id().into()
+    }
+
+    /// The id of the proxy token minted by the bridge contract
+    pub fn asset_id(&self) -> AssetId {
+        AssetId::new(*self.contract.contract_id().hash())
+    }
+
+    pub fn wallet(&self) -> &WalletUnlocked {
+        &self.wallet
+    }
+
+    pub fn relay_bytecode(&self) -> &RelayBytecode {
+        &self.relay
+    }
+
+    /// Read the configuration of the bridge contract
+    pub async fn config(&self) -> Result<BridgeConfig> {
+        BridgeConfig::read(&self.contract).await
+    }
+
+    /// Read the amount of proxy tokens currently minted
+    pub async fn total_supply(&self) -> Result<U256> {
+        Ok(self
+            .contract
+            .methods()
+            .total_supply()
+            .simulate()
+            .await?
+            .value)
+    }
+
+    /// Burn `amount` proxy tokens held by the wallet and release them to `to` on the base layer
+    pub async fn withdraw(
+        &self,
+        to: Bits256,
+        amount: u64,
+        tx_params: TxParameters,
+    ) -> Result<FuelCallResponse<()>> {
+        let call_params = CallParameters::new(amount, self.asset_id(), WITHDRAW_FORWARDED_GAS);
+        self.contract
+            .methods()
+            .withdraw(to)
+            .tx_params(tx_params)
+            .call_params(call_params)?
+            .call()
+            .await
+    }
+
+    /// Lock `amount` of a Fuel-native asset held by the wallet and have the gateway mint its
+    /// wrapped token to `to` on the base layer
+    pub async fn lock(
+        &self,
+        asset_id: AssetId,
+        to: Bits256,
+        amount: u64,
+        tx_params: TxParameters,
+    ) -> Result<FuelCallResponse<()>> {
+        let call_params = CallParameters::new(amount, asset_id, WITHDRAW_FORWARDED_GAS);
+        self.contract
+            .methods()
+            .lock(to)
+            .tx_params(tx_params)
+            .call_params(call_params)?
+            .call()
+            .await
+    }
+
+    /// Read the amount of a Fuel-native asset locked in the bridge contract
+    pub async fn locked_balance(&self, asset_id: AssetId) -> Result<u64> {
+        Ok(self
+            .contract
+            .methods()
+            .locked_balance(ContractId::new(*asset_id))
+            .simulate()
+            .await?
+            .value)
+    }
+
+    /// Send the refund registered for `originator` back to the base layer
+    pub async fn claim_refund(
+        &self,
+        originator: Bits256,
+        asset: Bits256,
+    ) -> Result<FuelCallResponse<()>> {
+        self.contract
+            .methods()
+            .claim_refund(originator, asset)
+            .call()
+            .await
+    }
+
+    /// Build the transaction relaying a deposit message to the bridge contract
+    /// note: `recipient_contract` must be set when the deposit is to a contract, and the gas
+    /// limit is taken from `tx_params`, [`Self::build_estimated_relay_tx`] derives it instead
+    pub fn build_relay_tx(
+        &self,
+        message: &Message,
+        recipient_contract: Option<ContractId>,
+        gas: impl Into<RelayGas>,
+        tx_params: TxParameters,
+    ) -> Result<ScriptTransaction> {
+        builder::build_contract_message_tx(
+            &self.relay.script,
+            self.relay.message_input(message),
+            self.relay_contracts(recipient_contract),
+            &gas.into(),
+            &relay_outputs(),
+            tx_params,
+        )
+    }
+
+    /// Build the transaction relaying a deposit message with its gas limit taken from a dry
+    /// run, the returned estimate gives the fee before anything is sent
+    pub async fn build_estimated_relay_tx(
+        &self,
+        message: &Message,
+        recipient_contract: Option<ContractId>,
+        gas: impl Into<RelayGas>,
+        tx_params: TxParameters,
+    ) -> Result<(ScriptTransaction, GasEstimate)> {
+        builder::build_estimated_contract_message_tx(
+            self.wallet.provider()?,
+            &self.relay.script,
+            self.relay.message_input(message),
+            self.relay_contracts(recipient_contract),
+            &gas.into(),
+            &relay_outputs(),
+            tx_params,
+        )
+        .await
+    }
+
+    /// Relay a deposit message to the bridge contract, paying gas with the given coins or as
+    /// described by a [`RelayGas`]
+    pub async fn relay(
+        &self,
+        message: &Message,
+        recipient_contract: Option<ContractId>,
+        gas: impl Into<RelayGas>,
+        tx_params: TxParameters,
+    ) -> Result<Vec<Receipt>> {
+        let (mut tx, _) = self
+            .build_estimated_relay_tx(message, recipient_contract, gas, tx_params)
+            .await?;
+
+        self.wallet.sign_transaction(&mut tx)?;
+        self.wallet.provider()?.send_transaction(&tx).await
+    }
+
+    /// Decode the bridge events logged in the given receipts
+    pub fn decode_events(&self, receipts: &[Receipt]) -> Result<BridgeEvents> {
+        BridgeEvents::decode(&self.contract.log_decoder(), receipts)
+    }
+
+    /// Decode why a bridge call or relay failed, `None` when the error is not a revert
+    pub fn decode_revert(&self, error: &Error) -> Result<Option<RevertReason>> {
+        RevertReason::from_error(&self.contract.log_decoder(), error)
+    }
+
+    fn relay_contracts(&self, recipient_contract: Option<ContractId>) -> Vec<Input> {
+        let mut contracts = vec![contract_input(self.contract_id())];
+        if let Some(id) = recipient_contract {
+            contracts.push(contract_input(id));
+        }
+        contracts
+    }
+}
+
+/// Outputs of a relay transaction, the variable output receives the minted tokens
+fn relay_outputs() -> Vec<Output> {
+    vec![Output::variable(Address::zeroed(), 0, AssetId::default())]
+}
+
+/// Build a contract input for the given contract
+pub fn contract_input(contract_id: ContractId) -> Input {
+    Input::contract(
+        UtxoId::new(Bytes32::zeroed(), 0u8),
+        Bytes32::zeroed(),
+        Bytes32::zeroed(),
+        TxPointer::default(),
+        contract_id,
+    )
+}
+
+fn load_configuration(
+    configurables: Option<BridgeFungibleTokenContractConfigurables>,
+) -> LoadConfiguration {
+    match configurables {
+        Some(config) => LoadConfiguration::default().set_configurables(config),
+        None => LoadConfiguration::default(),
+    }
+}
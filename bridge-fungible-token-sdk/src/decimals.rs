@@ -0,0 +1,135 @@
+/**
+ * Reference implementation of the decimal conversion done by the bridge
+ * contract in `bridge-fungible-token/src/utils.sw`. These functions must stay bit for bit
+ * identical to the Sway versions, including which error is returned.
+ */
+use primitive_types::U256 as Unsigned256;
+
+/// The subset of `BridgeFungibleTokenError` that a decimal conversion can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalsError {
+    OverflowError,
+    UnderflowError,
+}
+
+/// There are only 78 decimal digits in a 256bit number
+const MAX_DECIMALS_SHIFT: u8 = 77;
+
+/// Get 10 raised to the given exponent, the exponent must not be greater than 77
+pub fn pow_10(exp: u8) -> Unsigned256 {
+    Unsigned256::from(10).pow(Unsigned256::from(exp))
+}
+
+fn shift_decimals_left(bn: Unsigned256, d: u8) -> Result<Unsigned256, DecimalsError> {
+    if d == 0 {
+        return Ok(bn);
+    }
+    if d > MAX_DECIMALS_SHIFT {
+        return Err(DecimalsError::OverflowError);
+    }
+
+    bn.checked_mul(pow_10(d))
+        .ok_or(DecimalsError::OverflowError)
+}
+
+fn shift_decimals_right(bn: Unsigned256, d: u8) -> Result<Unsigned256, DecimalsError> {
+    if d == 0 {
+        return Ok(bn);
+    }
+    if d > MAX_DECIMALS_SHIFT {
+        return Err(DecimalsError::UnderflowError);
+    }
+
+    let (adjusted, remainder) = bn.div_mod(pow_10(d));
+    if !remainder.is_zero() {
+        return Err(DecimalsError::UnderflowError);
+    }
+    Ok(adjusted)
+}
+
+/// Mirrors `adjust_withdrawal_decimals`: converts an amount of proxy tokens being withdrawn
+/// into the amount of base layer tokens released by the gateway.
+pub fn adjust_withdrawal_decimals(
+    val: u64,
+    decimals: u8,
+    bridged_token_decimals: u8,
+) -> Result<Unsigned256, DecimalsError> {
+    let value = Unsigned256::from(val);
+    if bridged_token_decimals > decimals {
+        shift_decimals_left(value, bridged_token_decimals - decimals)
+    } else if bridged_token_decimals < decimals {
+        shift_decimals_right(value, decimals - bridged_token_decimals)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Mirrors `adjust_deposit_decimals`: converts an amount of base layer tokens being deposited
+/// into the amount of proxy tokens minted on Fuel.
+pub fn adjust_deposit_decimals(
+    val: Unsigned256,
+    decimals: u8,
+    bridged_token_decimals: u8,
+) -> Result<u64, DecimalsError> {
+    let adjusted = if bridged_token_decimals > decimals {
+        shift_decimals_right(val, bridged_token_decimals - decimals)?
+    } else if bridged_token_decimals < decimals {
+        shift_decimals_left(val, decimals - bridged_token_decimals)?
+    } else {
+        val
+    };
+
+    if adjusted > Unsigned256::from(u64::MAX) {
+        return Err(DecimalsError::OverflowError);
+    }
+    Ok(adjusted.as_u64())
+}
+
+/// The smallest deposit of base layer tokens that mints at least one proxy token
+pub fn min_deposit(decimals: u8, bridged_token_decimals: u8) -> Result<Unsigned256, DecimalsError> {
+    let amount = if bridged_token_decimals > decimals {
+        let delta = bridged_token_decimals - decimals;
+        if delta > MAX_DECIMALS_SHIFT {
+            return Err(DecimalsError::UnderflowError);
+        }
+        pow_10(delta)
+    } else {
+        Unsigned256::one()
+    };
+    adjust_deposit_decimals(amount, decimals, bridged_token_decimals)?;
+    Ok(amount)
+}
+
+/// The largest deposit of base layer tokens whose proxy tokens still fit in a u64
+pub fn max_deposit(decimals: u8, bridged_token_decimals: u8) -> Result<Unsigned256, DecimalsError> {
+    // no deposit converts when the smallest one does not
+    min_deposit(decimals, bridged_token_decimals)?;
+
+    let max = Unsigned256::from(u64::MAX);
+    let amount = if bridged_token_decimals > decimals {
+        shift_decimals_left(max, bridged_token_decimals - decimals)?
+    } else if bridged_token_decimals < decimals {
+        max / pow_10(decimals - bridged_token_decimals)
+    } else {
+        max
+    };
+    adjust_deposit_decimals(amount, decimals, bridged_token_decimals)?;
+    Ok(amount)
+}
+
+/// Check that a contract with the given decimals can bridge tokens in both directions,
+/// i.e. that `shift_decimals_left` does not overflow for the smallest deposit or the
+/// largest withdrawal.
+pub fn check_supported_decimals(
+    decimals: u8,
+    bridged_token_decimals: u8,
+) -> Result<(), DecimalsError> {
+    // the smallest deposit that mints one proxy token must convert
+    min_deposit(decimals, bridged_token_decimals)?;
+
+    // every amount of proxy tokens must be withdrawable
+    if bridged_token_decimals > decimals {
+        adjust_withdrawal_decimals(u64::MAX, decimals, bridged_token_decimals)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use crate::token_config::{pad, TokenConfigError, NAME_LEN, SYMBOL_LEN};
+
+use fuels::{
+    prelude::{AssetId, ContractId},
+    types::Bits256,
+};
+use primitive_types::U256 as Unsigned256;
+use sha3::{Digest, Keccak256};
+
+/// Length of the data of a deposit to an address, including the contract id prefix
+pub const DEPOSIT_TO_ADDRESS_DATA_LEN: usize = 160;
+/// Length of the data of a deposit to a contract without a callback, including the contract id prefix
+pub const DEPOSIT_TO_CONTRACT_DATA_LEN: usize = 161;
+/// Length of the data of a metadata message, including the contract id prefix
+pub const METADATA_MESSAGE_DATA_LEN: usize = 193;
+
+/// A deposit as sent by the base layer gateway, laid out the way `parse_message_data` reads it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositMessage {
+    /// The base layer token being deposited
+    pub token: Bits256,
+    /// The base layer address making the deposit, refunds are registered to it
+    pub from: Bits256,
+    /// The Fuel address or contract receiving the minted tokens
+    pub to: Bits256,
+    /// The deposited amount, in base layer decimals
+    pub amount: Unsigned256,
+    /// Whether `to` is a contract id rather than an address
+    pub deposit_to_contract: bool,
+    /// Data forwarded to the recipient contract, which makes the bridge call its `process_message`
+    pub extra_data: Vec<u8>,
+}
+
+impl DepositMessage {
+    /// A deposit of `amount` of `token` to the Fuel address `to`
+    pub fn to_address(token: Bits256, from: Bits256, to: Bits256, amount: Unsigned256) -> Self {
+        Self {
+            token,
+            from,
+            to,
+            amount,
+            deposit_to_contract: false,
+            extra_data: vec![],
+        }
+    }
+
+    /// A deposit of `amount` of `token` to the Fuel contract `to`
+    pub fn to_contract(
+        token: Bits256,
+        from: Bits256,
+        to: ContractId,
+        amount: Unsigned256,
+        extra_data: Vec<u8>,
+    ) -> Self {
+        Self {
+            token,
+            from,
+            to: Bits256(*to),
+            amount,
+            deposit_to_contract: true,
+            extra_data,
+        }
+    }
+
+    /// Encode the message data, prefixed with the id of the bridge contract the
+    /// contract message predicate should relay it to
+    pub fn encode(&self, bridge_contract_id: ContractId) -> Vec<u8> {
+        let mut data = Vec::with_capacity(DEPOSIT_TO_CONTRACT_DATA_LEN + self.extra_data.len());
+        data.extend_from_slice(bridge_contract_id.as_ref());
+        data.extend_from_slice(&self.token.0);
+        data.extend_from_slice(&self.from.0);
+        data.extend_from_slice(&self.to.0);
+
+        let mut amount = [0u8; 32];
+        self.amount.to_big_endian(&mut amount);
+        data.extend_from_slice(&amount);
+
+        if self.deposit_to_contract {
+            data.push(deposit_to_contract_marker());
+        }
+        data.extend_from_slice(&self.extra_data);
+        data
+    }
+}
+
+/// The byte marking a deposit to a contract, the first byte of keccak256("DEPOSIT_TO_CONTRACT")
+pub fn deposit_to_contract_marker() -> u8 {
+    let mut hasher = Keccak256::new();
+    hasher.update("DEPOSIT_TO_CONTRACT");
+    hasher.finalize()[0]
+}
+
+/// The token metadata sent once by the base layer gateway, laid out the way
+/// `parse_metadata_message` reads it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataMessage {
+    /// The base layer token the metadata was read from
+    pub token: Bits256,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl MetadataMessage {
+    /// The metadata of `token`, the name and symbol must fit the contract string lengths
+    pub fn new(
+        token: Bits256,
+        name: &str,
+        symbol: &str,
+        decimals: u8,
+    ) -> Result<Self, TokenConfigError> {
+        pad::<NAME_LEN>("name", name)?;
+        pad::<SYMBOL_LEN>("symbol", symbol)?;
+        Ok(Self {
+            token,
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            decimals,
+        })
+    }
+
+    /// Encode the message data, prefixed with the id of the bridge contract the
+    /// contract message predicate should relay it to
+    pub fn encode(&self, bridge_contract_id: ContractId) -> Vec<u8> {
+        let mut data = Vec::with_capacity(METADATA_MESSAGE_DATA_LEN);
+        data.extend_from_slice(bridge_contract_id.as_ref());
+        data.extend_from_slice(&metadata_message_marker());
+        data.extend_from_slice(&self.token.0);
+        data.extend_from_slice(format!("{:<width$}", self.name, width = NAME_LEN).as_bytes());
+        data.extend_from_slice(format!("{:<width$}", self.symbol, width = SYMBOL_LEN).as_bytes());
+        data.push(self.decimals);
+        data
+    }
+}
+
+/// The word marking a metadata message where a deposit has its token, keccak256("TOKEN_METADATA")
+pub fn metadata_message_marker() -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update("TOKEN_METADATA");
+    hasher.finalize().into()
+}
+
+/// A release of Fuel-native coins locked in the bridge contract, sent by the base layer gateway
+/// when their wrapped token is burned. Laid out like a deposit of the asset, after a marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseMessage {
+    /// The locked Fuel-native asset
+    pub asset: AssetId,
+    /// The base layer address that burned the wrapped tokens
+    pub from: Bits256,
+    /// The Fuel address or contract receiving the released coins
+    pub to: Bits256,
+    pub amount: u64,
+    /// Whether `to` is a contract id rather than an address
+    pub release_to_contract: bool,
+}
+
+impl ReleaseMessage {
+    /// A release of `amount` of `asset` to the Fuel address `to`
+    pub fn to_address(asset: AssetId, from: Bits256, to: Bits256, amount: u64) -> Self {
+        Self {
+            asset,
+            from,
+            to,
+            amount,
+            release_to_contract: false,
+        }
+    }
+
+    /// A release of `amount` of `asset` to the Fuel contract `to`
+    pub fn to_contract(asset: AssetId, from: Bits256, to: ContractId, amount: u64) -> Self {
+        Self {
+            asset,
+            from,
+            to: Bits256(*to),
+            amount,
+            release_to_contract: true,
+        }
+    }
+
+    /// Encode the message data, prefixed with the id of the bridge contract the
+    /// contract message predicate should relay it to
+    pub fn encode(&self, bridge_contract_id: ContractId) -> Vec<u8> {
+        let deposit = DepositMessage {
+            token: Bits256(*self.asset),
+            from: self.from,
+            to: self.to,
+            amount: self.amount.into(),
+            deposit_to_contract: self.release_to_contract,
+            extra_data: vec![],
+        };
+        let mut data = deposit.encode(bridge_contract_id);
+        data.splice(32..32, release_message_marker());
+        data
+    }
+}
+
+/// The word marking a release message where a deposit has its token, keccak256("RELEASE")
+pub fn release_message_marker() -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update("RELEASE");
+    hasher.finalize().into()
+}
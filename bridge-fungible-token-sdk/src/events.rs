@@ -0,0 +1,35 @@
+use crate::abi::{DepositEvent, LockEvent, RefundRegisteredEvent, ReleaseEvent, WithdrawalEvent};
+
+use fuels::{prelude::Result, programs::logs::LogDecoder, tx::Receipt};
+
+/// All the bridge events logged in a set of receipts
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BridgeEvents {
+    pub deposits: Vec<DepositEvent>,
+    pub withdrawals: Vec<WithdrawalEvent>,
+    pub refunds_registered: Vec<RefundRegisteredEvent>,
+    pub locks: Vec<LockEvent>,
+    pub releases: Vec<ReleaseEvent>,
+}
+
+impl BridgeEvents {
+    /// Decode the bridge events from the given receipts with the bridge contract log decoder
+    pub fn decode(log_decoder: &LogDecoder, receipts: &[Receipt]) -> Result<Self> {
+        Ok(Self {
+            deposits: log_decoder.decode_logs_with_type::<DepositEvent>(receipts)?,
+            withdrawals: log_decoder.decode_logs_with_type::<WithdrawalEvent>(receipts)?,
+            refunds_registered: log_decoder
+                .decode_logs_with_type::<RefundRegisteredEvent>(receipts)?,
+            locks: log_decoder.decode_logs_with_type::<LockEvent>(receipts)?,
+            releases: log_decoder.decode_logs_with_type::<ReleaseEvent>(receipts)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deposits.is_empty()
+            && self.withdrawals.is_empty()
+            && self.refunds_registered.is_empty()
+            && self.locks.is_empty()
+            && self.releases.is_empty()
+    }
+}
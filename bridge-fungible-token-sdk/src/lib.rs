@@ -0,0 +1,49 @@
+//! Rust client for the bridge fungible token contract.
+//!
+//! The contract ids, the compiled contract binary and the contract message
+//! predicate/script bytecode are all passed in by the caller, nothing is read
+//! from the paths of this repository at runtime.
+
+pub mod decimals;
+
+#[cfg(feature = "client")]
+pub mod abi;
+#[cfg(feature = "client")]
+pub mod builder;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "client")]
+pub mod deposit;
+#[cfg(feature = "client")]
+pub mod events;
+#[cfg(feature = "client")]
+pub mod proof;
+#[cfg(feature = "client")]
+pub mod revert;
+#[cfg(feature = "client")]
+pub mod simulate;
+#[cfg(feature = "client")]
+pub mod token_config;
+#[cfg(feature = "client")]
+pub mod token_registry;
+#[cfg(feature = "client")]
+pub mod withdrawal;
+
+#[cfg(feature = "client")]
+pub use client::{BridgeClient, BridgeConfig, RelayBytecode};
+#[cfg(feature = "client")]
+pub use deposit::{DepositMessage, MetadataMessage, ReleaseMessage};
+#[cfg(feature = "client")]
+pub use events::BridgeEvents;
+#[cfg(feature = "client")]
+pub use proof::{BlockMessages, MessageProof};
+#[cfg(feature = "client")]
+pub use revert::RevertReason;
+#[cfg(feature = "client")]
+pub use simulate::{simulate_deposit, DepositOutcome, RefundReason};
+#[cfg(feature = "client")]
+pub use token_config::{TokenConfig, TokenConfigError};
+#[cfg(feature = "client")]
+pub use token_registry::TokenRegistryClient;
+#[cfg(feature = "client")]
+pub use withdrawal::{WithdrawalMessage, WithdrawalMessageError};
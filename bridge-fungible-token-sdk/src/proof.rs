@@ -0,0 +1,124 @@
+use crate::withdrawal;
+
+use fuel_core_types::{
+    fuel_merkle::binary::{self, in_memory::MerkleTree},
+    fuel_tx::Receipt,
+    fuel_types::{Bytes32, MessageId},
+};
+use fuels::{
+    prelude::{Provider, Result},
+    types::errors::Error,
+};
+
+/// The ids of the messages sent in a block, in the order the block header commits to them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMessages {
+    pub block_id: Bytes32,
+    pub height: u64,
+    /// The `message_receipt_root` of the block header
+    pub header_root: Bytes32,
+    pub message_ids: Vec<MessageId>,
+}
+
+/// Proof that a message id is a leaf of a block's message Merkle tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageProof {
+    pub message_id: MessageId,
+    pub root: Bytes32,
+    /// Position of the message among the messages of the block
+    pub proof_index: u64,
+    pub num_leaves: u64,
+    pub proof_set: Vec<Bytes32>,
+}
+
+impl BlockMessages {
+    /// Collect the `MessageOut` receipts of every transaction in the block at the given height
+    pub async fn fetch(provider: &Provider, height: u64) -> Result<Self> {
+        let block = provider
+            .block_by_height(height)
+            .await?
+            .ok_or_else(|| Error::ProviderError(format!("no block at height {height}")))?;
+
+        let mut receipts = vec![];
+        for tx_id in &block.transactions {
+            receipts.extend(provider.get_receipts(tx_id).await?);
+        }
+
+        Ok(Self {
+            block_id: block.id,
+            height,
+            header_root: block.header.message_receipt_root,
+            message_ids: message_ids(&receipts),
+        })
+    }
+
+    /// The root of the Merkle tree of the message ids
+    pub fn root(&self) -> Bytes32 {
+        Bytes32::new(self.tree().root())
+    }
+
+    /// Check the message ids add up to the root in the block header
+    pub fn matches_header(&self) -> bool {
+        self.root() == self.header_root
+    }
+
+    /// Prove the given message was sent in this block
+    pub fn prove(&self, message_id: &MessageId) -> Option<MessageProof> {
+        let proof_index = self.message_ids.iter().position(|id| id == message_id)? as u64;
+        let (root, proof_set) = self.tree().prove(proof_index)?;
+
+        Some(MessageProof {
+            message_id: *message_id,
+            root: Bytes32::new(root),
+            proof_index,
+            num_leaves: self.message_ids.len() as u64,
+            proof_set: proof_set.into_iter().map(Bytes32::new).collect(),
+        })
+    }
+
+    fn tree(&self) -> MerkleTree {
+        let mut tree = MerkleTree::new();
+        for id in &self.message_ids {
+            tree.push(id.as_ref());
+        }
+        tree
+    }
+}
+
+impl MessageProof {
+    /// Check the message id is included in the root
+    pub fn verify(&self) -> bool {
+        let proof_set: Vec<[u8; 32]> = self.proof_set.iter().map(|node| **node).collect();
+        binary::verify(
+            &*self.root,
+            &self.message_id,
+            &proof_set,
+            self.proof_index,
+            self.num_leaves,
+        )
+    }
+}
+
+/// The ids of the messages sent by the given receipts, in order
+pub fn message_ids(receipts: &[Receipt]) -> Vec<MessageId> {
+    receipts
+        .iter()
+        .filter_map(|receipt| match receipt {
+            Receipt::MessageOut {
+                sender,
+                recipient,
+                amount,
+                nonce,
+                data,
+                ..
+            } => Some(withdrawal::message_id(
+                sender.as_ref(),
+                recipient,
+                nonce,
+                *amount,
+                data,
+            )),
+            _ => None,
+        })
+        .collect()
+}
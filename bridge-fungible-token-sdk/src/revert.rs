@@ -0,0 +1,55 @@
+use crate::abi::BridgeFungibleTokenError;
+
+use fuel_core_types::{fuel_asm::PanicReason, fuel_tx::Receipt};
+use fuels::{
+    prelude::{Error, Result},
+    programs::logs::LogDecoder,
+};
+
+/// Revert code of a failed `require`, the error it was given is logged just before
+pub const FAILED_REQUIRE_SIGNAL: u64 = 0xffff_ffff_ffff_0000;
+
+/// Why a transaction calling the bridge contract reverted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// A `require` failed with the logged bridge error
+    Bridge(BridgeFungibleTokenError),
+    /// A `require` failed without logging a bridge error, e.g. in a recipient contract
+    FailedRequire,
+    /// `revert` was called with the given code, unwrapping a `None` or an `Err` reverts with `0`
+    Revert(u64),
+    /// The VM panicked
+    Panic(PanicReason),
+}
+
+impl RevertReason {
+    /// Decode the reason from the receipts of a transaction, `None` when nothing reverted
+    pub fn decode(log_decoder: &LogDecoder, receipts: &[Receipt]) -> Result<Option<Self>> {
+        for receipt in receipts {
+            match receipt {
+                Receipt::Panic { reason, .. } => return Ok(Some(Self::Panic(*reason.reason()))),
+                Receipt::Revert { ra, .. } if *ra == FAILED_REQUIRE_SIGNAL => {
+                    let errors =
+                        log_decoder.decode_logs_with_type::<BridgeFungibleTokenError>(receipts)?;
+                    return Ok(Some(
+                        errors
+                            .last()
+                            .cloned()
+                            .map_or(Self::FailedRequire, Self::Bridge),
+                    ));
+                }
+                Receipt::Revert { ra, .. } => return Ok(Some(Self::Revert(*ra))),
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decode the reason from the receipts carried by a reverted transaction error
+    pub fn from_error(log_decoder: &LogDecoder, error: &Error) -> Result<Option<Self>> {
+        match error {
+            Error::RevertTransactionError { receipts, .. } => Self::decode(log_decoder, receipts),
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+/**
+ * Offline prediction of what `process_message` in `bridge-fungible-token/src/bridge_fungible_token.sw`
+ * does with a deposit message. The checks must stay in the same order as in the contract.
+ */
+use crate::{
+    abi::BridgeFungibleTokenError,
+    client::BridgeConfig,
+    decimals::{self, DecimalsError},
+    deposit::{
+        metadata_message_marker, release_message_marker, DEPOSIT_TO_ADDRESS_DATA_LEN,
+        DEPOSIT_TO_CONTRACT_DATA_LEN, METADATA_MESSAGE_DATA_LEN,
+    },
+};
+
+use fuels::{
+    prelude::{Address, AssetId, ContractId},
+    types::{message::Message, Bits256, Identity},
+};
+use primitive_types::U256 as Unsigned256;
+
+/// Why a deposit registers a refund instead of minting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefundReason {
+    /// The deposited token is not the one bridged by the contract
+    WrongToken,
+    /// The gateway registered the metadata of another token, or with other decimals
+    MetadataMismatch,
+    /// The amount cannot be converted into proxy token decimals
+    Decimals(DecimalsError),
+}
+
+/// What relaying a deposit message does, as predicted by [`simulate_deposit`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DepositOutcome {
+    /// `amount` proxy tokens are minted to `to`, `callback` is set when the bridge then calls
+    /// `process_message` on the recipient contract
+    Mint {
+        to: Identity,
+        amount: u64,
+        callback: bool,
+    },
+    /// The deposit is recorded as a refund to `from` that can be claimed on the base layer
+    Refund {
+        from: Bits256,
+        token: Bits256,
+        amount: Unsigned256,
+        reason: RefundReason,
+    },
+    /// The message registers the token metadata
+    RegisterMetadata,
+    /// `amount` locked coins of `asset` are released to `to`
+    Release {
+        to: Identity,
+        asset: AssetId,
+        amount: u64,
+    },
+    /// The relay transaction reverts and the message stays unspent
+    Revert(BridgeFungibleTokenError),
+}
+
+/// Predict the outcome of relaying `message` to a bridge contract with the given configuration
+/// note: a callback to a recipient contract can still revert, and so can minting past
+/// `u64::MAX` total supply, and a release can exceed the locked balance, none of which is
+/// known without the chain state
+pub fn simulate_deposit(message: &Message, config: &BridgeConfig) -> DepositOutcome {
+    let sender: Address = (&message.sender).into();
+    simulate_deposit_data(sender, &message.data, config)
+}
+
+/// Like [`simulate_deposit`], for a message sent by `sender` with the given data
+pub fn simulate_deposit_data(
+    sender: Address,
+    data: &[u8],
+    config: &BridgeConfig,
+) -> DepositOutcome {
+    if *sender != config.bridged_token_gateway.0 {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::UnauthorizedSender);
+    }
+    if data.len() < DEPOSIT_TO_ADDRESS_DATA_LEN {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::MessageDataTooShort);
+    }
+
+    let word = |offset: usize| -> [u8; 32] { data[offset..offset + 32].try_into().unwrap() };
+    if word(32) == metadata_message_marker() {
+        return if config.metadata.is_some() {
+            DepositOutcome::Revert(BridgeFungibleTokenError::MetadataAlreadyRegistered)
+        } else if data.len() < METADATA_MESSAGE_DATA_LEN {
+            DepositOutcome::Revert(BridgeFungibleTokenError::MessageDataTooShort)
+        } else {
+            DepositOutcome::RegisterMetadata
+        };
+    }
+    if word(32) == release_message_marker() {
+        return simulate_release(&data[32..]);
+    }
+
+    let token = Bits256(word(32));
+    let from = Bits256(word(64));
+    let to = word(96);
+    let amount = Unsigned256::from_big_endian(&word(128));
+
+    if amount.is_zero() {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::NoCoinsSent);
+    }
+    if token != config.bridged_token {
+        return DepositOutcome::Refund {
+            from,
+            token,
+            amount,
+            reason: RefundReason::WrongToken,
+        };
+    }
+    let metadata_matches = config.metadata.as_ref().map_or(true, |metadata| {
+        metadata.token == config.bridged_token && metadata.decimals == config.bridged_token_decimals
+    });
+    if !metadata_matches {
+        return DepositOutcome::Refund {
+            from,
+            token,
+            amount,
+            reason: RefundReason::MetadataMismatch,
+        };
+    }
+
+    match decimals::adjust_deposit_decimals(amount, config.decimals, config.bridged_token_decimals)
+    {
+        Ok(minted) => DepositOutcome::Mint {
+            to: if data.len() > DEPOSIT_TO_ADDRESS_DATA_LEN {
+                Identity::ContractId(ContractId::new(to))
+            } else {
+                Identity::Address(Address::new(to))
+            },
+            amount: minted,
+            callback: data.len() > DEPOSIT_TO_CONTRACT_DATA_LEN,
+        },
+        Err(error) => DepositOutcome::Refund {
+            from,
+            token,
+            amount,
+            reason: RefundReason::Decimals(error),
+        },
+    }
+}
+
+/// Predict a release from the data following its marker, which is laid out like a deposit
+fn simulate_release(data: &[u8]) -> DepositOutcome {
+    if data.len() < DEPOSIT_TO_ADDRESS_DATA_LEN {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::MessageDataTooShort);
+    }
+
+    let word = |offset: usize| -> [u8; 32] { data[offset..offset + 32].try_into().unwrap() };
+    let to = word(96);
+    let amount = Unsigned256::from_big_endian(&word(128));
+
+    // nothing locked can exceed a u64, the contract checks this before the amount is zero
+    if amount > Unsigned256::from(u64::MAX) {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::ReleaseExceedsLocked);
+    }
+    if amount.is_zero() {
+        return DepositOutcome::Revert(BridgeFungibleTokenError::NoCoinsSent);
+    }
+
+    DepositOutcome::Release {
+        to: if data.len() > DEPOSIT_TO_ADDRESS_DATA_LEN {
+            Identity::ContractId(ContractId::new(to))
+        } else {
+            Identity::Address(Address::new(to))
+        },
+        asset: AssetId::new(word(32)),
+        amount: amount.as_u64(),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    abi::BridgeFungibleTokenContractConfigurables,
+    client::BridgeConfig,
+    decimals::{self, DecimalsError},
+};
+
+use fuels::types::{Bits256, SizedAsciiString};
+
+/// Length of the `NAME` configurable, `str[64]`
+pub const NAME_LEN: usize = 64;
+/// Length of the `SYMBOL` configurable, `str[32]`
+pub const SYMBOL_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TokenConfigError {
+    #[error("invalid address {0}, expected 20 or 32 bytes of 0x prefixed hex")]
+    InvalidAddress(String),
+    #[error("{field} must not be empty")]
+    Empty { field: &'static str },
+    #[error("{field} must only contain ASCII characters")]
+    NotAscii { field: &'static str },
+    #[error("{field} is {len} characters long, at most {max} fit")]
+    TooLong {
+        field: &'static str,
+        len: usize,
+        max: usize,
+    },
+    #[error("decimals {decimals} cannot bridge a token with {bridged_token_decimals} decimals ({error:?})")]
+    UnsupportedDecimals {
+        decimals: u8,
+        bridged_token_decimals: u8,
+        error: DecimalsError,
+    },
+}
+
+/// Everything a bridge contract is configured with at deploy time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenConfig {
+    /// The base layer gateway contract
+    pub gateway: Bits256,
+    /// The base layer token contract
+    pub token: Bits256,
+    /// Decimals of the proxy token on Fuel
+    pub decimals: u8,
+    /// Decimals of the base layer token
+    pub bridged_token_decimals: u8,
+    pub name: String,
+    pub symbol: String,
+}
+
+impl TokenConfig {
+    /// Check the configuration can be deployed and bridge tokens in both directions
+    pub fn validate(&self) -> Result<(), TokenConfigError> {
+        pad::<NAME_LEN>("name", &self.name)?;
+        pad::<SYMBOL_LEN>("symbol", &self.symbol)?;
+        decimals::check_supported_decimals(self.decimals, self.bridged_token_decimals).map_err(
+            |error| TokenConfigError::UnsupportedDecimals {
+                decimals: self.decimals,
+                bridged_token_decimals: self.bridged_token_decimals,
+                error,
+            },
+        )
+    }
+
+    /// Build the contract configurables, padding the name and symbol with spaces
+    pub fn configurables(
+        &self,
+    ) -> Result<BridgeFungibleTokenContractConfigurables, TokenConfigError> {
+        self.validate()?;
+
+        Ok(BridgeFungibleTokenContractConfigurables::new()
+            .set_BRIDGED_TOKEN_GATEWAY(self.gateway)
+            .set_BRIDGED_TOKEN(self.token)
+            .set_DECIMALS(self.decimals)
+            .set_BRIDGED_TOKEN_DECIMALS(self.bridged_token_decimals)
+            .set_NAME(pad::<NAME_LEN>("name", &self.name)?)
+            .set_SYMBOL(pad::<SYMBOL_LEN>("symbol", &self.symbol)?))
+    }
+}
+
+/// The configuration a contract deployed with this token config reports
+impl From<&TokenConfig> for BridgeConfig {
+    fn from(config: &TokenConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            symbol: config.symbol.clone(),
+            decimals: config.decimals,
+            bridged_token: config.token,
+            bridged_token_decimals: config.bridged_token_decimals,
+            bridged_token_gateway: config.gateway,
+            metadata: None,
+        }
+    }
+}
+
+/// The token config a contract reporting this configuration was deployed with
+impl From<&BridgeConfig> for TokenConfig {
+    fn from(config: &BridgeConfig) -> Self {
+        Self {
+            gateway: config.bridged_token_gateway,
+            token: config.bridged_token,
+            decimals: config.decimals,
+            bridged_token_decimals: config.bridged_token_decimals,
+            name: config.name.clone(),
+            symbol: config.symbol.clone(),
+        }
+    }
+}
+
+/// Parse a base layer address, either 20 bytes or already left padded to 32 bytes
+pub fn parse_address(address: &str) -> Result<Bits256, TokenConfigError> {
+    let invalid = || TokenConfigError::InvalidAddress(address.to_string());
+    let hex = address.strip_prefix("0x").ok_or_else(invalid)?;
+    if (hex.len() != 40 && hex.len() != 64) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let padded = format!("0x{hex:0>64}");
+    Bits256::from_hex_str(&padded).map_err(|_| invalid())
+}
+
+/// Right pad a string with spaces into a fixed length ASCII string
+pub fn pad<const LEN: usize>(
+    field: &'static str,
+    value: &str,
+) -> Result<SizedAsciiString<LEN>, TokenConfigError> {
+    if value.trim().is_empty() {
+        return Err(TokenConfigError::Empty { field });
+    }
+    if !value.is_ascii() {
+        return Err(TokenConfigError::NotAscii { field });
+    }
+    if value.len() > LEN {
+        return Err(TokenConfigError::TooLong {
+            field,
+            len: value.len(),
+            max: LEN,
+        });
+    }
+
+    Ok(
+        SizedAsciiString::new(format!("{value:<width$}", width = LEN))
+            .expect("padded to the exact length"),
+    )
+}
@@ -0,0 +1,154 @@
+use crate::{
+    abi::{
+        BridgeFungibleTokenContract, TokenEntry, TokenRegistryContract,
+        TokenRegistryContractConfigurables,
+    },
+    client::{BridgeClient, BridgeConfig},
+    token_config::TokenConfig,
+};
+
+use std::path::Path;
+
+use fuel_core_types::fuel_tx::Bytes32;
+use fuels::{
+    accounts::wallet::WalletUnlocked,
+    prelude::{
+        Address, AssetId, Bech32ContractId, Contract, ContractId, Error, LoadConfiguration, Result,
+        TxParameters,
+    },
+    programs::call_response::FuelCallResponse,
+    types::Bits256,
+};
+
+/// Client for the registry mapping base layer tokens to their bridge contracts
+pub struct TokenRegistryClient {
+    contract: TokenRegistryContract<WalletUnlocked>,
+    wallet: WalletUnlocked,
+}
+
+impl TokenRegistryClient {
+    /// Connect to an already deployed registry contract
+    pub fn new(contract_id: impl Into<Bech32ContractId>, wallet: WalletUnlocked) -> Self {
+        Self {
+            contract: TokenRegistryContract::new(contract_id.into(), wallet.clone()),
+            wallet,
+        }
+    }
+
+    /// Deploy the given registry contract binary, only `owner` can approve and register bridge contracts
+    pub async fn deploy(
+        wallet: WalletUnlocked,
+        binary: impl AsRef<Path>,
+        owner: Address,
+        tx_params: TxParameters,
+    ) -> Result<Self> {
+        let configurables = TokenRegistryContractConfigurables::new().set_OWNER(Bits256(*owner));
+        let contract_id = Contract::load_from(
+            binary,
+            LoadConfiguration::default().set_configurables(configurables),
+        )?
+        .deploy(&wallet, tx_params)
+        .await?;
+
+        Ok(Self::new(contract_id, wallet))
+    }
+
+    pub fn contract(&self) -> &TokenRegistryContract<WalletUnlocked> {
+        &self.contract
+    }
+
+    pub fn contract_id(&self) -> ContractId {
+        self.contract.contract_id().into()
+    }
+
+    /// Approve the given bridge binary built with `config` for the token of `config`,
+    /// only a bridge contract with exactly that bytecode can then be registered for the token
+    pub async fn approve(
+        &self,
+        approved_binary: impl AsRef<Path>,
+        config: &TokenConfig,
+        tx_params: TxParameters,
+    ) -> Result<FuelCallResponse<()>> {
+        let configurables = config
+            .configurables()
+            .map_err(|error| Error::InvalidData(error.to_string()))?;
+        let root = BridgeClient::precompute_bytecode_root(approved_binary, Some(configurables))?;
+        self.contract
+            .methods()
+            .approve_bytecode_root(config.token, Bits256(*root))
+            .tx_params(tx_params)
+            .call()
+            .await
+    }
+
+    /// Compute the bytecode root the approved bridge binary has when built with the
+    /// configuration the given bridge contract was deployed with
+    pub async fn expected_bytecode_root(
+        &self,
+        approved_binary: impl AsRef<Path>,
+        bridge: ContractId,
+    ) -> Result<Bytes32> {
+        let contract = BridgeFungibleTokenContract::new(bridge.into(), self.wallet.clone());
+        let config = BridgeConfig::read(&contract).await?;
+        let configurables = TokenConfig::from(&config)
+            .configurables()
+            .map_err(|error| Error::InvalidData(error.to_string()))?;
+
+        BridgeClient::precompute_bytecode_root(approved_binary, Some(configurables))
+    }
+
+    /// Register a bridge contract, the registry checks it against the root approved for its token
+    pub async fn register(
+        &self,
+        bridge: ContractId,
+        tx_params: TxParameters,
+    ) -> Result<FuelCallResponse<()>> {
+        self.contract
+            .methods()
+            .register(bridge)
+            .set_contract_ids(&[bridge.into()])
+            .tx_params(tx_params)
+            .call()
+            .await
+    }
+
+    /// Get the bytecode root approved for a base layer token
+    pub async fn approved_bytecode_root(&self, token: Bits256) -> Result<Option<Bytes32>> {
+        Ok(self
+            .contract
+            .methods()
+            .approved_bytecode_root(token)
+            .simulate()
+            .await?
+            .value
+            .map(|root| Bytes32::new(root.0)))
+    }
+
+    /// Look up the bridge contract of a base layer token
+    pub async fn by_token(&self, token: Bits256) -> Result<Option<TokenEntry>> {
+        Ok(self
+            .contract
+            .methods()
+            .entry_for_token(token)
+            .simulate()
+            .await?
+            .value)
+    }
+
+    /// Look up the base layer token of a bridge contract
+    pub async fn by_bridge(&self, bridge: ContractId) -> Result<Option<TokenEntry>> {
+        Ok(self
+            .contract
+            .methods()
+            .entry_for_bridge(bridge)
+            .simulate()
+            .await?
+            .value)
+    }
+
+    /// Look up the base layer token of a proxy token
+    pub async fn by_asset(&self, asset_id: AssetId) -> Result<Option<TokenEntry>> {
+        // the proxy token of a bridge contract has the id of the contract
+        self.by_bridge(ContractId::new(*asset_id)).await
+    }
+}
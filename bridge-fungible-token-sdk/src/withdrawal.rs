@@ -0,0 +1,166 @@
+use fuel_core_types::{
+    fuel_crypto::Hasher,
+    fuel_tx::Receipt,
+    fuel_types::{Address, Bytes32, ContractId, MessageId, Nonce, Word},
+};
+use fuels::types::Bits256;
+use primitive_types::U256 as Unsigned256;
+
+/// The function selector for finalizeWithdrawal on the base layer gateway contract:
+/// finalizeWithdrawal(address,address,uint256) = 0x53ef1461
+pub const FINALIZE_WITHDRAWAL_SELECTOR: [u8; 4] = [0x53, 0xef, 0x14, 0x61];
+
+/// The function selector for minting the wrapped token of a locked Fuel-native asset:
+/// mintWrapped(address,bytes32,uint256) = 0x880c9442
+pub const MINT_WRAPPED_SELECTOR: [u8; 4] = [0x88, 0x0c, 0x94, 0x42];
+
+/// Length of the data of a withdrawal message: 4 + 32 + 32 + 32 = 100
+pub const WITHDRAWAL_DATA_LEN: usize = 100;
+
+/// Length of the data of a withdrawal in the `MessageOut` receipt. The contract encodes
+/// 100 bytes, the receipts of `withdraw` and `claim_refund` carry them followed by 4 zero
+/// bytes, as asserted on a local node by the withdrawal tests in `bridge-fungible-token`.
+const PADDED_WITHDRAWAL_DATA_LEN: usize = 104;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum WithdrawalMessageError {
+    #[error("expected a MessageOut receipt")]
+    NotAMessageOut,
+    #[error("expected {WITHDRAWAL_DATA_LEN} bytes of message data, got {0}")]
+    InvalidLength(usize),
+    #[error("message data is padded with non zero bytes")]
+    InvalidPadding,
+    #[error("unexpected selector 0x{}", hex(.0))]
+    InvalidSelector([u8; 4]),
+    #[error("message was sent by {actual}, expected the bridge contract {expected}")]
+    UnexpectedSender {
+        expected: ContractId,
+        actual: ContractId,
+    },
+    #[error("message was sent to {actual}, expected the gateway {expected}")]
+    UnexpectedRecipient { expected: Address, actual: Address },
+}
+
+/// A withdrawal sent by the bridge contract to the base layer gateway, decoded from its `MessageOut` receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalMessage {
+    /// The bridge contract that sent the message
+    pub sender: ContractId,
+    /// The base layer gateway receiving the message
+    pub recipient: Address,
+    /// The base asset amount attached to the message
+    pub amount: Word,
+    pub nonce: Nonce,
+    /// The base layer address the tokens are released to
+    pub to: Bits256,
+    /// The base layer token being released
+    pub token: Bits256,
+    /// The amount released, in base layer decimals
+    pub token_amount: Unsigned256,
+    /// The raw message data, including any padding
+    pub data: Vec<u8>,
+}
+
+impl WithdrawalMessage {
+    /// Check the message was sent by the given bridge contract to the given gateway
+    pub fn ensure_route(
+        self,
+        bridge_contract: ContractId,
+        gateway: Address,
+    ) -> Result<Self, WithdrawalMessageError> {
+        if self.sender != bridge_contract {
+            return Err(WithdrawalMessageError::UnexpectedSender {
+                expected: bridge_contract,
+                actual: self.sender,
+            });
+        }
+        if self.recipient != gateway {
+            return Err(WithdrawalMessageError::UnexpectedRecipient {
+                expected: gateway,
+                actual: self.recipient,
+            });
+        }
+        Ok(self)
+    }
+
+    /// Decode a message calling the gateway function `selector` with the arguments of a
+    /// withdrawal, as also sent for locks with [`MINT_WRAPPED_SELECTOR`]
+    pub fn decode(receipt: &Receipt, selector: [u8; 4]) -> Result<Self, WithdrawalMessageError> {
+        let Receipt::MessageOut {
+            sender,
+            recipient,
+            amount,
+            nonce,
+            data,
+            ..
+        } = receipt
+        else {
+            return Err(WithdrawalMessageError::NotAMessageOut);
+        };
+
+        if data.len() != WITHDRAWAL_DATA_LEN && data.len() != PADDED_WITHDRAWAL_DATA_LEN {
+            return Err(WithdrawalMessageError::InvalidLength(data.len()));
+        }
+        if data[WITHDRAWAL_DATA_LEN..].iter().any(|byte| *byte != 0) {
+            return Err(WithdrawalMessageError::InvalidPadding);
+        }
+
+        let actual: [u8; 4] = data[0..4].try_into().unwrap();
+        if actual != selector {
+            return Err(WithdrawalMessageError::InvalidSelector(actual));
+        }
+
+        Ok(Self {
+            sender: ContractId::new(**sender),
+            recipient: *recipient,
+            amount: *amount,
+            nonce: *nonce,
+            to: Bits256(data[4..36].try_into().unwrap()),
+            token: Bits256(data[36..68].try_into().unwrap()),
+            token_amount: Unsigned256::from_big_endian(&data[68..100]),
+            data: data.clone(),
+        })
+    }
+
+    /// The id of the message, as needed by `relayMessage` on the base layer
+    pub fn message_id(&self) -> MessageId {
+        message_id(
+            self.sender.as_ref(),
+            &self.recipient,
+            &self.nonce,
+            self.amount,
+            &self.data,
+        )
+    }
+}
+
+/// The id of an output message
+/// note: message_id = sha256(sender ++ recipient ++ nonce ++ amount ++ data)
+pub fn message_id(
+    sender: &[u8],
+    recipient: &Address,
+    nonce: &Nonce,
+    amount: Word,
+    data: &[u8],
+) -> MessageId {
+    let id: Bytes32 = Hasher::default()
+        .chain(sender)
+        .chain(recipient.as_ref())
+        .chain(nonce.as_ref())
+        .chain(amount.to_be_bytes())
+        .chain(data)
+        .finalize();
+    MessageId::new(*id)
+}
+
+impl TryFrom<&Receipt> for WithdrawalMessage {
+    type Error = WithdrawalMessageError;
+
+    fn try_from(receipt: &Receipt) -> Result<Self, Self::Error> {
+        Self::decode(receipt, FINALIZE_WITHDRAWAL_SELECTOR)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
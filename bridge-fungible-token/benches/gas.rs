@@ -0,0 +1,265 @@
+//! Measures the gas used by the bridge contract for several decimal configurations
+//! and compares it against the checked in baseline.
+//!
+//! Run with `cargo bench --bench gas`. Set `GAS_BASELINE_BLESS=1` to record the
+//! current numbers as the new baseline.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../tests/utils/environment.rs"]
+mod environment;
+
+use std::{collections::BTreeMap, fs};
+
+use env::BridgeFungibleTokenContractConfigurables;
+use environment as env;
+use fuels::{
+    accounts::ViewOnlyAccount,
+    prelude::{ContractId, TxParameters},
+    tx::{Nonce, Receipt},
+    types::Bits256,
+};
+use serde::{Deserialize, Serialize};
+
+const BASELINE: &str = "benches/gas_baseline.json";
+
+const BRIDGED_TOKEN: &str = "0x00000000000000000000000000000000000000000000000000000000deadbeef";
+const FROM: &str = "0x0000000000000000000000008888888888888888888888888888888888888888";
+
+/// (bridged token decimals, proxy token decimals)
+const DECIMAL_CONFIGS: [(u8, u8); 4] = [(18, 9), (9, 9), (6, 9), (27, 9)];
+
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    /// How much more gas than the baseline a measurement may use before failing
+    tolerance_percent: u64,
+    gas: BTreeMap<String, u64>,
+}
+
+fn script_gas(receipts: &[Receipt]) -> u64 {
+    receipts
+        .iter()
+        .find_map(|receipt| match receipt {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .expect("a script result receipt")
+}
+
+/// Measure every contract function with the given decimals
+async fn measure(bridged_token_decimals: u8, decimals: u8) -> BTreeMap<String, u64> {
+    let configurables = BridgeFungibleTokenContractConfigurables::new()
+        .set_BRIDGED_TOKEN_DECIMALS(bridged_token_decimals)
+        .set_DECIMALS(decimals);
+    let config = env::generate_test_config((bridged_token_decimals, decimals));
+    let wallet = env::setup_wallet();
+    let deposit_contract_id = env::precalculate_deposit_id().await;
+
+    // (name, recipient, amount, to contract, extra data)
+    let deposits = [
+        (
+            "process_message_address",
+            *wallet.address().hash(),
+            config.test_amount,
+            false,
+            None,
+        ),
+        (
+            "process_message_contract",
+            *deposit_contract_id,
+            config.test_amount,
+            true,
+            None,
+        ),
+        (
+            "process_message_callback",
+            *deposit_contract_id,
+            config.test_amount,
+            true,
+            Some(vec![1u8; 32]),
+        ),
+        (
+            "process_message_refund",
+            *wallet.address().hash(),
+            config.overflow_2,
+            false,
+            None,
+        ),
+    ];
+    let mut messages = vec![];
+    for (_, to, amount, to_contract, extra_data) in deposits.iter().cloned() {
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            to,
+            amount,
+            Some(configurables.clone()),
+            to_contract,
+            extra_data,
+        )
+        .await;
+        messages.push(message);
+    }
+
+    let test_env = env::BridgeTestEnv::builder()
+        .messages(messages)
+        .configurables(configurables)
+        .build()
+        .await;
+    let wallet = test_env.wallet().clone();
+    let test_contract_id = test_env.contract_id().clone();
+    let provider = test_env.provider().clone();
+    env::get_deposit_recipient_contract_instance(wallet.clone()).await;
+    let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+    let pending = provider
+        .get_messages(&client.relay_bytecode().predicate_root())
+        .await
+        .unwrap();
+
+    let mut gas = BTreeMap::new();
+    for (index, (name, _, _, to_contract, _)) in deposits.iter().enumerate() {
+        let message = pending
+            .iter()
+            .find(|m| m.nonce == Nonce::from(index as u64))
+            .unwrap();
+        let recipient_contract: Option<ContractId> = to_contract.then_some(deposit_contract_id);
+        let receipts = client
+            .relay(
+                message,
+                recipient_contract,
+                &env::get_gas_coins(&wallet).await,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+        gas.insert(name.to_string(), script_gas(&receipts));
+    }
+
+    let from = Bits256::from_hex_str(FROM).unwrap();
+    let minted = config.fuel_equivalent_amount(config.test_amount);
+    let withdraw = client
+        .withdraw(from, minted, TxParameters::new(0, 30_000_000, 0))
+        .await
+        .unwrap();
+    gas.insert("withdraw".to_string(), withdraw.gas_used);
+
+    let claim_refund = client
+        .claim_refund(from, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap())
+        .await
+        .unwrap();
+    gas.insert("claim_refund".to_string(), claim_refund.gas_used);
+
+    let methods = client.contract().methods();
+    let views = [
+        ("name", methods.name().call().await.unwrap().gas_used),
+        ("symbol", methods.symbol().call().await.unwrap().gas_used),
+        (
+            "decimals",
+            methods.decimals().call().await.unwrap().gas_used,
+        ),
+        (
+            "total_supply",
+            methods.total_supply().call().await.unwrap().gas_used,
+        ),
+        (
+            "bridged_token",
+            methods.bridged_token().call().await.unwrap().gas_used,
+        ),
+        (
+            "bridged_token_decimals",
+            methods
+                .bridged_token_decimals()
+                .call()
+                .await
+                .unwrap()
+                .gas_used,
+        ),
+        (
+            "bridged_token_gateway",
+            methods
+                .bridged_token_gateway()
+                .call()
+                .await
+                .unwrap()
+                .gas_used,
+        ),
+    ];
+    for (name, gas_used) in views {
+        gas.insert(name.to_string(), gas_used);
+    }
+
+    gas.into_iter()
+        .map(|(name, gas_used)| {
+            (
+                format!("{bridged_token_decimals}_to_{decimals}/{name}"),
+                gas_used,
+            )
+        })
+        .collect()
+}
+
+#[tokio::main]
+async fn main() {
+    let mut measured = BTreeMap::new();
+    for (bridged_token_decimals, decimals) in DECIMAL_CONFIGS {
+        measured.extend(measure(bridged_token_decimals, decimals).await);
+    }
+
+    let mut baseline: Baseline =
+        serde_json::from_slice(&fs::read(BASELINE).expect("the gas baseline")).unwrap();
+    if std::env::var_os("GAS_BASELINE_BLESS").is_some() {
+        baseline.gas = measured;
+        let mut json = serde_json::to_string_pretty(&baseline).unwrap();
+        json.push('\n');
+        fs::write(BASELINE, json).unwrap();
+        println!("recorded a new gas baseline in {BASELINE}");
+        return;
+    }
+
+    // until a baseline is recorded there is nothing to compare against, once it is every
+    // measurement needs an entry
+    let recorded = !baseline.gas.is_empty();
+    if !recorded {
+        println!("no gas baseline recorded yet, run with GAS_BASELINE_BLESS=1 to record one\n");
+    }
+
+    let mut regressions = vec![];
+    for (name, gas_used) in &measured {
+        match baseline.gas.get(name) {
+            None => {
+                println!("{name:<48} {gas_used:>10}   not in the baseline");
+                if recorded {
+                    regressions.push(format!("{name} used {gas_used} gas and has no baseline"));
+                }
+            }
+            Some(&expected) => {
+                let limit = expected + expected * baseline.tolerance_percent / 100;
+                let change = *gas_used as f64 / expected as f64 * 100.0 - 100.0;
+                println!("{name:<48} {gas_used:>10} {change:>+7.2}%");
+                if *gas_used > limit {
+                    regressions.push(format!(
+                        "{name} used {gas_used} gas, the baseline is {expected} and the limit {limit}"
+                    ));
+                }
+            }
+        }
+    }
+    for name in baseline
+        .gas
+        .keys()
+        .filter(|name| !measured.contains_key(*name))
+    {
+        println!("{name:<48} no longer measured");
+    }
+
+    if !regressions.is_empty() {
+        eprintln!(
+            "\ngas regressions past {}% or missing from the baseline:",
+            baseline.tolerance_percent
+        );
+        for regression in &regressions {
+            eprintln!("  {regression}");
+        }
+        eprintln!("\nif the change is expected, rerun with GAS_BASELINE_BLESS=1");
+        std::process::exit(1);
+    }
+}
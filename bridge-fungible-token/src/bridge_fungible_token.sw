@@ -5,12 +5,26 @@ mod errors;
 mod events;
 mod utils;
 
-use fungible_bridge_abi::FungibleBridge;
+use fungible_bridge_abi::{FungibleBridge, NativeAssetBridge, TokenMetadata};
 use FRC20_abi::FRC20;
 use contract_message_receiver::MessageReceiver;
 use reentrancy::reentrancy_guard;
+use data::{
+    FINALIZE_WITHDRAWAL_SELECTOR,
+    METADATA_MESSAGE,
+    METADATA_MESSAGE_LEN,
+    MINT_WRAPPED_SELECTOR,
+    RELEASE_MESSAGE,
+};
 use errors::BridgeFungibleTokenError;
-use events::{DepositEvent, RefundRegisteredEvent, WithdrawalEvent};
+use events::{
+    DepositEvent,
+    LockEvent,
+    MetadataRegisteredEvent,
+    RefundRegisteredEvent,
+    ReleaseEvent,
+    WithdrawalEvent,
+};
 use std::{
     call_frames::{
         contract_id,
@@ -19,6 +33,7 @@ use std::{
     constants::ZERO_B256,
     context::msg_amount,
     inputs::{
+        input_message_data,
         input_message_data_length,
         input_message_sender,
     },
@@ -37,11 +52,14 @@ use utils::{
     decompose,
     encode_data,
     parse_message_data,
+    parse_metadata_message,
 };
 
 storage {
     refund_amounts: StorageMap<b256, StorageMap<b256, b256>> = StorageMap {},
     tokens_minted: u64 = 0,
+    metadata: Option<TokenMetadata> = Option::None,
+    locked_balances: StorageMap<ContractId, u64> = StorageMap {},
 }
 
 configurable {
@@ -64,7 +82,21 @@ impl MessageReceiver for Contract {
         let input_sender = input_message_sender(msg_idx);
         require(input_sender.value == BRIDGED_TOKEN_GATEWAY, BridgeFungibleTokenError::UnauthorizedSender);
 
-        let message_data = parse_message_data(msg_idx);
+        // the data is read at fixed offsets, anything shorter than a deposit to an address is malformed
+        require(input_message_data_length(msg_idx) >= 160, BridgeFungibleTokenError::MessageDataTooShort);
+
+        // a metadata message has a marker where a deposit has its token
+        let message_type: b256 = input_message_data(msg_idx, 32).into();
+        if (message_type == METADATA_MESSAGE) {
+            register_metadata(msg_idx);
+            return;
+        };
+        if (message_type == RELEASE_MESSAGE) {
+            release(msg_idx);
+            return;
+        };
+
+        let message_data = parse_message_data(msg_idx, 0);
         require(message_data.amount != ZERO_B256, BridgeFungibleTokenError::NoCoinsSent);
 
         // register a refund if tokens don't match
@@ -73,6 +105,12 @@ impl MessageReceiver for Contract {
             return;
         };
 
+        // register a refund if the gateway registered metadata of another token
+        if (!metadata_matches()) {
+            register_refund(message_data.from, message_data.token, message_data.amount);
+            return;
+        };
+
         let res_amount = adjust_deposit_decimals(message_data.amount, DECIMALS, BRIDGED_TOKEN_DECIMALS);
 
         match res_amount {
@@ -128,8 +166,8 @@ impl FungibleBridge for Contract {
         // reset the refund amount to 0
         storage.refund_amounts.get(originator).insert(asset, ZERO_B256);
 
-        // send a message to unlock this amount on the base layer gateway contract
-        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(originator, stored_amount, BRIDGED_TOKEN), 0);
+        // send a message to unlock this amount of the refunded asset on the base layer gateway contract
+        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(FINALIZE_WITHDRAWAL_SELECTOR, originator, stored_amount, asset), 0);
     }
 
     #[payable]
@@ -147,7 +185,7 @@ impl FungibleBridge for Contract {
 
         // send a message to unlock this amount on the base layer gateway contract
         let sender = msg_sender().unwrap();
-        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(to, adjusted_amount, BRIDGED_TOKEN), 0);
+        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(FINALIZE_WITHDRAWAL_SELECTOR, to, adjusted_amount, BRIDGED_TOKEN), 0);
         log(WithdrawalEvent {
             to: to,
             from: sender,
@@ -166,6 +204,49 @@ impl FungibleBridge for Contract {
     fn bridged_token_gateway() -> b256 {
         BRIDGED_TOKEN_GATEWAY
     }
+
+    fn configured_name() -> str[64] {
+        NAME
+    }
+
+    fn configured_symbol() -> str[32] {
+        SYMBOL
+    }
+
+    #[storage(read)]
+    fn bridged_token_metadata() -> Option<TokenMetadata> {
+        storage.metadata.read()
+    }
+}
+
+impl NativeAssetBridge for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn lock(to: b256) {
+        let amount = msg_amount();
+        let asset = msg_asset_id();
+        require(amount != 0, BridgeFungibleTokenError::NoCoinsSent);
+        require(asset != contract_id(), BridgeFungibleTokenError::IncorrectAssetDeposited);
+
+        // locked coins are tracked apart from the proxy token supply
+        let locked = storage.locked_balances.get(asset).try_read().unwrap_or(0);
+        storage.locked_balances.insert(asset, locked + amount);
+
+        // send a message to mint this amount of the wrapped token on the base layer gateway contract
+        let sender = msg_sender().unwrap();
+        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(MINT_WRAPPED_SELECTOR, to, compose((0, 0, 0, amount)), asset.value), 0);
+        log(LockEvent {
+            to,
+            from: sender,
+            asset,
+            amount,
+        });
+    }
+
+    #[storage(read)]
+    fn locked_balance(asset: ContractId) -> u64 {
+        storage.locked_balances.get(asset).try_read().unwrap_or(0)
+    }
 }
 
 impl FRC20 for Contract {
@@ -174,12 +255,20 @@ impl FRC20 for Contract {
         U256::from((0, 0, 0, storage.tokens_minted.read()))
     }
 
+    #[storage(read)]
     fn name() -> str[64] {
-        NAME
+        match storage.metadata.read() {
+            Option::Some(metadata) => metadata.name,
+            Option::None => NAME,
+        }
     }
 
+    #[storage(read)]
     fn symbol() -> str[32] {
-        SYMBOL
+        match storage.metadata.read() {
+            Option::Some(metadata) => metadata.symbol,
+            Option::None => SYMBOL,
+        }
     }
 
     fn decimals() -> u8 {
@@ -188,12 +277,66 @@ impl FRC20 for Contract {
 }
 
 // Storage-dependant private functions
-#[storage(write)]
+#[storage(read, write)]
 fn register_refund(from: b256, asset: b256, amount: b256) {
-    storage.refund_amounts.get(from).insert(asset, amount);
+    // add to any refund of the same asset that has not been claimed yet, so an earlier one is not lost.
+    // note: unclaimed refunds of an asset are held by the gateway, so their sum fits in 256 bits
+    let previous_amount = storage.refund_amounts.get(from).get(asset).try_read().unwrap_or(ZERO_B256);
+    let total = U256::from(decompose(previous_amount)) + U256::from(decompose(amount));
+    storage.refund_amounts.get(from).insert(asset, compose(total.into()));
     log(RefundRegisteredEvent {
         from,
         asset,
         amount,
     });
 }
+
+#[storage(read, write)]
+fn register_metadata(msg_idx: u8) {
+    // the metadata can only be set once, it then replaces the configured name and symbol
+    require(storage.metadata.read().is_none(), BridgeFungibleTokenError::MetadataAlreadyRegistered);
+    require(input_message_data_length(msg_idx) >= METADATA_MESSAGE_LEN, BridgeFungibleTokenError::MessageDataTooShort);
+
+    let metadata = parse_metadata_message(msg_idx);
+    storage.metadata.write(Option::Some(metadata));
+    log(MetadataRegisteredEvent {
+        token: metadata.token,
+        name: metadata.name,
+        symbol: metadata.symbol,
+        decimals: metadata.decimals,
+    });
+}
+
+/// Whether deposits can be minted, which is no longer the case once the gateway
+/// registered metadata that is not the one of the bridged token
+#[storage(read)]
+fn metadata_matches() -> bool {
+    match storage.metadata.read() {
+        Option::Some(metadata) => metadata.token == BRIDGED_TOKEN && metadata.decimals == BRIDGED_TOKEN_DECIMALS,
+        Option::None => true,
+    }
+}
+
+#[storage(read, write)]
+fn release(msg_idx: u8) {
+    // the fields of a deposit follow the marker, the token is the locked asset
+    require(input_message_data_length(msg_idx) >= 32 + 160, BridgeFungibleTokenError::MessageDataTooShort);
+    let message_data = parse_message_data(msg_idx, 32);
+    let asset = ContractId::from(message_data.token);
+
+    // the gateway only burns wrapped tokens minted for a lock, so a release never exceeds what is locked
+    let locked = storage.locked_balances.get(asset).try_read().unwrap_or(0);
+    let amount = U256::from(decompose(message_data.amount));
+    require(amount <= U256::from((0, 0, 0, locked)), BridgeFungibleTokenError::ReleaseExceedsLocked);
+    let amount = amount.as_u64().unwrap();
+    require(amount != 0, BridgeFungibleTokenError::NoCoinsSent);
+
+    storage.locked_balances.insert(asset, locked - amount);
+    transfer(amount, asset, message_data.to);
+    log(ReleaseEvent {
+        to: message_data.to,
+        from: message_data.from,
+        asset,
+        amount,
+    });
+}
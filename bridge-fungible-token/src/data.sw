@@ -1,5 +1,22 @@
 library;
 
+/// Marks a message registering the token metadata where a deposit has its token: keccak256("TOKEN_METADATA")
+pub const METADATA_MESSAGE: b256 = 0xc3472f7400e0735ef73770e2803048ae006732d96934148ab74b8b0c963045fb;
+
+/// Length of the data of a metadata message: 32 + 32 + 32 + 64 + 32 + 1
+pub const METADATA_MESSAGE_LEN: u64 = 193;
+
+/// Marks a message releasing locked Fuel-native coins, followed by the fields of a deposit: keccak256("RELEASE")
+pub const RELEASE_MESSAGE: b256 = 0x4650e4c308846ad3a0c95f18e7dec27b598429f6f567e1373fc9fa8e9c73329a;
+
+/// The function selector for finalizeWithdrawal on the base layer gateway contract:
+/// finalizeWithdrawal(address,address,uint256) = 0x53ef1461
+pub const FINALIZE_WITHDRAWAL_SELECTOR: [u8; 4] = [0x53u8, 0xefu8, 0x14u8, 0x61u8];
+
+/// The function selector for minting the wrapped token of a locked Fuel-native asset on the base layer gateway contract:
+/// mintWrapped(address,bytes32,uint256) = 0x880c9442
+pub const MINT_WRAPPED_SELECTOR: [u8; 4] = [0x88u8, 0x0cu8, 0x94u8, 0x42u8];
+
 pub struct MessageData {
     token: b256,
     from: b256,
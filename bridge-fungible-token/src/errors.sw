@@ -7,4 +7,7 @@ pub enum BridgeFungibleTokenError {
     NoRefundAvailable: (),
     OverflowError: (),
     UnderflowError: (),
+    MessageDataTooShort: (),
+    MetadataAlreadyRegistered: (),
+    ReleaseExceedsLocked: (),
 }
@@ -19,3 +19,24 @@ pub struct WithdrawalEvent {
     from: Identity,
     amount: u64,
 }
+
+pub struct MetadataRegisteredEvent {
+    token: b256,
+    name: str[64],
+    symbol: str[32],
+    decimals: u8,
+}
+
+pub struct LockEvent {
+    to: b256,
+    from: Identity,
+    asset: ContractId,
+    amount: u64,
+}
+
+pub struct ReleaseEvent {
+    to: Identity,
+    from: b256,
+    asset: ContractId,
+    amount: u64,
+}
@@ -3,10 +3,6 @@ library;
 use std::{
     bytes::Bytes,
     constants::ZERO_B256,
-    flags::{
-        disable_panic_on_overflow,
-        enable_panic_on_overflow,
-    },
     inputs::{
         input_message_data,
         input_message_data_length,
@@ -17,14 +13,29 @@ use std::{
 
 use ::errors::BridgeFungibleTokenError;
 use ::data::MessageData;
+use fungible_bridge_abi::TokenMetadata;
+
+/// Build the U256 value of 10 raised to the given exponent.
+/// note: only exponents up to 77 fit in a 256bit number, callers must check this first
+fn pow_10(exp: u64) -> U256 {
+    // 10^19 is the largest power of 10 that fits in a single 64bit word
+    // note: 10_000_000_000_000_000_000 = 10.pow(19)
+    let max_word_power = U256::from((0, 0, 0, 10_000_000_000_000_000_000));
+    let mut result = U256::from((0, 0, 0, 10.pow(exp % 19)));
+    let mut words_to_shift = exp / 19;
+    while (words_to_shift > 0) {
+        result = result * max_word_power;
+        words_to_shift = words_to_shift - 1;
+    }
+    result
+}
 
 fn shift_decimals_left(bn: U256, d: u8) -> Result<U256, BridgeFungibleTokenError> {
-    let mut bn_clone = bn;
-    let mut decimals_to_shift = asm(r1: d) { r1: u64 };
+    let decimals_to_shift = asm(r1: d) { r1: u64 };
 
     // the zero case
     if (decimals_to_shift == 0) {
-        return Result::Ok(bn_clone);
+        return Result::Ok(bn);
     }
 
     // the too large case
@@ -33,54 +44,35 @@ fn shift_decimals_left(bn: U256, d: u8) -> Result<U256, BridgeFungibleTokenError
         return Result::Err(BridgeFungibleTokenError::OverflowError);
     }
 
-    // shift decimals in increments of the max power of 10 that bn_mult will allow (10^19)
-    while (decimals_to_shift > 19) {
-        // note: 10_000_000_000_000_000_000 = 10.pow(19)
-        let (adjusted, overflow) = bn_mult(bn_clone, 10_000_000_000_000_000_000);
-        if (overflow != 0) {
-            return Result::Err(BridgeFungibleTokenError::OverflowError);
-        };
-        decimals_to_shift = decimals_to_shift - 19;
-        bn_clone = adjusted;
-    }
-
-    let (adjusted, overflow) = bn_mult(bn_clone, 10.pow(decimals_to_shift));
-    if (overflow != 0) {
+    // check the product fits in 256 bits before doing a single multiplication
+    let factor = pow_10(decimals_to_shift);
+    if (bn > U256::max() / factor) {
         return Result::Err(BridgeFungibleTokenError::OverflowError);
     }
-    Result::Ok(adjusted)
+    Result::Ok(bn * factor)
 }
 
 fn shift_decimals_right(bn: U256, d: u8) -> Result<U256, BridgeFungibleTokenError> {
-    let mut bn_clone = bn;
-    let mut decimals_to_shift: u32 = asm(r1: d) { r1: u32 };
+    let decimals_to_shift = asm(r1: d) { r1: u64 };
 
     // the zero case
-    if (decimals_to_shift == 0u32) {
-        return Result::Ok(bn_clone);
+    if (decimals_to_shift == 0) {
+        return Result::Ok(bn);
     }
 
     // the too large case
     // (there are only 78 decimal digits in a 256bit number)
-    if (decimals_to_shift > 77u32) {
+    if (decimals_to_shift > 77) {
         return Result::Err(BridgeFungibleTokenError::UnderflowError);
     }
 
-    // shift decimals in increments of the max power of 10 that bn_div will allow (10^9)
-    while (decimals_to_shift > 9u32) {
-        // note: 1_000_000_000 = 10.pow(9)
-        let (adjusted, remainder) = bn_div(bn_clone, 1_000_000_000u32);
-        if remainder != 0u32 {
-            return Result::Err(BridgeFungibleTokenError::UnderflowError);
-        };
-        decimals_to_shift = decimals_to_shift - 9u32;
-        bn_clone = adjusted;
-    }
-    let (adjusted, remainder) = bn_div(bn_clone, 10u32.pow(decimals_to_shift));
-    if remainder != 0u32 {
+    // a single division, any remainder means precision would be lost
+    let factor = pow_10(decimals_to_shift);
+    let adjusted = bn / factor;
+    if (adjusted * factor != bn) {
         return Result::Err(BridgeFungibleTokenError::UnderflowError);
     }
-    return Result::Ok(adjusted)
+    Result::Ok(adjusted)
 }
 
 /// Adjust decimals(precision) on a withdrawal amount to match the originating token decimals
@@ -149,8 +141,9 @@ pub fn decompose(val: b256) -> (u64, u64, u64, u64) {
 }
 
 /// Read the bytes passed as message data into an in-memory representation using the MessageData type.
-pub fn parse_message_data(msg_idx: u8) -> MessageData {
-    let token: b256 = input_message_data(msg_idx, 32).into();
+/// `offset` is the length of the marker preceding the fields, if any, and is not counted in `len`.
+pub fn parse_message_data(msg_idx: u8, offset: u64) -> MessageData {
+    let token: b256 = input_message_data(msg_idx, offset + 32).into();
 
     let mut msg_data = MessageData {
         token,
@@ -162,13 +155,13 @@ pub fn parse_message_data(msg_idx: u8) -> MessageData {
 
     // Parse the message data
     // TODO: Bug, have to mutate this struct for these values or tests fail
-    msg_data.from = input_message_data(msg_idx, 32 + 32).into();
-    msg_data.amount = input_message_data(msg_idx, 32 + 32 + 32 + 32).into();
+    msg_data.from = input_message_data(msg_idx, offset + 32 + 32).into();
+    msg_data.amount = input_message_data(msg_idx, offset + 32 + 32 + 32 + 32).into();
 
     // any data beyond 160 bytes means deposit is meant for a contract.
     // if data is > 161 bytes, then we also need to call process_message on the destination contract.
-    msg_data.len = input_message_data_length(msg_idx);
-    let data = input_message_data(msg_idx, 32 + 32 + 32);
+    msg_data.len = input_message_data_length(msg_idx) - offset;
+    let data = input_message_data(msg_idx, offset + 32 + 32 + 32);
     let to: b256 = data.into();
 
     if msg_data.len > 160u16 {
@@ -180,131 +173,38 @@ pub fn parse_message_data(msg_idx: u8) -> MessageData {
     msg_data
 }
 
-/// Encode the data to be passed out of the contract when sending a message
-pub fn encode_data(to: b256, amount: b256, bridged_token: b256) -> Bytes {
+/// Read the bytes passed as metadata message data into the metadata they register.
+/// note: callers must check the message is at least `METADATA_MESSAGE_LEN` bytes long first
+pub fn parse_metadata_message(msg_idx: u8) -> TokenMetadata {
+    // the name and symbol are read as whole words, then reinterpreted as the string arrays they hold
+    let name: (b256, b256) = (
+        input_message_data(msg_idx, 32 + 32 + 32).into(),
+        input_message_data(msg_idx, 32 + 32 + 32 + 32).into(),
+    );
+    let symbol: b256 = input_message_data(msg_idx, 32 + 32 + 32 + 64).into();
+
+    TokenMetadata {
+        token: input_message_data(msg_idx, 32 + 32).into(),
+        name: asm(r1: __addr_of(name)) { r1: str[64] },
+        symbol: asm(r1: __addr_of(symbol)) { r1: str[32] },
+        decimals: input_message_data(msg_idx, 32 + 32 + 32 + 64 + 32).get(0).unwrap(),
+    }
+}
+
+/// Encode the data to be passed out of the contract when sending a message calling the base layer gateway function `selector`
+pub fn encode_data(selector: [u8; 4], to: b256, amount: b256, token: b256) -> Bytes {
     // capacity is 4 + 32 + 32 + 32 = 100
     let mut data = Bytes::with_capacity(100);
 
     // first, we push the selector 1 byte at a time
-    // the function selector for finalizeWithdrawal on the base layer gateway contract:
-    // finalizeWithdrawal(address,address,uint256) = 0x53ef1461
-    data.push(0x53u8);
-    data.push(0xefu8);
-    data.push(0x14u8);
-    data.push(0x61u8);
+    data.push(selector[0]);
+    data.push(selector[1]);
+    data.push(selector[2]);
+    data.push(selector[3]);
 
     data.append(Bytes::from(to));
-    data.append(Bytes::from(bridged_token));
+    data.append(Bytes::from(token));
     data.append(Bytes::from(amount));
 
     data
 }
-
-// TODO: [std-lib] replace when added as a method to U128/U256
-fn bn_mult(bn: U256, factor: u64) -> (U256, u64) {
-    disable_panic_on_overflow();
-    let result = U256::new();
-    let result = asm(bn: __addr_of(bn), factor: factor, carry_0, carry_1, value, product, sum, result: __addr_of(result)) {
-        // Run multiplication on the lower 64bit word
-        lw   value bn i3; // load the word in (bn + 3 words) into value
-        mul  product value factor; // mult value * factor and save in product
-        move carry_0 of; // record the carry
-        sw   result product i3;
-
-        // Run multiplication on the next 64bit word
-        lw   value bn i2; // load the word in (bn + 2 words) into value
-        mul  product value factor; // mult value * factor and save in product
-        move carry_1 of; // record the carry
-        add  sum product carry_0; // add previous carry + product
-        add  carry_0 carry_1 of; // record the total new carry
-        sw   result sum i2;
-
-        // Run multiplication on the next 64bit word
-        lw   value bn i1; // load the word in (bn + 1 words) into value
-        mul  product value factor; // mult value * factor and save in product
-        move carry_1 of; // record the carry
-        add  sum product carry_0; // add previous carry + product
-        add  carry_0 carry_1 of; // record the total new carry
-        sw   result sum i1;
-
-        // Run multiplication on the next 64bit word
-        lw   value bn i0; // load the word in bn into value
-        mul  product value factor; // mult value * factor and save in product
-        move carry_1 of; // record the carry
-        add  sum product carry_0; // add previous carry + product
-        add  carry_0 carry_1 of; // record the total new carry
-        move carry_1 of; // record any overflow
-        sw   result sum i0;
-        sw   result carry_0 i4;
-
-        result: (U256, u64)
-    };
-    enable_panic_on_overflow();
-    result
-}
-
-// TODO: [std-lib] replace when added as a method to U128/U256
-fn bn_div(bn: U256, d: u32) -> (U256, u32) {
-    let bn_clone = bn;
-    // bit mask to isolate the lower 32 bits of each word
-    let mask: u64 = 0x00000000FFFFFFFF;
-    let result = (U256::new(), 0u32);
-    asm(bn: __addr_of(bn_clone), d: d, m: mask, r0, r1, r2, r3, v0, v1, sum_1, sum_2, q, result: __addr_of(result)) {
-        // The upper 64bits can just be divided normal
-        lw   v0 bn i0;
-        mod  r0 v0 d; // record the remainder
-        div  q v0 d;
-        sw   result q i0;
-
-        // The next 64bits are broken into 2 32bit numbers
-        lw   v0 bn i1;
-        and  v1 v0 m;
-        srli v0 v0 i32;
-        slli r1 r0 i32; // the previous remainder is shifted up and added before next division
-        add  v0 r1 v0;
-        mod  r2 v0 d; // record the remainder
-        div  v0 v0 d;
-        slli r3 r2 i32; // the previous remainder is shifted up and added before next division
-        add  sum_1 r3 v1;
-        mod  r0 sum_1 d; // record the remainder
-        div  q sum_1 d;
-        slli v0 v0 i32; // re-combine the 2 32bit numbers
-        add  sum_2 v0 q;
-        sw   result sum_2 i1;
-
-        // The next 64bits are broken into 2 32bit numbers
-        lw   v0 bn i2;
-        and  v1 v0 m;
-        srli v0 v0 i32;
-        slli r1 r0 i32; // the previous remainder is shifted up and added before next division
-        add  v0 r1 v0;
-        mod  r2 v0 d; // record the remainder
-        div  v0 v0 d;
-        slli r3 r2 i32; // the previous remainder is shifted up and added before next division
-        add  v1 r3 v1;
-        mod  r0 v1 d; // record the remainder
-        div  v1 v1 d;
-        slli v0 v0 i32; // re-combine the 2 32bit numbers
-        add  v0 v0 v1;
-        sw   result v0 i2;
-
-        // The next 64bits are broken into 2 32bit numbers
-        lw   v0 bn i3;
-        and  v1 v0 m;
-        srli v0 v0 i32;
-        slli r1 r0 i32; // the previous remainder is shifted up and added before next division
-        add  v0 r1 v0;
-        mod  r2 v0 d; // record the remainder
-        div  v0 v0 d;
-        slli r3 r2 i32; // the previous remainder is shifted up and added before next division
-        add  v1 r3 v1;
-        mod  r0 v1 d; // record the remainder
-        div  v1 v1 d;
-        slli v0 v0 i32; // re-combine the 2 32bit numbers
-        add  v0 v0 v1;
-        sw   result v0 i3;
-        sw   result r0 i4;
-
-        result: (U256, u32)
-    }
-}
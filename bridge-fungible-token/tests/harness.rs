@@ -1,18 +1,24 @@
 mod utils {
-    pub mod builder;
     pub mod environment;
+    pub mod gateway;
 }
-use crate::env::{BridgeFungibleTokenContractConfigurables, RefundRegisteredEvent};
+use crate::env::{BridgeFungibleTokenContractConfigurables, BridgeTestEnv, RefundRegisteredEvent};
+use bridge_fungible_token_sdk::{
+    abi::BridgeFungibleTokenError, builder, token_config, withdrawal, RevertReason, TokenConfig,
+    TokenConfigError, WithdrawalMessage, WithdrawalMessageError,
+};
 
 use std::str::FromStr;
 use utils::environment as env;
 
 use fuels::{
     accounts::ViewOnlyAccount,
-    prelude::{launch_provider_and_get_wallet, Address, AssetId, CallParameters, TxParameters},
+    prelude::{
+        launch_provider_and_get_wallet, Address, AssetId, CallParameters, ContractId, TxParameters,
+    },
     programs::contract::SettableContract,
     tx::Receipt,
-    types::Bits256,
+    types::{transaction::Transaction, Bits256, Identity},
 };
 
 pub const BRIDGED_TOKEN: &str =
@@ -29,190 +35,109 @@ mod success {
 
     #[tokio::test]
     async fn relay_message_with_predicate_and_script_constraint() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         // generate the test config struct based on the decimals
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.test_amount,
-            configurables.clone(),
-            false,
             None,
-        )
-        .await;
-
-        // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
+            false,
             None,
-            configurables,
-        )
-        .await;
-
-        // Relay the test message to the test contract
-        let _receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
         )
         .await;
 
-        let test_contract_base_asset_balance = provider
-            .get_contract_asset_balance(test_contract.contract_id(), AssetId::default())
-            .await
-            .unwrap();
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
 
-        let balance = wallet
-            .get_asset_balance(&AssetId::new(*test_contract_id.hash()))
-            .await
-            .unwrap();
+        let contract = Identity::ContractId(test_env.contract_id().into());
+        let recipient = Identity::Address(test_env.wallet().address().into());
 
         // Verify the message value was received by the test contract
-        assert_eq!(test_contract_base_asset_balance, 100);
+        assert_eq!(
+            test_env
+                .balance_of_asset(&contract, AssetId::default())
+                .await,
+            100
+        );
         // Check that wallet now has bridged coins
-        assert_eq!(balance, config.fuel_equivalent_amount(config.test_amount));
+        assert_eq!(
+            test_env.balance_of(&recipient).await,
+            config.fuel_equivalent_amount(config.test_amount)
+        );
     }
 
     #[tokio::test]
     async fn depositing_max_amount_ok() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.max_amount,
-            configurables.clone(),
-            false,
             None,
-        )
-        .await;
-
-        // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
+            false,
             None,
-            configurables,
-        )
-        .await;
-
-        // Relay the test message to the test contract
-        let _receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
         )
         .await;
 
-        let test_contract_base_asset_balance = provider
-            .get_contract_asset_balance(test_contract.contract_id(), AssetId::default())
-            .await
-            .unwrap();
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
 
-        let balance = wallet
-            .get_asset_balance(&AssetId::new(*test_contract_id.hash()))
-            .await
-            .unwrap();
+        let contract = Identity::ContractId(test_env.contract_id().into());
+        let recipient = Identity::Address(test_env.wallet().address().into());
 
         // Verify the message value was received by the test contract
-        assert_eq!(test_contract_base_asset_balance, 100);
+        assert_eq!(
+            test_env
+                .balance_of_asset(&contract, AssetId::default())
+                .await,
+            100
+        );
 
         // Check that wallet now has bridged coins
-        assert_eq!(balance, config.fuel_equivalent_amount(config.max_amount));
+        assert_eq!(
+            test_env.balance_of(&recipient).await,
+            config.fuel_equivalent_amount(config.max_amount)
+        );
     }
 
     #[tokio::test]
     async fn claim_refund() {
         // perform a failing deposit first to register a refund & verify it, then claim and verify output message is created as expected
-        let mut wallet = env::setup_wallet();
-
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.overflow_2,
-            configurables.clone(),
-            false,
             None,
-        )
-        .await;
-
-        // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
+            false,
             None,
-            configurables,
         )
         .await;
 
         // Relay the test message to the test contract
-        let receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
-        )
-        .await;
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
+        let refund_registered_event = test_env.last_logs::<RefundRegisteredEvent>();
 
-        let log_decoder = test_contract.log_decoder();
-        let refund_registered_event = log_decoder
-            .decode_logs_with_type::<RefundRegisteredEvent>(&receipts)
-            .unwrap();
+        let contract = Identity::ContractId(test_env.contract_id().into());
+        let recipient = Identity::Address(test_env.wallet().address().into());
 
         // Verify the message value was received by the test contract
-        let test_contract_balance = provider
-            .get_contract_asset_balance(test_contract.contract_id(), AssetId::default())
-            .await
-            .unwrap();
-        let balance = wallet
-            .get_asset_balance(&AssetId::new(*test_contract_id.hash()))
-            .await
-            .unwrap();
-
-        assert_eq!(test_contract_balance, 100);
+        assert_eq!(
+            test_env
+                .balance_of_asset(&contract, AssetId::default())
+                .await,
+            100
+        );
         assert_eq!(
             refund_registered_event[0].amount,
             Bits256(env::encode_hex(config.overflow_2))
@@ -227,7 +152,9 @@ mod success {
         );
 
         // verify that no tokens were minted for message.data.to
-        assert_eq!(balance, 0);
+        assert_eq!(test_env.balance_of(&recipient).await, 0);
+        let test_contract = test_env.contract();
+        let test_contract_id = test_env.contract_id();
         let call_response = test_contract
             .methods()
             .claim_refund(
@@ -255,52 +182,134 @@ mod success {
         );
         assert_eq!(message_receipt.amount().unwrap(), 0);
         assert_eq!(message_receipt.len().unwrap(), 104);
+        // the 100 encoded bytes are followed by the 4 zero bytes the decoder accepts
+        assert_eq!(
+            message_receipt.data().unwrap()[withdrawal::WITHDRAWAL_DATA_LEN..],
+            [0u8; 4]
+        );
 
         // message data
-        let (selector, to, token, amount) =
-            env::parse_output_message_data(message_receipt.data().unwrap());
-        assert_eq!(selector, env::decode_hex("0x53ef1461").to_vec());
-        assert_eq!(to, Bits256::from_hex_str(FROM).unwrap());
-        assert_eq!(token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+        let withdrawal = env::decode_withdrawal_message(message_receipt, &test_contract_id);
+        let amount = withdrawal.token_amount;
+        assert_eq!(withdrawal.to, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(
+            withdrawal.token,
+            Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+        );
         // Compare the value output in the message with the original value sent
         assert_eq!(amount, config.overflow_2);
     }
 
+    #[tokio::test]
+    async fn refunds_of_the_same_asset_accumulate() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let to = *Address::from_str(TO).unwrap();
+
+        // Two deposits of the same originator and token that cannot be minted
+        let mut messages = vec![];
+        for _ in 0..2 {
+            let (message, _, _) = env::construct_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                to,
+                config.overflow_2,
+                None,
+                false,
+                None,
+            )
+            .await;
+            messages.push(message);
+        }
+        let mut test_env = BridgeTestEnv::builder().messages(messages).build().await;
+        test_env.relay(0).await;
+        test_env.relay(1).await;
+
+        // The second refund does not replace the first one, a single claim releases both
+        let call_response = test_env
+            .client()
+            .claim_refund(
+                Bits256::from_hex_str(FROM).unwrap(),
+                Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+            )
+            .await
+            .unwrap();
+        let message_receipt = call_response
+            .receipts
+            .iter()
+            .find(|&r| matches!(r, Receipt::MessageOut { .. }))
+            .unwrap();
+        let withdrawal = env::decode_withdrawal_message(message_receipt, test_env.contract_id());
+        assert_eq!(withdrawal.to, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(withdrawal.token_amount, config.overflow_2 * 2);
+    }
+
+    #[tokio::test]
+    async fn refund_claim_releases_the_refunded_asset() {
+        let wrong_token: &str =
+            "0x1111110000000000000000000000000000000000000000000000000000111111";
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            wrong_token,
+            FROM,
+            *Address::from_str(TO).unwrap(),
+            config.min_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
+
+        // The gateway is asked to release the token that was deposited, not the bridged one
+        let call_response = test_env
+            .client()
+            .claim_refund(
+                Bits256::from_hex_str(FROM).unwrap(),
+                Bits256::from_hex_str(wrong_token).unwrap(),
+            )
+            .await
+            .unwrap();
+        let message_receipt = call_response
+            .receipts
+            .iter()
+            .find(|&r| matches!(r, Receipt::MessageOut { .. }))
+            .unwrap();
+        let withdrawal = env::decode_withdrawal_message(message_receipt, test_env.contract_id());
+        assert_eq!(withdrawal.to, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(
+            withdrawal.token,
+            Bits256::from_hex_str(wrong_token).unwrap()
+        );
+        assert_eq!(withdrawal.token_amount, config.min_amount);
+    }
+
     #[tokio::test]
     async fn withdraw_from_bridge() {
         // perform successful deposit first, verify it, then withdraw and verify balances
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.max_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let _receipts = env::relay_message_to_contract(
@@ -363,13 +372,20 @@ mod success {
         );
         assert_eq!(message_receipt.amount().unwrap(), 0);
         assert_eq!(message_receipt.len().unwrap(), 104);
+        // the 100 encoded bytes are followed by the 4 zero bytes the decoder accepts
+        assert_eq!(
+            message_receipt.data().unwrap()[withdrawal::WITHDRAWAL_DATA_LEN..],
+            [0u8; 4]
+        );
 
         // message data
-        let (selector, to, token, amount) =
-            env::parse_output_message_data(message_receipt.data().unwrap());
-        assert_eq!(selector, env::decode_hex("0x53ef1461").to_vec());
-        assert_eq!(to, Bits256(*wallet.address().hash()));
-        assert_eq!(token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+        let withdrawal = env::decode_withdrawal_message(message_receipt, &test_contract_id);
+        let amount = withdrawal.token_amount;
+        assert_eq!(withdrawal.to, Bits256(*wallet.address().hash()));
+        assert_eq!(
+            withdrawal.token,
+            Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+        );
         assert_eq!(amount, withdrawal_amount);
     }
 
@@ -381,37 +397,28 @@ mod success {
         // compare starting value with ending value, should be identical
 
         // first make a deposit
-        let mut wallet = env::setup_wallet();
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.min_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let _receipts = env::relay_message_to_contract(
@@ -475,13 +482,20 @@ mod success {
         );
         assert_eq!(message_receipt.amount().unwrap(), 0);
         assert_eq!(message_receipt.len().unwrap(), 104);
+        // the 100 encoded bytes are followed by the 4 zero bytes the decoder accepts
+        assert_eq!(
+            message_receipt.data().unwrap()[withdrawal::WITHDRAWAL_DATA_LEN..],
+            [0u8; 4]
+        );
 
         // message data
-        let (selector, to, token, msg_data_amount) =
-            env::parse_output_message_data(message_receipt.data().unwrap());
-        assert_eq!(selector, env::decode_hex("0x53ef1461").to_vec());
-        assert_eq!(to, Bits256(*wallet.address().hash()));
-        assert_eq!(token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+        let withdrawal = env::decode_withdrawal_message(message_receipt, &test_contract_id);
+        let msg_data_amount = withdrawal.token_amount;
+        assert_eq!(withdrawal.to, Bits256(*wallet.address().hash()));
+        assert_eq!(
+            withdrawal.token,
+            Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+        );
 
         // now verify that the initial amount == the final amount
         assert_eq!(msg_data_amount, config.min_amount);
@@ -493,66 +507,35 @@ mod success {
         if BRIDGED_TOKEN_DECIMALS <= PROXY_TOKEN_DECIMALS {
             return;
         }
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
 
         // Create start test message
-        let mut wallet = env::setup_wallet();
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.not_enough,
-            configurables.clone(),
-            false,
             None,
-        )
-        .await;
-
-        // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
+            false,
             None,
-            configurables,
         )
         .await;
 
-        // Relay the test message to the test contract
-        let receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
-        )
-        .await;
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
 
-        let log_decoder = test_contract.log_decoder();
-        let refund_registered_event = log_decoder
-            .decode_logs_with_type::<RefundRegisteredEvent>(&receipts)
-            .unwrap();
+        let refund_registered_event = test_env.last_logs::<RefundRegisteredEvent>();
+        let contract = Identity::ContractId(test_env.contract_id().into());
+        let recipient = Identity::Address(test_env.wallet().address().into());
 
         // Verify the message value was received by the test contract
-        let test_contract_balance = provider
-            .get_contract_asset_balance(test_contract.contract_id(), AssetId::default())
-            .await
-            .unwrap();
-        let balance = wallet
-            .get_asset_balance(&AssetId::new(*test_contract_id.hash()))
-            .await
-            .unwrap();
-
-        assert_eq!(test_contract_balance, 100);
+        assert_eq!(
+            test_env
+                .balance_of_asset(&contract, AssetId::default())
+                .await,
+            100
+        );
         assert_eq!(
             refund_registered_event[0].amount,
             Bits256(env::encode_hex(config.not_enough))
@@ -567,42 +550,33 @@ mod success {
         );
 
         // verify that no tokens were minted for message.data.to
-        assert_eq!(balance, 0);
+        assert_eq!(test_env.balance_of(&recipient).await, 0);
     }
 
     #[tokio::test]
     async fn depositing_amount_too_large_registers_refund() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.overflow_1,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let receipts = env::relay_message_to_contract(
@@ -651,38 +625,29 @@ mod success {
 
     #[tokio::test]
     async fn depositing_amount_too_large_registers_refund_2() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.overflow_2,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let receipts = env::relay_message_to_contract(
@@ -731,38 +696,29 @@ mod success {
 
     #[tokio::test]
     async fn depositing_amount_too_large_registers_refund_3() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.overflow_3,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let receipts = env::relay_message_to_contract(
@@ -885,185 +841,403 @@ mod success {
     }
 
     #[tokio::test]
-    async fn can_deposit_to_contract() {
-        let mut wallet = env::setup_wallet();
-        let deposit_contract_id = env::precalculate_deposit_id().await;
+    async fn client_reads_bridge_config() {
+        let wallet = launch_provider_and_get_wallet().await;
+        // Set up the environment
+        let (contract, _id) = env::get_fungible_token_instance(wallet.clone()).await;
+        let client = env::get_bridge_client(wallet, contract.contract_id().clone());
 
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
-        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let config = client.config().await.unwrap();
+        assert_eq!(config.name, "MY_TOKEN");
+        assert_eq!(config.symbol, "MYTKN");
+        assert_eq!(config.decimals, PROXY_TOKEN_DECIMALS);
+        assert_eq!(
+            config.bridged_token,
+            Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+        );
+        assert_eq!(config.bridged_token_decimals, BRIDGED_TOKEN_DECIMALS);
+        assert_eq!(
+            config.bridged_token_gateway,
+            Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap()
+        );
+    }
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+    #[tokio::test]
+    async fn client_relays_deposit_and_withdraws() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
-            *deposit_contract_id,
-            config.max_amount,
-            configurables.clone(),
-            true,
+            *wallet.address().hash(),
+            config.test_amount,
+            None,
+            false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (_, contract_inputs, coin_inputs, message_inputs, test_contract_id, provider) =
-            env::setup_environment(
-                &mut wallet,
-                vec![coin],
-                vec![message],
-                deposit_contract,
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+
+        // Find the pending deposit through the predicate root and relay it
+        let messages = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        let receipts = client
+            .relay(
+                &messages[0],
                 None,
-                configurables,
-            )
-            .await;
-
-        let (deposit_contract, _) =
-            env::get_deposit_recipient_contract_instance(wallet.clone()).await;
-
-        // get the balance for the deposit contract before
-        let deposit_contract_balance_before = provider
-            .get_contract_asset_balance(
-                deposit_contract.contract_id(),
-                AssetId::new(*test_contract_id.hash()),
+                &coin_inputs[..],
+                TxParameters::default(),
             )
             .await
             .unwrap();
 
-        // Relay the test message to the test contract
-        let _receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
-        )
-        .await;
-
-        // get the balance for the deposit contract after
-        let deposit_contract_balance_after = provider
-            .get_contract_asset_balance(
-                deposit_contract.contract_id(),
-                AssetId::new(*test_contract_id.hash()),
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+        let events = client.decode_events(&receipts).unwrap();
+        assert_eq!(events.deposits.len(), 1);
+        assert_eq!(events.deposits[0].amount, minted);
+        assert!(events.refunds_registered.is_empty());
+
+        // Withdraw everything that was minted
+        let call_response = client
+            .withdraw(
+                Bits256(*wallet.address().hash()),
+                minted,
+                TxParameters::new(0, 30_000_000, 0),
             )
             .await
             .unwrap();
+        let events = client.decode_events(&call_response.receipts).unwrap();
+        assert_eq!(events.withdrawals.len(), 1);
+        assert_eq!(events.withdrawals[0].amount, minted);
 
-        assert_eq!(
-            deposit_contract_balance_after,
+        let balance = wallet.get_asset_balance(&client.asset_id()).await.unwrap();
+        assert_eq!(balance, 0);
+    }
+
+    #[tokio::test]
+    async fn client_estimates_relay_gas() {
+        use fuel_core_types::fuel_tx::{Chargeable, Script};
+
+        let deposit_contract_id = env::precalculate_deposit_id().await;
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *deposit_contract_id,
+            config.test_amount,
+            None,
+            true,
+            Some(vec![7u8; 64]),
+        )
+        .await;
+
+        let test_env = BridgeTestEnv::builder()
+            .message(message)
+            .deposit_recipient()
+            .build()
+            .await;
+        let client = test_env.client();
+        let messages = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        let gas_coins = env::get_gas_coins(test_env.wallet()).await;
+        let consensus_parameters = test_env
+            .provider()
+            .chain_info()
+            .await
+            .unwrap()
+            .consensus_parameters;
+        // At a gas price of one gas price factor a unit of gas costs one unit of base asset
+        let tx_params =
+            TxParameters::default().set_gas_price(consensus_parameters.gas_price_factor);
+
+        let (tx, estimate) = client
+            .build_estimated_relay_tx(
+                &messages[0],
+                Some(deposit_contract_id),
+                &gas_coins,
+                tx_params,
+            )
+            .await
+            .unwrap();
+        assert!(estimate.gas_used > 0);
+        assert!(estimate.gas_limit > estimate.gas_used);
+        assert!(estimate.gas_limit < consensus_parameters.max_gas_per_tx);
+        assert_eq!(tx.gas_limit(), estimate.gas_limit);
+        // The transaction size is charged on top of the gas limit
+        let metered_bytes = Script::from(tx.clone()).metered_bytes_size() as u64;
+        assert_eq!(
+            estimate.max_fee,
+            estimate.gas_limit + metered_bytes * consensus_parameters.gas_per_byte
+        );
+
+        // The estimated limit covers the deposit callback
+        let receipts = client
+            .relay(
+                &messages[0],
+                Some(deposit_contract_id),
+                &gas_coins,
+                tx_params,
+            )
+            .await
+            .unwrap();
+        let events = client.decode_events(&receipts).unwrap();
+        assert_eq!(events.deposits.len(), 1);
+        assert_eq!(
+            events.deposits[0].amount,
+            config.fuel_equivalent_amount(config.test_amount)
+        );
+    }
+
+    #[tokio::test]
+    async fn can_deploy_with_token_config() {
+        let wallet = launch_provider_and_get_wallet().await;
+        let token_config = TokenConfig {
+            gateway: token_config::parse_address("0x96c53cd98B7297564716a8f2E1de2C83928Af2fe")
+                .unwrap(),
+            token: token_config::parse_address("0x00000000000000000000000000000000deadbeef")
+                .unwrap(),
+            decimals: 9,
+            bridged_token_decimals: 6,
+            name: "USD Coin".to_string(),
+            symbol: "USDC".to_string(),
+        };
+        let configurables = token_config.configurables().unwrap();
+
+        let client = env::deploy_bridge_client(wallet, Some(configurables.clone())).await;
+        assert_eq!(
+            client.contract_id(),
+            env::precalculate_test_contract_id(Some(configurables))
+        );
+
+        let config = client.config().await.unwrap();
+        assert_eq!(config.name, "USD Coin");
+        assert_eq!(config.symbol, "USDC");
+        assert_eq!(config.decimals, 9);
+        assert_eq!(config.bridged_token, token_config.token);
+        assert_eq!(config.bridged_token_decimals, 6);
+        assert_eq!(
+            config.bridged_token_gateway,
+            Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn can_deposit_to_contract() {
+        let deposit_contract_id = env::precalculate_deposit_id().await;
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *deposit_contract_id,
+            config.max_amount,
+            None,
+            true,
+            None,
+        )
+        .await;
+
+        let mut test_env = BridgeTestEnv::builder()
+            .message(message)
+            .deposit_recipient()
+            .build()
+            .await;
+        let recipient = Identity::ContractId(deposit_contract_id);
+
+        // get the balance for the deposit contract before
+        let deposit_contract_balance_before = test_env.balance_of(&recipient).await;
+
+        test_env.relay(0).await;
+
+        // get the balance for the deposit contract after
+        let deposit_contract_balance_after = test_env.balance_of(&recipient).await;
+
+        assert_eq!(
+            deposit_contract_balance_after,
             deposit_contract_balance_before + config.fuel_equivalent_amount(config.max_amount)
         );
     }
 
     #[tokio::test]
     async fn can_deposit_to_contract_with_extra_data() {
-        let mut wallet = env::setup_wallet();
         let deposit_contract_id = env::precalculate_deposit_id().await;
-
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *deposit_contract_id,
             config.max_amount,
-            configurables.clone(),
+            None,
             true,
             Some(vec![11u8, 42u8, 69u8]),
         )
         .await;
 
-        // Set up the environment
-        let (_, contract_inputs, coin_inputs, message_inputs, test_contract_id, provider) =
-            env::setup_environment(
-                &mut wallet,
-                vec![coin],
-                vec![message],
-                deposit_contract,
-                None,
-                configurables,
-            )
+        let mut test_env = BridgeTestEnv::builder()
+            .message(message)
+            .deposit_recipient()
+            .build()
             .await;
-
-        let (deposit_contract, _) =
-            env::get_deposit_recipient_contract_instance(wallet.clone()).await;
+        let recipient = Identity::ContractId(deposit_contract_id);
 
         // get the balance for the deposit contract before
-        let deposit_contract_balance_before = provider
-            .get_contract_asset_balance(
-                deposit_contract.contract_id(),
-                AssetId::new(*test_contract_id.hash()),
-            )
-            .await
-            .unwrap();
+        let deposit_contract_balance_before = test_env.balance_of(&recipient).await;
 
-        // Relay the test message to the test contract
-        let _receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
-        )
-        .await;
+        test_env.relay(0).await;
 
         // get the balance for the deposit contract after
-        let deposit_contract_balance_after = provider
-            .get_contract_asset_balance(
-                deposit_contract.contract_id(),
-                AssetId::new(*test_contract_id.hash()),
-            )
-            .await
-            .unwrap();
+        let deposit_contract_balance_after = test_env.balance_of(&recipient).await;
 
         assert_eq!(
             deposit_contract_balance_after,
             deposit_contract_balance_before + config.fuel_equivalent_amount(config.max_amount)
         );
+
+        // the callback went through to the recipient
+        assert!(
+            test_env
+                .deposit_recipient()
+                .unwrap()
+                .methods()
+                .get_stored_val()
+                .call()
+                .await
+                .unwrap()
+                .value
+        );
     }
 }
 
 mod revert {
     use super::*;
 
+    use bridge_fungible_token_sdk::abi::ReentrancyError;
+    use fuel_core_types::fuel_asm::PanicReason;
+    use fuels::{prelude::Error, tx::Nonce};
+
+    #[tokio::test]
+    async fn relaying_truncated_deposit_reverts() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let ((amount, mut data), coin, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *wallet.address().hash(),
+            config.test_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+        // drop the last byte of the amount
+        data.truncate(159);
+
+        let test_env = BridgeTestEnv::builder()
+            .messages(vec![(amount, data)])
+            .build()
+            .await;
+        let wallet = test_env.wallet().clone();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+        let messages = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+
+        let result = client
+            .relay(
+                &messages[0],
+                None,
+                &coin_inputs[..],
+                TxParameters::default(),
+            )
+            .await;
+
+        assert_eq!(
+            client.decode_revert(&result.unwrap_err()).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::MessageDataTooShort
+            ))
+        );
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+    }
+
+    #[tokio::test]
+    async fn claimed_refund_cannot_be_claimed_again() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *Address::from_str(TO).unwrap(),
+            config.overflow_2,
+            None,
+            false,
+            None,
+        )
+        .await;
+        let mut test_env = BridgeTestEnv::builder().message(message).build().await;
+        test_env.relay(0).await;
+
+        let client = test_env.client();
+        let from = Bits256::from_hex_str(FROM).unwrap();
+        let token = Bits256::from_hex_str(BRIDGED_TOKEN).unwrap();
+        client.claim_refund(from, token).await.unwrap();
+        let result = client.claim_refund(from, token).await;
+
+        assert_eq!(
+            client.decode_revert(&result.unwrap_err()).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::NoRefundAvailable
+            ))
+        );
+    }
+
     #[tokio::test]
-    #[should_panic(expected = "Revert(0)")]
     async fn withdraw_fails_with_too_small_value() {
         // In cases where BRIDGED_TOKEN_DECIMALS == PROXY_TOKEN_DECIMALS or BRIDGED_TOKEN_DECIMALS > PROXY_TOKEN_DECIMALS, this test won't fail because it will attempt to withdraw only 1 coin.
         if BRIDGED_TOKEN_DECIMALS >= PROXY_TOKEN_DECIMALS {
-            panic!("Revert(0)");
+            return;
         }
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
 
         // perform successful deposit first, verify it, then withdraw and verify balances
-        let mut wallet = env::setup_wallet();
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.max_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let _receipts = env::relay_message_to_contract(
@@ -1098,7 +1272,7 @@ mod revert {
             CallParameters::new(withdrawal_amount, AssetId::new(*test_contract_id.hash()), 0);
 
         // The following withdraw should fail since it doesn't meet the minimum withdraw (underflow error)
-        test_contract
+        let error = test_contract
             .methods()
             .withdraw(Bits256(*wallet.address().hash()))
             .tx_params(custom_tx_params)
@@ -1106,21 +1280,24 @@ mod revert {
             .expect("Call param Error")
             .call()
             .await
-            .unwrap();
+            .unwrap_err();
+
+        // the contract unwraps the failed decimal conversion
+        assert_eq!(
+            RevertReason::from_error(&test_contract.log_decoder(), &error).unwrap(),
+            Some(RevertReason::Revert(0))
+        );
     }
 
     #[tokio::test]
-    #[should_panic(expected = "Revert(18446744073709486080)")]
     async fn verification_fails_with_wrong_sender() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *Address::from_str(TO).unwrap(),
             config.min_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
@@ -1129,33 +1306,34 @@ mod revert {
         let bad_sender: &str =
             "0x55555500000000000000000000000000000000000000000000000000005555555";
 
-        // Set up the environment
-        let (
-            _test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            _test_contract_id,
-            _provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            Some(bad_sender),
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder()
+            .message(message)
+            .sender(bad_sender)
+            .build()
+            .await;
+        let client = test_env.client();
+        let messages = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
 
-        // Relay the test message to the test contract
-        let _receipts = env::relay_message_to_contract(
-            &wallet,
-            message_inputs[0].clone(),
-            contract_inputs,
-            &coin_inputs[..],
-            &env::generate_variable_output(),
-        )
-        .await;
+        let error = client
+            .relay(
+                &messages[0],
+                None,
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::UnauthorizedSender
+            ))
+        );
     }
 
     #[tokio::test]
@@ -1163,37 +1341,28 @@ mod revert {
         // In cases where BRIDGED_TOKEN_DECIMALS - PROXY_TOKEN_DECIMALS > 19,
         // there would be arithmetic overflow and possibly tokens lost.
         // We want to catch these cases early and register a refund.
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             BRIDGED_TOKEN,
             FROM,
             *wallet.address().hash(),
             config.test_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let receipts = env::relay_message_to_contract(
@@ -1244,41 +1413,32 @@ mod revert {
 
     #[tokio::test]
     async fn deposit_with_wrong_token_registers_refund() {
-        let mut wallet = env::setup_wallet();
-        let configurables: Option<BridgeFungibleTokenContractConfigurables> = None;
+        let wallet = env::setup_wallet();
         let wrong_token_value: &str =
             "0x1111110000000000000000000000000000000000000000000000000000111111";
 
         let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
 
-        let (message, coin, deposit_contract) = env::construct_msg_data(
+        let (message, _, _) = env::construct_msg_data(
             wrong_token_value,
             FROM,
             *Address::from_str(TO).unwrap(),
             config.min_amount,
-            configurables.clone(),
+            None,
             false,
             None,
         )
         .await;
 
         // Set up the environment
-        let (
-            test_contract,
-            contract_inputs,
-            coin_inputs,
-            message_inputs,
-            test_contract_id,
-            provider,
-        ) = env::setup_environment(
-            &mut wallet,
-            vec![coin],
-            vec![message],
-            deposit_contract,
-            None,
-            configurables,
-        )
-        .await;
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract = test_env.contract().clone();
+        let contract_inputs = test_env.contract_inputs().to_vec();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let message_inputs = test_env.message_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
 
         // Relay the test message to the test contract
         let receipts = env::relay_message_to_contract(
@@ -1326,4 +1486,3412 @@ mod revert {
         // verify that no tokens were minted for message.data.to
         assert_eq!(balance, 0);
     }
+
+    /// A bridge with a deposit to a recipient making the given attack, followed by a deposit to an address
+    async fn attack_env(attack: env::Attack) -> (BridgeTestEnv, ContractId) {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let attacker = env::malicious_recipient(env::precalculate_test_contract_id(None), attack);
+        let attacker_id = attacker.contract_id();
+        let (to_attacker, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *attacker_id,
+            config.test_amount,
+            None,
+            true,
+            Some(vec![1u8; 8]),
+        )
+        .await;
+        let (to_address, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *Address::from_str(TO).unwrap(),
+            config.test_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let test_env = BridgeTestEnv::builder()
+            .messages([to_attacker, to_address])
+            .build()
+            .await;
+        attacker
+            .deploy(test_env.wallet(), TxParameters::default())
+            .await
+            .unwrap();
+
+        (test_env, attacker_id)
+    }
+
+    async fn relay_pending(
+        test_env: &BridgeTestEnv,
+        index: usize,
+        recipient_contract: Option<ContractId>,
+    ) -> Result<Vec<Receipt>, Error> {
+        let client = test_env.client();
+        let message = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|message| message.nonce == Nonce::from(index as u64))
+            .unwrap();
+
+        client
+            .relay(
+                &message,
+                recipient_contract,
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await
+    }
+
+    /// Check the attacked deposit is still pending, nothing was minted, and other deposits still go through
+    async fn assert_failed_safely(test_env: &BridgeTestEnv) {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let client = test_env.client();
+        let pending = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+
+        relay_pending(test_env, 1, None).await.unwrap();
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+        assert_eq!(client.total_supply().await.unwrap(), minted.into());
+        assert_eq!(
+            test_env
+                .balance_of(&Identity::Address(Address::from_str(TO).unwrap()))
+                .await,
+            minted
+        );
+    }
+
+    #[tokio::test]
+    async fn recipient_cannot_reenter_process_message() {
+        let (test_env, attacker_id) = attack_env(env::Attack::Reenter).await;
+
+        let error = relay_pending(&test_env, 0, Some(attacker_id))
+            .await
+            .unwrap_err();
+
+        // the reentrancy guard of the bridge stops the second call
+        let Error::RevertTransactionError { receipts, .. } = &error else {
+            panic!("expected a reverted relay, got {error:?}");
+        };
+        let reentrancy_errors = test_env
+            .client()
+            .contract()
+            .log_decoder()
+            .decode_logs_with_type::<ReentrancyError>(receipts)
+            .unwrap();
+        assert_eq!(reentrancy_errors, vec![ReentrancyError::NonReentrant]);
+        assert_eq!(
+            test_env.client().decode_revert(&error).unwrap(),
+            Some(RevertReason::FailedRequire)
+        );
+        assert_failed_safely(&test_env).await;
+    }
+
+    #[tokio::test]
+    async fn recipient_withdrawing_during_callback_keeps_supply_balanced() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (test_env, attacker_id) = attack_env(env::Attack::Withdraw).await;
+        let client = test_env.client();
+
+        let receipts = relay_pending(&test_env, 0, Some(attacker_id))
+            .await
+            .unwrap();
+
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+        let events = client.decode_events(&receipts).unwrap();
+        assert_eq!(events.deposits.len(), 1);
+        assert_eq!(events.deposits[0].amount, minted);
+        assert_eq!(events.withdrawals.len(), 1);
+        assert_eq!(events.withdrawals[0].amount, minted);
+
+        // everything minted was burnt again and released to the depositor on the base layer
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+        assert_eq!(
+            test_env
+                .balance_of(&Identity::ContractId(attacker_id))
+                .await,
+            0
+        );
+        let message_receipt = receipts
+            .iter()
+            .find(|&r| matches!(r, Receipt::MessageOut { .. }))
+            .unwrap();
+        let withdrawal =
+            env::decode_withdrawal_message(message_receipt, client.contract().contract_id());
+        assert_eq!(withdrawal.to, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(withdrawal.token_amount, config.test_amount);
+    }
+
+    #[tokio::test]
+    async fn recipient_burning_all_gas_leaves_deposit_pending() {
+        let (test_env, attacker_id) = attack_env(env::Attack::BurnGas).await;
+
+        let error = relay_pending(&test_env, 0, Some(attacker_id))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            test_env.client().decode_revert(&error).unwrap(),
+            Some(RevertReason::Panic(PanicReason::OutOfGas))
+        );
+        assert_failed_safely(&test_env).await;
+    }
+
+    #[tokio::test]
+    async fn recipient_reverting_leaves_deposit_pending() {
+        let (test_env, attacker_id) = attack_env(env::Attack::Revert).await;
+
+        let error = relay_pending(&test_env, 0, Some(attacker_id))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            test_env.client().decode_revert(&error).unwrap(),
+            Some(RevertReason::Revert(42))
+        );
+        assert_failed_safely(&test_env).await;
+    }
+
+    fn withdrawal_receipt(data: Vec<u8>) -> Receipt {
+        Receipt::message_out(
+            Address::new([1u8; 32]),
+            Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            0,
+            Default::default(),
+            data,
+        )
+    }
+
+    fn withdrawal_data() -> Vec<u8> {
+        let mut data = withdrawal::FINALIZE_WITHDRAWAL_SELECTOR.to_vec();
+        data.extend_from_slice(&Bits256::from_hex_str(FROM).unwrap().0);
+        data.extend_from_slice(&Bits256::from_hex_str(BRIDGED_TOKEN).unwrap().0);
+        data.extend_from_slice(&env::encode_hex(1_000_000_000u64.into()));
+        data
+    }
+
+    #[test]
+    fn withdrawal_message_decodes_padded_data() {
+        let mut data = withdrawal_data();
+        let message = WithdrawalMessage::try_from(&withdrawal_receipt(data.clone())).unwrap();
+        data.extend_from_slice(&[0u8; 4]);
+        let padded = WithdrawalMessage::try_from(&withdrawal_receipt(data)).unwrap();
+
+        assert_eq!(message.to, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(message.token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+        assert_eq!(message.token_amount, 1_000_000_000u64.into());
+        assert_eq!(message.token_amount, padded.token_amount);
+        // the message id commits to the exact data, including the padding
+        assert_ne!(message.message_id(), padded.message_id());
+    }
+
+    #[test]
+    fn withdrawal_message_rejects_wrong_selector() {
+        let mut data = withdrawal_data();
+        data[0] = 0;
+        let error = WithdrawalMessage::try_from(&withdrawal_receipt(data)).unwrap_err();
+        assert_eq!(
+            error,
+            WithdrawalMessageError::InvalidSelector([0x00, 0xef, 0x14, 0x61])
+        );
+    }
+
+    #[test]
+    fn withdrawal_message_rejects_wrong_length() {
+        let mut data = withdrawal_data();
+        data.push(0);
+        let error = WithdrawalMessage::try_from(&withdrawal_receipt(data)).unwrap_err();
+        assert_eq!(error, WithdrawalMessageError::InvalidLength(101));
+
+        let mut data = withdrawal_data();
+        data.extend_from_slice(&[0u8, 0u8, 0u8, 1u8]);
+        let error = WithdrawalMessage::try_from(&withdrawal_receipt(data)).unwrap_err();
+        assert_eq!(error, WithdrawalMessageError::InvalidPadding);
+    }
+
+    #[test]
+    fn withdrawal_message_rejects_wrong_route() {
+        let message = WithdrawalMessage::try_from(&withdrawal_receipt(withdrawal_data())).unwrap();
+        let gateway = Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap();
+
+        let error = message
+            .clone()
+            .ensure_route(ContractId::new([2u8; 32]), gateway)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            WithdrawalMessageError::UnexpectedSender { .. }
+        ));
+
+        let error = message
+            .ensure_route(ContractId::new([1u8; 32]), Address::new([3u8; 32]))
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            WithdrawalMessageError::UnexpectedRecipient { .. }
+        ));
+    }
+
+    #[test]
+    fn withdrawal_message_rejects_other_receipts() {
+        let receipt = Receipt::ret(ContractId::zeroed(), 0, 0, 0);
+        let error = WithdrawalMessage::try_from(&receipt).unwrap_err();
+        assert_eq!(error, WithdrawalMessageError::NotAMessageOut);
+    }
+
+    fn token_config() -> TokenConfig {
+        TokenConfig {
+            gateway: Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+            decimals: PROXY_TOKEN_DECIMALS,
+            bridged_token_decimals: BRIDGED_TOKEN_DECIMALS,
+            name: "MY_TOKEN".to_string(),
+            symbol: "MYTKN".to_string(),
+        }
+    }
+
+    #[test]
+    fn token_config_pads_name_and_symbol() {
+        let name = token_config::pad::<{ token_config::NAME_LEN }>("name", "MY_TOKEN").unwrap();
+        let symbol = token_config::pad::<{ token_config::SYMBOL_LEN }>("symbol", "MYTKN").unwrap();
+        assert_eq!(
+            name,
+            "MY_TOKEN                                                        "
+        );
+        assert_eq!(symbol, "MYTKN                           ");
+        assert!(token_config().configurables().is_ok());
+    }
+
+    #[test]
+    fn token_config_rejects_invalid_strings() {
+        let mut config = token_config();
+        config.name = "N".repeat(65);
+        assert_eq!(
+            config.validate(),
+            Err(TokenConfigError::TooLong {
+                field: "name",
+                len: 65,
+                max: 64
+            })
+        );
+
+        let mut config = token_config();
+        config.symbol = "TKN€".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(TokenConfigError::NotAscii { field: "symbol" })
+        );
+
+        let mut config = token_config();
+        config.symbol = "   ".to_string();
+        assert_eq!(
+            config.validate(),
+            Err(TokenConfigError::Empty { field: "symbol" })
+        );
+    }
+
+    #[test]
+    fn token_config_rejects_invalid_addresses() {
+        assert!(token_config::parse_address("96c53cd98B7297564716a8f2E1de2C83928Af2fe").is_err());
+        assert!(token_config::parse_address("0x96c53cd98B7297564716a8f2E1de2C83928Af2").is_err());
+        assert!(token_config::parse_address("0xzzc53cd98B7297564716a8f2E1de2C83928Af2fe").is_err());
+        assert_eq!(
+            token_config::parse_address("0x96c53cd98B7297564716a8f2E1de2C83928Af2fe").unwrap(),
+            Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap()
+        );
+    }
+
+    #[test]
+    fn token_config_rejects_unsupported_decimals() {
+        // a single base layer unit would not fit in a u64 of proxy tokens
+        let mut config = token_config();
+        config.decimals = 26;
+        config.bridged_token_decimals = 6;
+        assert!(matches!(
+            config.validate(),
+            Err(TokenConfigError::UnsupportedDecimals { .. })
+        ));
+
+        // the largest withdrawal would not fit in a u256
+        let mut config = token_config();
+        config.decimals = 0;
+        config.bridged_token_decimals = 58;
+        assert!(matches!(
+            config.validate(),
+            Err(TokenConfigError::UnsupportedDecimals { .. })
+        ));
+
+        // the limits themselves are fine
+        config.bridged_token_decimals = 57;
+        assert!(config.validate().is_ok());
+        config.decimals = 19;
+        config.bridged_token_decimals = 0;
+        assert!(config.validate().is_ok());
+    }
+}
+
+mod differential {
+    use super::*;
+
+    use crate::env::DepositEvent;
+    use bridge_fungible_token_sdk::decimals;
+    use primitive_types::U256 as Unsigned256;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // 40 decimal configurations * 50 deposits = 2000 deposits checked against the reference
+    const DECIMAL_CONFIGURATIONS: usize = 40;
+    const DEPOSITS_PER_CONFIGURATION: usize = 50;
+    const WITHDRAWALS_PER_CONFIGURATION: usize = 5;
+    const SEED: u64 = 0x6272_6964_6765;
+
+    fn random_decimals(rng: &mut StdRng) -> (u8, u8) {
+        // mostly realistic token decimals, with the occasional pair far outside the supported range
+        if rng.gen_ratio(1, 8) {
+            (rng.gen_range(0..=100), rng.gen_range(0..=100))
+        } else {
+            (rng.gen_range(0..=30), rng.gen_range(0..=18))
+        }
+    }
+
+    fn random_amount(
+        rng: &mut StdRng,
+        bridged_token_decimals: u8,
+        proxy_token_decimals: u8,
+    ) -> Unsigned256 {
+        let amount = match rng.gen_range(0..3) {
+            // any value with a random bit length
+            0 => {
+                let mut bytes = [0u8; 32];
+                rng.fill(&mut bytes);
+                Unsigned256::from_big_endian(&bytes) >> rng.gen_range(0..256usize)
+            }
+            // a multiple of the decimal adjustment, which usually converts without remainder
+            1 => {
+                let delta = bridged_token_decimals
+                    .abs_diff(proxy_token_decimals)
+                    .min(77);
+                Unsigned256::from(rng.gen::<u64>())
+                    .checked_mul(decimals::pow_10(delta))
+                    .unwrap_or(Unsigned256::MAX)
+            }
+            // a small value
+            _ => Unsigned256::from(rng.gen_range(1..=1_000_000u64)),
+        };
+
+        // depositing zero always reverts, which is covered elsewhere
+        amount.max(Unsigned256::one())
+    }
+
+    #[tokio::test]
+    async fn decimal_conversions_match_reference_implementation() {
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        for _ in 0..DECIMAL_CONFIGURATIONS {
+            let (bridged_token_decimals, proxy_token_decimals) = random_decimals(&mut rng);
+            let configurables = BridgeFungibleTokenContractConfigurables::new()
+                .set_DECIMALS(proxy_token_decimals)
+                .set_BRIDGED_TOKEN_DECIMALS(bridged_token_decimals);
+
+            let wallet = env::setup_wallet();
+            let amounts: Vec<Unsigned256> = (0..DEPOSITS_PER_CONFIGURATION)
+                .map(|_| random_amount(&mut rng, bridged_token_decimals, proxy_token_decimals))
+                .collect();
+
+            let mut messages = Vec::with_capacity(amounts.len());
+            let mut coins = Vec::with_capacity(amounts.len());
+            for amount in &amounts {
+                let (message, coin, _) = env::construct_msg_data(
+                    BRIDGED_TOKEN,
+                    FROM,
+                    *wallet.address().hash(),
+                    *amount,
+                    Some(configurables.clone()),
+                    false,
+                    None,
+                )
+                .await;
+                messages.push(message);
+                coins.push(coin);
+            }
+
+            // Set up the environment, every relay spends a coin of its own
+            let test_env = BridgeTestEnv::builder()
+                .coins(coins)
+                .messages(messages)
+                .configurables(configurables)
+                .build()
+                .await;
+            let wallet = test_env.wallet().clone();
+            let test_contract = test_env.contract().clone();
+            let contract_inputs = test_env.contract_inputs().to_vec();
+            let coin_inputs = test_env.coin_inputs().to_vec();
+            let message_inputs = test_env.message_inputs().to_vec();
+            let test_contract_id = test_env.contract_id().clone();
+            let log_decoder = test_contract.log_decoder();
+
+            // Relay every deposit and compare the outcome with the reference implementation
+            let mut minted: u64 = 0;
+            for (i, amount) in amounts.iter().enumerate() {
+                let context = format!(
+                    "deposit of {amount} with decimals {proxy_token_decimals} and bridged decimals {bridged_token_decimals}"
+                );
+                let expected = decimals::adjust_deposit_decimals(
+                    *amount,
+                    proxy_token_decimals,
+                    bridged_token_decimals,
+                );
+
+                // the supply counter would overflow and revert, which is not a decimal conversion outcome
+                if let Ok(value) = expected {
+                    if minted.checked_add(value).is_none() {
+                        continue;
+                    }
+                }
+
+                let receipts = env::relay_message_to_contract(
+                    &wallet,
+                    message_inputs[i].clone(),
+                    contract_inputs.clone(),
+                    &coin_inputs[i..i + 1],
+                    &env::generate_variable_output(),
+                )
+                .await;
+
+                match expected {
+                    Ok(value) => {
+                        let deposit_event = log_decoder
+                            .decode_logs_with_type::<DepositEvent>(&receipts)
+                            .unwrap();
+                        assert_eq!(deposit_event.len(), 1, "{context}");
+                        assert_eq!(deposit_event[0].amount, value, "{context}");
+                        minted += value;
+                    }
+                    Err(_) => {
+                        // the refund event is all a failed conversion leaves, in either direction
+                        let refund_registered_event = log_decoder
+                            .decode_logs_with_type::<RefundRegisteredEvent>(&receipts)
+                            .unwrap();
+                        assert_eq!(refund_registered_event.len(), 1, "{context}");
+                        assert_eq!(
+                            refund_registered_event[0].amount,
+                            Bits256(env::encode_hex(*amount)),
+                            "{context}"
+                        );
+                    }
+                }
+            }
+
+            let balance = wallet
+                .get_asset_balance(&AssetId::new(*test_contract_id.hash()))
+                .await
+                .unwrap();
+            assert_eq!(balance, minted);
+
+            // Withdraw part of the minted tokens and compare the outgoing message amount
+            let mut remaining = minted;
+            for _ in 0..WITHDRAWALS_PER_CONFIGURATION {
+                if remaining == 0 {
+                    break;
+                }
+                let withdrawal_amount = rng.gen_range(1..=remaining);
+                let context = format!(
+                    "withdrawal of {withdrawal_amount} with decimals {proxy_token_decimals} and bridged decimals {bridged_token_decimals}"
+                );
+                let expected = decimals::adjust_withdrawal_decimals(
+                    withdrawal_amount,
+                    proxy_token_decimals,
+                    bridged_token_decimals,
+                );
+
+                let custom_tx_params = TxParameters::new(0, 30_000_000, 0);
+                let call_params = CallParameters::new(
+                    withdrawal_amount,
+                    AssetId::new(*test_contract_id.hash()),
+                    5000,
+                );
+                let call_response = test_contract
+                    .methods()
+                    .withdraw(Bits256(*wallet.address().hash()))
+                    .tx_params(custom_tx_params)
+                    .call_params(call_params)
+                    .expect("Call param Error")
+                    .call()
+                    .await;
+
+                match expected {
+                    Ok(value) => {
+                        let call_response = call_response.expect(&context);
+                        let message_receipt = call_response
+                            .receipts
+                            .iter()
+                            .find(|&r| matches!(r, Receipt::MessageOut { .. }))
+                            .unwrap();
+                        let withdrawal =
+                            env::decode_withdrawal_message(message_receipt, &test_contract_id);
+                        assert_eq!(withdrawal.token_amount, value, "{context}");
+                        remaining -= withdrawal_amount;
+                    }
+                    Err(_) => assert_eq!(
+                        RevertReason::from_error(
+                            &test_contract.log_decoder(),
+                            &call_response.unwrap_err()
+                        )
+                        .unwrap(),
+                        Some(RevertReason::Revert(0)),
+                        "{context}"
+                    ),
+                }
+            }
+        }
+    }
+}
+
+mod relay_gas {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{builder::RelayGas, BridgeClient};
+    use fuels::{accounts::wallet::WalletUnlocked, test_helpers::DEFAULT_COIN_AMOUNT};
+
+    /// An environment with a deposit to a wallet holding no base asset and a client using it
+    async fn unfunded_relayer(
+        sponsor_coin: bool,
+    ) -> (BridgeTestEnv, WalletUnlocked, BridgeClient, u64) {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *wallet.address().hash(),
+            config.test_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let mut builder = BridgeTestEnv::builder().message(message);
+        if sponsor_coin {
+            builder = builder.coin_for(
+                env::relay_bytecode().predicate_root(),
+                DEFAULT_COIN_AMOUNT,
+                AssetId::default(),
+            );
+        }
+        let test_env = builder.build().await;
+        wallet.set_provider(test_env.provider().clone());
+        let client = env::get_bridge_client(wallet.clone(), test_env.contract_id().clone());
+
+        (
+            test_env,
+            wallet,
+            client,
+            config.fuel_equivalent_amount(config.test_amount),
+        )
+    }
+
+    #[tokio::test]
+    async fn sponsor_predicate_pays_for_relay() {
+        let (test_env, wallet, client, minted) = unfunded_relayer(true).await;
+        let relay = client.relay_bytecode();
+        let sponsor_root = relay.predicate_root();
+        let messages = test_env
+            .provider()
+            .get_messages(&sponsor_root)
+            .await
+            .unwrap();
+
+        let coins = builder::predicate_gas_coins(test_env.provider(), &relay.predicate, &[], 1)
+            .await
+            .unwrap();
+        assert!(!coins.is_empty());
+        let gas = RelayGas::Sponsor {
+            coins,
+            change_to: (&sponsor_root).into(),
+        };
+        let receipts = client
+            .relay(
+                &messages[0],
+                None,
+                gas,
+                TxParameters::default().set_gas_price(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(client.decode_events(&receipts).unwrap().deposits.len(), 1);
+
+        // The relayer paid nothing and the sponsor got its unspent gas back
+        let sponsor_balance = test_env
+            .provider()
+            .get_asset_balance(&sponsor_root, AssetId::default())
+            .await
+            .unwrap();
+        assert!(sponsor_balance < DEFAULT_COIN_AMOUNT);
+        assert!(sponsor_balance > DEFAULT_COIN_AMOUNT / 2);
+        assert_eq!(
+            wallet.get_asset_balance(&AssetId::default()).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            wallet.get_asset_balance(&client.asset_id()).await.unwrap(),
+            minted
+        );
+    }
+
+    #[tokio::test]
+    async fn predicate_root_sponsor_can_be_drained() {
+        let (test_env, _, client, _) = unfunded_relayer(true).await;
+        let relay = client.relay_bytecode();
+        let sponsor_root = relay.predicate_root();
+        let messages = test_env
+            .provider()
+            .get_messages(&sponsor_root)
+            .await
+            .unwrap();
+
+        // Anyone can relay with the contract message predicate's coins and keep the change
+        let thief = WalletUnlocked::new_random(None);
+        let coins = builder::predicate_gas_coins(test_env.provider(), &relay.predicate, &[], 1)
+            .await
+            .unwrap();
+        let gas = RelayGas::Sponsor {
+            coins,
+            change_to: thief.address().into(),
+        };
+        client
+            .relay(
+                &messages[0],
+                None,
+                gas,
+                TxParameters::default().set_gas_price(1),
+            )
+            .await
+            .unwrap();
+
+        let sponsor_balance = test_env
+            .provider()
+            .get_asset_balance(&sponsor_root, AssetId::default())
+            .await
+            .unwrap();
+        let thief_balance = test_env
+            .provider()
+            .get_asset_balance(thief.address(), AssetId::default())
+            .await
+            .unwrap();
+        assert_eq!(sponsor_balance, 0);
+        assert!(thief_balance > DEFAULT_COIN_AMOUNT / 2);
+    }
+
+    #[tokio::test]
+    async fn message_value_pays_for_relay_at_zero_gas_price() {
+        let (test_env, wallet, client, minted) = unfunded_relayer(false).await;
+        let messages = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        let gas = RelayGas::MessageValue {
+            change_to: wallet.address().into(),
+        };
+
+        // The script forwards the whole message value, leaving nothing for a fee
+        let priced = client
+            .build_estimated_relay_tx(
+                &messages[0],
+                None,
+                gas.clone(),
+                TxParameters::default().set_gas_price(1),
+            )
+            .await;
+        assert!(priced.is_err());
+
+        let receipts = client
+            .relay(
+                &messages[0],
+                None,
+                gas,
+                TxParameters::default().set_gas_price(0),
+            )
+            .await
+            .unwrap();
+        assert_eq!(client.decode_events(&receipts).unwrap().deposits.len(), 1);
+        assert_eq!(
+            wallet.get_asset_balance(&AssetId::default()).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            wallet.get_asset_balance(&client.asset_id()).await.unwrap(),
+            minted
+        );
+    }
+
+    #[test]
+    fn gas_change_goes_to_the_chosen_address() {
+        let wallet = env::setup_wallet();
+        let change_to = Address::new([7u8; 32]);
+        let coin = fuels::tx::Input::coin_signed(
+            Default::default(),
+            wallet.address().into(),
+            DEFAULT_COIN_AMOUNT,
+            AssetId::default(),
+            Default::default(),
+            0,
+            Default::default(),
+        );
+        let contract = fuels::tx::Input::contract(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            ContractId::zeroed(),
+        );
+
+        assert_eq!(
+            RelayGas::from(vec![coin.clone()]).change_to().unwrap(),
+            Some(wallet.address().into())
+        );
+        assert_eq!(RelayGas::Coins(vec![]).change_to().unwrap(), None);
+        assert_eq!(
+            RelayGas::Sponsor {
+                coins: vec![coin],
+                change_to,
+            }
+            .change_to()
+            .unwrap(),
+            Some(change_to)
+        );
+        assert_eq!(
+            RelayGas::MessageValue { change_to }.change_to().unwrap(),
+            Some(change_to)
+        );
+        // Inputs that cannot pay for gas are rejected rather than skipped
+        assert!(RelayGas::Coins(vec![contract]).change_to().is_err());
+    }
+}
+
+mod round_trip {
+    use super::*;
+
+    use bridge_fungible_token_sdk::DepositMessage;
+    use fuels::types::U256;
+    use primitive_types::U256 as Unsigned256;
+    use utils::gateway::{GatewayError, MockGateway};
+
+    fn withdrawal_receipt(receipts: &[Receipt]) -> &Receipt {
+        receipts
+            .iter()
+            .find(|r| matches!(r, Receipt::MessageOut { .. }))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn deposit_then_withdraw_releases_tokens_on_base_layer() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let token = Bits256::from_hex_str(BRIDGED_TOKEN).unwrap();
+        let from = Bits256::from_hex_str(FROM).unwrap();
+        let bridge_contract = env::precalculate_test_contract_id(None);
+
+        let mut gateway = MockGateway::new(BRIDGED_TOKEN_GATEWAY);
+        gateway.mint(token, from, config.test_amount);
+        let deposit = DepositMessage::to_address(
+            token,
+            from,
+            Bits256(*wallet.address().hash()),
+            config.test_amount,
+        );
+        let message = gateway.deposit(bridge_contract, &deposit).unwrap();
+        assert_eq!(gateway.balance_of(token, from), Unsigned256::zero());
+        assert_eq!(
+            gateway.custodied(token, bridge_contract),
+            config.test_amount
+        );
+
+        // Relay the deposit on Fuel
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+        let messages = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        client
+            .relay(
+                &messages[0],
+                None,
+                &coin_inputs[..],
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+        let balance = wallet.get_asset_balance(&client.asset_id()).await.unwrap();
+        assert_eq!(balance, minted);
+
+        // Withdraw back to the depositor and finalize on the base layer
+        let call_response = client
+            .withdraw(from, minted, TxParameters::new(0, 30_000_000, 0))
+            .await
+            .unwrap();
+        let receipt = withdrawal_receipt(&call_response.receipts);
+        let withdrawal = gateway.finalize_withdrawal(receipt).unwrap();
+
+        assert_eq!(withdrawal.sender, bridge_contract);
+        assert_eq!(withdrawal.token_amount, config.test_amount);
+        assert_eq!(gateway.balance_of(token, from), config.test_amount);
+        assert_eq!(
+            gateway.custodied(token, bridge_contract),
+            Unsigned256::zero()
+        );
+        assert_eq!(client.total_supply().await.unwrap(), U256::from(0));
+
+        // A message can only be finalized once
+        assert_eq!(
+            gateway.finalize_withdrawal(receipt),
+            Err(GatewayError::AlreadyFinalized(withdrawal.message_id()))
+        );
+    }
+
+    #[tokio::test]
+    async fn refunded_deposit_is_released_on_base_layer() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let token = Bits256::from_hex_str(BRIDGED_TOKEN).unwrap();
+        let from = Bits256::from_hex_str(FROM).unwrap();
+        let bridge_contract = env::precalculate_test_contract_id(None);
+
+        // An amount that does not fit into a u64 on Fuel is refunded
+        let mut gateway = MockGateway::new(BRIDGED_TOKEN_GATEWAY);
+        gateway.mint(token, from, config.overflow_2);
+        let deposit = DepositMessage::to_address(
+            token,
+            from,
+            Bits256(*wallet.address().hash()),
+            config.overflow_2,
+        );
+        let message = gateway.deposit(bridge_contract, &deposit).unwrap();
+
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+        let messages = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        let receipts = client
+            .relay(
+                &messages[0],
+                None,
+                &coin_inputs[..],
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+        let events = client.decode_events(&receipts).unwrap();
+        assert_eq!(events.refunds_registered.len(), 1);
+
+        // Nothing can be released before the refund is claimed
+        assert_eq!(gateway.balance_of(token, from), Unsigned256::zero());
+        assert_eq!(gateway.custodied(token, bridge_contract), config.overflow_2);
+
+        let call_response = client.claim_refund(from, token).await.unwrap();
+        gateway
+            .finalize_withdrawal(withdrawal_receipt(&call_response.receipts))
+            .unwrap();
+
+        assert_eq!(gateway.balance_of(token, from), config.overflow_2);
+        assert_eq!(
+            gateway.custodied(token, bridge_contract),
+            Unsigned256::zero()
+        );
+    }
+
+    #[test]
+    fn cannot_deposit_more_than_balance() {
+        let token = Bits256::from_hex_str(BRIDGED_TOKEN).unwrap();
+        let from = Bits256::from_hex_str(FROM).unwrap();
+        let mut gateway = MockGateway::new(BRIDGED_TOKEN_GATEWAY);
+        gateway.mint(token, from, Unsigned256::from(10));
+
+        let deposit = DepositMessage::to_address(
+            token,
+            from,
+            Bits256::from_hex_str(TO).unwrap(),
+            Unsigned256::from(11),
+        );
+        assert_eq!(
+            gateway.deposit(env::precalculate_test_contract_id(None), &deposit),
+            Err(GatewayError::InsufficientBalance {
+                token,
+                account: from
+            })
+        );
+    }
+}
+
+mod proof {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{proof, BlockMessages};
+    use fuels::tx::{Bytes32, MessageId, Nonce};
+
+    fn block_messages(count: u8) -> BlockMessages {
+        BlockMessages {
+            block_id: Bytes32::zeroed(),
+            height: 1,
+            header_root: Bytes32::zeroed(),
+            message_ids: (0..count).map(|i| MessageId::new([i; 32])).collect(),
+        }
+    }
+
+    #[test]
+    fn proves_every_message_of_a_block() {
+        for count in 1..=7 {
+            let messages = block_messages(count);
+            for id in &messages.message_ids {
+                let proof = messages.prove(id).unwrap();
+                assert_eq!(proof.root, messages.root());
+                assert_eq!(proof.num_leaves, count as u64);
+                assert!(proof.verify());
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_proofs() {
+        let messages = block_messages(5);
+        assert!(messages.prove(&MessageId::new([9; 32])).is_none());
+
+        let proof = messages.prove(&messages.message_ids[2]).unwrap();
+
+        let mut wrong_index = proof.clone();
+        wrong_index.proof_index = 3;
+        assert!(!wrong_index.verify());
+
+        let mut wrong_message = proof.clone();
+        wrong_message.message_id = messages.message_ids[3];
+        assert!(!wrong_message.verify());
+
+        let mut wrong_root = proof;
+        wrong_root.root = block_messages(4).root();
+        assert!(!wrong_root.verify());
+    }
+
+    #[test]
+    fn collects_message_ids_from_receipts() {
+        let receipt = Receipt::message_out(
+            Address::new([1u8; 32]),
+            Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            0,
+            Nonce::default(),
+            vec![1, 2, 3],
+        );
+        let other = Receipt::ret(ContractId::zeroed(), 0, 0, 0);
+
+        let ids = proof::message_ids(&[other, receipt]);
+        assert_eq!(ids.len(), 1);
+        assert_eq!(
+            ids[0],
+            withdrawal::message_id(
+                &[1u8; 32],
+                &Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+                &Nonce::default(),
+                0,
+                &[1, 2, 3],
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn proves_withdrawal_against_block_header() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *wallet.address().hash(),
+            config.test_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        // Set up the environment and relay the deposit
+        let test_env = BridgeTestEnv::builder().message(message).build().await;
+        let wallet = test_env.wallet().clone();
+        let coin_inputs = test_env.coin_inputs().to_vec();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id.clone());
+        let messages = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        client
+            .relay(
+                &messages[0],
+                None,
+                &coin_inputs[..],
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+
+        // Withdraw and prove the message against the block it was included in
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+        let call_response = client
+            .withdraw(
+                Bits256::from_hex_str(FROM).unwrap(),
+                minted,
+                TxParameters::new(0, 30_000_000, 0),
+            )
+            .await
+            .unwrap();
+        let receipt = call_response
+            .receipts
+            .iter()
+            .find(|r| matches!(r, Receipt::MessageOut { .. }))
+            .unwrap();
+        let withdrawal = env::decode_withdrawal_message(receipt, &test_contract_id);
+
+        let height = provider.latest_block_height().await.unwrap();
+        let block_messages = BlockMessages::fetch(&provider, height).await.unwrap();
+        assert!(block_messages.matches_header());
+
+        let proof = block_messages.prove(&withdrawal.message_id()).unwrap();
+        assert_eq!(proof.root, block_messages.header_root);
+        assert!(proof.verify());
+    }
+}
+
+mod state_machine {
+    use super::*;
+
+    use bridge_fungible_token_sdk::BridgeClient;
+    use fuels::{
+        accounts::wallet::WalletUnlocked,
+        prelude::{Bech32ContractId, Provider},
+        test_helpers::DEFAULT_COIN_AMOUNT,
+        tx::Nonce,
+        types::U256,
+    };
+    use primitive_types::U256 as Unsigned256;
+    use proptest::{prelude::*, test_runner::TestCaseError};
+    use std::collections::HashMap;
+
+    const WRONG_TOKEN: &str = "0x000000000000000000000000000000000000000000000000000000000badbeef";
+    const OTHER_FROM: &str = "0x0000000000000000000000009999999999999999999999999999999999999999";
+
+    #[derive(Debug, Clone, Copy)]
+    enum DepositKind {
+        Valid,
+        WrongToken,
+        TooSmall,
+        TooLarge,
+        ToContract,
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Deposit {
+            kind: DepositKind,
+            other_from: bool,
+            units: u64,
+        },
+        /// Withdraw a percentage of the proxy tokens held by the wallet
+        Withdraw {
+            percent: u64,
+        },
+        ClaimRefund {
+            other_from: bool,
+            wrong_token: bool,
+        },
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let kind = prop_oneof![
+            Just(DepositKind::Valid),
+            Just(DepositKind::WrongToken),
+            Just(DepositKind::TooSmall),
+            Just(DepositKind::TooLarge),
+            Just(DepositKind::ToContract),
+        ];
+        prop_oneof![
+            3 => (kind, any::<bool>(), 1..1_000_000u64).prop_map(|(kind, other_from, units)| {
+                Op::Deposit {
+                    kind,
+                    other_from,
+                    units,
+                }
+            }),
+            2 => (1..=100u64).prop_map(|percent| Op::Withdraw { percent }),
+            2 => (any::<bool>(), any::<bool>()).prop_map(|(other_from, wrong_token)| {
+                Op::ClaimRefund {
+                    other_from,
+                    wrong_token,
+                }
+            }),
+        ]
+    }
+
+    fn originator(other_from: bool) -> Bits256 {
+        Bits256::from_hex_str(if other_from { OTHER_FROM } else { FROM }).unwrap()
+    }
+
+    /// Number of base layer units per proxy token unit
+    fn decimal_factor() -> Unsigned256 {
+        Unsigned256::from(10).pow((BRIDGED_TOKEN_DECIMALS - PROXY_TOKEN_DECIMALS).into())
+    }
+
+    /// The message data of a deposit op
+    fn deposit_amount(kind: DepositKind, units: u64, overflow: Unsigned256) -> Unsigned256 {
+        match kind {
+            DepositKind::Valid | DepositKind::WrongToken | DepositKind::ToContract => {
+                Unsigned256::from(units) * decimal_factor()
+            }
+            // leaves a remainder that cannot be represented on Fuel
+            DepositKind::TooSmall => Unsigned256::from(units) % decimal_factor() + 1,
+            DepositKind::TooLarge => overflow + Unsigned256::from(units),
+        }
+    }
+
+    /// What the bridge should look like after every step
+    #[derive(Default)]
+    struct Model {
+        wallet_balance: u64,
+        contract_balance: u64,
+        refunds: HashMap<([u8; 32], [u8; 32]), Unsigned256>,
+    }
+
+    struct Bridge {
+        client: BridgeClient,
+        wallet: WalletUnlocked,
+        provider: Provider,
+        deposit_contract_id: ContractId,
+    }
+
+    impl Bridge {
+        async fn check_invariants(&self, model: &Model) -> Result<(), TestCaseError> {
+            let asset_id = self.client.asset_id();
+            let wallet_balance = self.wallet.get_asset_balance(&asset_id).await.unwrap();
+            let contract_balance = self
+                .provider
+                .get_contract_asset_balance(
+                    &Bech32ContractId::from(self.deposit_contract_id),
+                    asset_id,
+                )
+                .await
+                .unwrap();
+
+            // total supply equals the sum of the holder balances
+            let total_supply = self.client.total_supply().await.unwrap();
+            prop_assert_eq!(
+                total_supply,
+                U256::from(wallet_balance) + U256::from(contract_balance)
+            );
+            prop_assert_eq!(wallet_balance, model.wallet_balance);
+            prop_assert_eq!(contract_balance, model.contract_balance);
+            Ok(())
+        }
+
+        /// Claim a refund and check the released amount is everything registered since the last
+        /// claim, as `refunds_of_the_same_asset_accumulate` checks on its own
+        async fn claim_refund(
+            &self,
+            model: &mut Model,
+            originator: Bits256,
+            asset: Bits256,
+        ) -> Result<(), TestCaseError> {
+            let result = self.client.claim_refund(originator, asset).await;
+            let Some(expected) = model.refunds.remove(&(originator.0, asset.0)) else {
+                prop_assert_eq!(
+                    self.client.decode_revert(&result.unwrap_err()).unwrap(),
+                    Some(RevertReason::Bridge(
+                        BridgeFungibleTokenError::NoRefundAvailable
+                    ))
+                );
+                return Ok(());
+            };
+
+            let receipts = result.unwrap().receipts;
+            let message = message_out(&receipts)?;
+            prop_assert_eq!(message.to, originator);
+            // see `refund_claim_releases_the_refunded_asset`
+            prop_assert_eq!(message.token, asset);
+            prop_assert_eq!(message.token_amount, expected);
+            Ok(())
+        }
+    }
+
+    fn message_out(receipts: &[Receipt]) -> Result<WithdrawalMessage, TestCaseError> {
+        let receipt = receipts
+            .iter()
+            .find(|r| matches!(r, Receipt::MessageOut { .. }));
+        prop_assert!(receipt.is_some());
+        Ok(WithdrawalMessage::try_from(receipt.unwrap()).unwrap())
+    }
+
+    async fn run(ops: Vec<Op>) -> Result<(), TestCaseError> {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let deposit_contract_id = env::precalculate_deposit_id().await;
+
+        // every deposit message has to exist when the node starts
+        let mut messages = vec![];
+        for op in &ops {
+            if let Op::Deposit {
+                kind,
+                other_from,
+                units,
+            } = *op
+            {
+                let token = match kind {
+                    DepositKind::WrongToken => WRONG_TOKEN,
+                    _ => BRIDGED_TOKEN,
+                };
+                let from = if other_from { OTHER_FROM } else { FROM };
+                let to = match kind {
+                    DepositKind::ToContract => *deposit_contract_id,
+                    _ => *wallet.address().hash(),
+                };
+                let (message, _, _) = env::construct_msg_data(
+                    token,
+                    from,
+                    to,
+                    deposit_amount(kind, units, config.overflow_2),
+                    None,
+                    matches!(kind, DepositKind::ToContract),
+                    None,
+                )
+                .await;
+                messages.push(message);
+            }
+        }
+        // every relay spends its own coin, on top of the one for deploying
+        let coins = vec![(DEFAULT_COIN_AMOUNT, AssetId::default()); messages.len()];
+
+        let test_env = BridgeTestEnv::builder()
+            .coins(coins)
+            .messages(messages)
+            .build()
+            .await;
+        let wallet = test_env.wallet().clone();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        env::get_deposit_recipient_contract_instance(wallet.clone()).await;
+        let bridge = Bridge {
+            client: env::get_bridge_client(wallet.clone(), test_contract_id),
+            wallet: wallet.clone(),
+            provider: provider.clone(),
+            deposit_contract_id,
+        };
+        let pending = provider
+            .get_messages(&bridge.client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+
+        let mut model = Model::default();
+        let mut deposit_index = 0;
+        for op in ops {
+            match op {
+                Op::Deposit {
+                    kind,
+                    other_from,
+                    units,
+                } => {
+                    let nonce = Nonce::from(deposit_index as u64);
+                    let message = pending.iter().find(|m| m.nonce == nonce).unwrap();
+                    let recipient_contract = match kind {
+                        DepositKind::ToContract => Some(deposit_contract_id),
+                        _ => None,
+                    };
+                    let receipts = bridge
+                        .client
+                        .relay(
+                            message,
+                            recipient_contract,
+                            &env::get_gas_coins(&wallet).await,
+                            TxParameters::default(),
+                        )
+                        .await
+                        .unwrap();
+                    deposit_index += 1;
+
+                    let events = bridge.client.decode_events(&receipts).unwrap();
+                    let amount = deposit_amount(kind, units, config.overflow_2);
+                    match kind {
+                        DepositKind::Valid | DepositKind::ToContract => {
+                            prop_assert_eq!(events.deposits.len(), 1);
+                            prop_assert_eq!(events.deposits[0].amount, units);
+                            if let DepositKind::Valid = kind {
+                                model.wallet_balance += units;
+                            } else {
+                                model.contract_balance += units;
+                            }
+                        }
+                        DepositKind::WrongToken | DepositKind::TooSmall | DepositKind::TooLarge => {
+                            prop_assert_eq!(events.refunds_registered.len(), 1);
+                            let token = match kind {
+                                DepositKind::WrongToken => WRONG_TOKEN,
+                                _ => BRIDGED_TOKEN,
+                            };
+                            let key = (
+                                originator(other_from).0,
+                                Bits256::from_hex_str(token).unwrap().0,
+                            );
+                            *model.refunds.entry(key).or_default() += amount;
+                        }
+                    }
+                }
+                Op::Withdraw { percent } => {
+                    let amount = model.wallet_balance * percent / 100;
+                    if amount == 0 {
+                        continue;
+                    }
+                    let call_response = bridge
+                        .client
+                        .withdraw(
+                            Bits256::from_hex_str(FROM).unwrap(),
+                            amount,
+                            TxParameters::new(0, 30_000_000, 0),
+                        )
+                        .await
+                        .unwrap();
+                    model.wallet_balance -= amount;
+
+                    // the released amount is the burned amount times the decimal factor
+                    let message = message_out(&call_response.receipts)?;
+                    prop_assert_eq!(
+                        message.token_amount,
+                        Unsigned256::from(amount) * decimal_factor()
+                    );
+                    prop_assert_eq!(message.token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+                }
+                Op::ClaimRefund {
+                    other_from,
+                    wrong_token,
+                } => {
+                    let asset = if wrong_token {
+                        WRONG_TOKEN
+                    } else {
+                        BRIDGED_TOKEN
+                    };
+                    bridge
+                        .claim_refund(
+                            &mut model,
+                            originator(other_from),
+                            Bits256::from_hex_str(asset).unwrap(),
+                        )
+                        .await?;
+                }
+            }
+            bridge.check_invariants(&model).await?;
+        }
+
+        // no refund is ever lost, everything registered can still be claimed
+        let outstanding: Vec<_> = model.refunds.keys().copied().collect();
+        for (originator, asset) in outstanding {
+            bridge
+                .claim_refund(&mut model, Bits256(originator), Bits256(asset))
+                .await?;
+        }
+        bridge.check_invariants(&model).await
+    }
+
+    proptest! {
+        // every case starts a node, so keep the number of cases small
+        #![proptest_config(ProptestConfig {
+            cases: 8,
+            max_shrink_iters: 32,
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn bridge_invariants_hold(ops in prop::collection::vec(op(), 1..12)) {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(run(ops))?;
+        }
+    }
+}
+
+mod fuzz {
+    use super::*;
+
+    use bridge_fungible_token_sdk::decimals;
+    use fuels::{test_helpers::DEFAULT_COIN_AMOUNT, tx::Nonce, types::U256};
+    use primitive_types::U256 as Unsigned256;
+    use proptest::{prelude::*, test_runner::TestCaseError};
+
+    /// Where the body of a deposit puts its fields, after the 32 byte contract id
+    const TOKEN: std::ops::Range<usize> = 0..32;
+    const RECIPIENT: std::ops::Range<usize> = 64..96;
+    const AMOUNT: std::ops::Range<usize> = 96..128;
+
+    /// Who a fuzzed deposit is sent to
+    #[derive(Debug, Clone, Copy)]
+    enum Recipient {
+        Wallet,
+        DepositContract,
+        Random([u8; 32]),
+    }
+
+    fn recipient() -> impl Strategy<Value = Recipient> {
+        prop_oneof![
+            Just(Recipient::Wallet),
+            Just(Recipient::DepositContract),
+            any::<[u8; 32]>().prop_map(Recipient::Random),
+        ]
+    }
+
+    /// The deposit fields, mostly valid so the malformed parts are what gets exercised
+    fn deposit_body() -> impl Strategy<Value = (Vec<u8>, Recipient)> {
+        let token = prop_oneof![
+            4 => Just(Bits256::from_hex_str(BRIDGED_TOKEN).unwrap().0),
+            1 => any::<[u8; 32]>(),
+        ];
+        let amount = prop_oneof![
+            4 => (1..u64::MAX).prop_map(|units| {
+                let mut amount = [0u8; 32];
+                (Unsigned256::from(units) * Unsigned256::from(10).pow(9.into()))
+                    .to_big_endian(&mut amount);
+                amount
+            }),
+            1 => any::<[u8; 32]>(),
+        ];
+        (token, recipient(), amount).prop_map(|(token, recipient, amount)| {
+            let mut body = token.to_vec();
+            body.extend_from_slice(&Bits256::from_hex_str(FROM).unwrap().0);
+            body.extend_from_slice(&[0u8; 32]);
+            body.extend_from_slice(&amount);
+            (body, recipient)
+        })
+    }
+
+    /// Message data bodies that are well formed, truncated, oversized, carry a
+    /// garbage contract marker, or are garbage altogether
+    fn malformed_body() -> impl Strategy<Value = (Vec<u8>, Recipient)> {
+        prop_oneof![
+            deposit_body(),
+            (deposit_body(), 0..128usize).prop_map(|((mut body, recipient), len)| {
+                body.truncate(len);
+                (body, recipient)
+            }),
+            (deposit_body(), any::<u8>()).prop_map(|((mut body, recipient), marker)| {
+                body.push(marker);
+                (body, recipient)
+            }),
+            (deposit_body(), prop::collection::vec(any::<u8>(), 2..256)).prop_map(
+                |((mut body, recipient), extra)| {
+                    body.extend(extra);
+                    (body, recipient)
+                }
+            ),
+            (prop::collection::vec(any::<u8>(), 0..300), recipient()),
+        ]
+    }
+
+    /// Tokens of the given asset that left the bridge contract, per receiver
+    fn transfers(receipts: &[Receipt], asset_id: AssetId) -> Vec<([u8; 32], u64)> {
+        receipts
+            .iter()
+            .filter_map(|receipt| match receipt {
+                Receipt::TransferOut {
+                    to,
+                    amount,
+                    asset_id: id,
+                    ..
+                } if *id == asset_id => Some((**to, *amount)),
+                Receipt::Transfer {
+                    to,
+                    amount,
+                    asset_id: id,
+                    ..
+                }
+                | Receipt::Call {
+                    to,
+                    amount,
+                    asset_id: id,
+                    ..
+                } if *id == asset_id && *amount > 0 => Some((**to, *amount)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    async fn run(bodies: Vec<(Vec<u8>, Recipient)>) -> Result<(), TestCaseError> {
+        let wallet = env::setup_wallet();
+        let bridge_contract = env::precalculate_test_contract_id(None);
+        let deposit_contract_id = env::precalculate_deposit_id().await;
+
+        let bodies: Vec<Vec<u8>> = bodies
+            .into_iter()
+            .map(|(mut body, recipient)| {
+                let to = match recipient {
+                    Recipient::Wallet => *wallet.address().hash(),
+                    Recipient::DepositContract => *deposit_contract_id,
+                    Recipient::Random(to) => to,
+                };
+                if body.len() >= RECIPIENT.end {
+                    body[RECIPIENT].copy_from_slice(&to);
+                }
+                body
+            })
+            .collect();
+        let messages = bodies
+            .iter()
+            .map(|body| (100, [bridge_contract.to_vec(), body.clone()].concat()))
+            .collect();
+        let test_env = BridgeTestEnv::builder().messages(messages).build().await;
+        let wallet = test_env.wallet().clone();
+        let test_contract_id = test_env.contract_id().clone();
+        let provider = test_env.provider().clone();
+        env::get_deposit_recipient_contract_instance(wallet.clone()).await;
+        let client = env::get_bridge_client(wallet.clone(), test_contract_id);
+        let asset_id = client.asset_id();
+        let pending = provider
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+
+        for (index, body) in bodies.iter().enumerate() {
+            let message = pending
+                .iter()
+                .find(|m| m.nonce == Nonce::from(index as u64))
+                .unwrap();
+            let data_len = body.len() + 32;
+            let to = (body.len() >= RECIPIENT.end)
+                .then(|| <[u8; 32]>::try_from(&body[RECIPIENT]).unwrap());
+            let recipient_contract = match to {
+                Some(to) if data_len > 160 && to == *deposit_contract_id => {
+                    Some(deposit_contract_id)
+                }
+                _ => None,
+            };
+
+            let supply_before = client.total_supply().await.unwrap();
+            let result = client
+                .relay(
+                    message,
+                    recipient_contract,
+                    &env::get_gas_coins(&wallet).await,
+                    TxParameters::default(),
+                )
+                .await;
+            let supply_after = client.total_supply().await.unwrap();
+
+            // a rejected or reverted relay changes nothing, but a deposit to an
+            // address always goes through unless there is nothing to deposit
+            let amount = (data_len >= 160).then(|| Unsigned256::from_big_endian(&body[AMOUNT]));
+            let Ok(receipts) = result else {
+                prop_assert_eq!(supply_after, supply_before);
+                prop_assert!(data_len != 160 || amount == Some(Unsigned256::zero()));
+                continue;
+            };
+            prop_assert!(
+                data_len >= 160,
+                "relayed {} bytes of message data",
+                data_len
+            );
+
+            let token = &body[TOKEN];
+            let amount = amount.unwrap();
+            let expected = decimals::adjust_deposit_decimals(
+                amount,
+                PROXY_TOKEN_DECIMALS,
+                BRIDGED_TOKEN_DECIMALS,
+            );
+            let events = client.decode_events(&receipts).unwrap();
+            let transfers = transfers(&receipts, asset_id);
+
+            match expected {
+                Ok(minted) if token == Bits256::from_hex_str(BRIDGED_TOKEN).unwrap().0 => {
+                    // minted exactly once, and only to the recipient named in the data
+                    prop_assert_eq!(events.deposits.len(), 1);
+                    prop_assert_eq!(events.deposits[0].amount, minted);
+                    prop_assert_eq!(supply_after, supply_before + U256::from(minted));
+                    prop_assert_eq!(transfers, vec![(to.unwrap(), minted)]);
+                    prop_assert!(events.refunds_registered.is_empty());
+                }
+                _ => {
+                    // wrong token or an amount that does not convert is refunded in full
+                    prop_assert_eq!(events.refunds_registered.len(), 1);
+                    prop_assert_eq!(
+                        Unsigned256::from_big_endian(&events.refunds_registered[0].amount.0),
+                        amount
+                    );
+                    prop_assert_eq!(supply_after, supply_before);
+                    prop_assert!(transfers.is_empty());
+                    prop_assert!(events.deposits.is_empty());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    proptest! {
+        // every case starts a node, so each case relays a batch of messages
+        #![proptest_config(ProptestConfig {
+            cases: 16,
+            max_shrink_iters: 32,
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn malformed_deposits_never_mint_to_unintended_recipients(
+            bodies in prop::collection::vec(malformed_body(), 1..16)
+        ) {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(run(bodies))?;
+        }
+    }
+}
+
+mod simulate {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{
+        client::BridgeConfig, decimals::DecimalsError, simulate, simulate_deposit, BridgeClient,
+        DepositMessage, DepositOutcome, RefundReason,
+    };
+    use fuels::{tx::Nonce, types::message::Message};
+    use primitive_types::U256 as Unsigned256;
+
+    const WRONG_TOKEN: &str = "0x0000000000000000000000000000000000000000000000000000000000c0ffee";
+
+    async fn message(test_env: &BridgeTestEnv, index: usize) -> Message {
+        test_env
+            .provider()
+            .get_messages(&env::relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|message| message.nonce == Nonce::from(index as u64))
+            .unwrap()
+    }
+
+    /// Simulate the message, relay it and check the prediction against what happened
+    async fn relay_as_predicted(
+        test_env: &BridgeTestEnv,
+        client: &BridgeClient,
+        index: usize,
+        recipient_contract: Option<ContractId>,
+    ) -> DepositOutcome {
+        let message = message(test_env, index).await;
+        let predicted = simulate_deposit(&message, &client.config().await.unwrap());
+
+        let result = client
+            .relay(
+                &message,
+                recipient_contract,
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await;
+
+        match &predicted {
+            DepositOutcome::Mint { to, amount, .. } => {
+                let events = client.decode_events(&result.unwrap()).unwrap();
+                assert_eq!(events.deposits.len(), 1);
+                assert_eq!(&events.deposits[0].to, to);
+                assert_eq!(events.deposits[0].amount, *amount);
+                assert!(events.refunds_registered.is_empty());
+            }
+            DepositOutcome::Refund {
+                from,
+                token,
+                amount,
+                ..
+            } => {
+                let events = client.decode_events(&result.unwrap()).unwrap();
+                assert!(events.deposits.is_empty());
+                assert_eq!(events.refunds_registered.len(), 1);
+                assert_eq!(&events.refunds_registered[0].from, from);
+                assert_eq!(&events.refunds_registered[0].asset, token);
+                assert_eq!(
+                    events.refunds_registered[0].amount,
+                    Bits256(env::encode_hex(*amount))
+                );
+            }
+            DepositOutcome::Release { to, asset, amount } => {
+                let events = client.decode_events(&result.unwrap()).unwrap();
+                assert_eq!(events.releases.len(), 1);
+                assert_eq!(&events.releases[0].to, to);
+                assert_eq!(events.releases[0].asset, ContractId::new(**asset));
+                assert_eq!(events.releases[0].amount, *amount);
+            }
+            DepositOutcome::RegisterMetadata => {
+                let registered = client
+                    .contract()
+                    .log_decoder()
+                    .decode_logs_with_type::<env::MetadataRegisteredEvent>(&result.unwrap())
+                    .unwrap();
+                assert_eq!(registered.len(), 1);
+            }
+            DepositOutcome::Revert(error) => assert_eq!(
+                client.decode_revert(&result.unwrap_err()).unwrap(),
+                Some(RevertReason::Bridge(error.clone()))
+            ),
+        }
+
+        predicted
+    }
+
+    #[tokio::test]
+    async fn predictions_match_execution() {
+        let wallet = env::setup_wallet();
+        let deposit_contract_id = env::precalculate_deposit_id().await;
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let to = *wallet.address().hash();
+
+        let deposit = |token, to, amount, to_contract, extra_data| {
+            env::construct_msg_data(token, FROM, to, amount, None, to_contract, extra_data)
+        };
+        let (to_address, _, _) = deposit(BRIDGED_TOKEN, to, config.test_amount, false, None).await;
+        let (to_contract, _, _) = deposit(
+            BRIDGED_TOKEN,
+            *deposit_contract_id,
+            config.test_amount,
+            true,
+            None,
+        )
+        .await;
+        let (wrong_token, _, _) = deposit(WRONG_TOKEN, to, config.test_amount, false, None).await;
+        let (underflow, _, _) = deposit(BRIDGED_TOKEN, to, config.not_enough, false, None).await;
+        let (overflow, _, _) = deposit(BRIDGED_TOKEN, to, config.overflow_1, false, None).await;
+        let (zero, _, _) = deposit(BRIDGED_TOKEN, to, Unsigned256::zero(), false, None).await;
+        let (mut truncated, _, _) =
+            deposit(BRIDGED_TOKEN, to, config.test_amount, false, None).await;
+        truncated.1.truncate(159);
+
+        let test_env = BridgeTestEnv::builder()
+            .messages([
+                to_address,
+                to_contract,
+                wrong_token,
+                underflow,
+                overflow,
+                zero,
+                truncated,
+            ])
+            .deposit_recipient()
+            .build()
+            .await;
+        let client = test_env.client();
+        let minted = config.fuel_equivalent_amount(config.test_amount);
+
+        assert_eq!(
+            relay_as_predicted(&test_env, client, 0, None).await,
+            DepositOutcome::Mint {
+                to: Identity::Address(wallet.address().into()),
+                amount: minted,
+                callback: false,
+            }
+        );
+        assert_eq!(
+            relay_as_predicted(&test_env, client, 1, Some(deposit_contract_id)).await,
+            DepositOutcome::Mint {
+                to: Identity::ContractId(deposit_contract_id),
+                amount: minted,
+                callback: false,
+            }
+        );
+        assert!(matches!(
+            relay_as_predicted(&test_env, client, 2, None).await,
+            DepositOutcome::Refund {
+                reason: RefundReason::WrongToken,
+                ..
+            }
+        ));
+        if BRIDGED_TOKEN_DECIMALS > PROXY_TOKEN_DECIMALS {
+            assert!(matches!(
+                relay_as_predicted(&test_env, client, 3, None).await,
+                DepositOutcome::Refund {
+                    reason: RefundReason::Decimals(DecimalsError::UnderflowError),
+                    ..
+                }
+            ));
+        }
+        assert!(matches!(
+            relay_as_predicted(&test_env, client, 4, None).await,
+            DepositOutcome::Refund {
+                reason: RefundReason::Decimals(DecimalsError::OverflowError),
+                ..
+            }
+        ));
+        assert!(matches!(
+            relay_as_predicted(&test_env, client, 5, None).await,
+            DepositOutcome::Revert(BridgeFungibleTokenError::NoCoinsSent)
+        ));
+        assert!(matches!(
+            relay_as_predicted(&test_env, client, 6, None).await,
+            DepositOutcome::Revert(BridgeFungibleTokenError::MessageDataTooShort)
+        ));
+    }
+
+    #[tokio::test]
+    async fn predicts_wrong_sender_reverts() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *wallet.address().hash(),
+            config.test_amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+
+        let test_env = BridgeTestEnv::builder()
+            .message(message)
+            .sender("0x0000000000000000000000000000000000000000000000000000000000005555")
+            .build()
+            .await;
+
+        assert!(matches!(
+            relay_as_predicted(&test_env, test_env.client(), 0, None).await,
+            DepositOutcome::Revert(BridgeFungibleTokenError::UnauthorizedSender)
+        ));
+    }
+
+    #[test]
+    fn token_config_gives_the_bridge_config() {
+        let token_config = TokenConfig {
+            gateway: Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+            decimals: PROXY_TOKEN_DECIMALS,
+            bridged_token_decimals: BRIDGED_TOKEN_DECIMALS,
+            name: "MY_TOKEN".to_string(),
+            symbol: "MYTKN".to_string(),
+        };
+        let bridge_config = BridgeConfig::from(&token_config);
+        let sender = Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap();
+        let deposit = DepositMessage::to_address(
+            token_config.token,
+            Bits256::from_hex_str(FROM).unwrap(),
+            Bits256::from_hex_str(TO).unwrap(),
+            Unsigned256::from(10).pow(BRIDGED_TOKEN_DECIMALS.into()),
+        );
+
+        assert_eq!(
+            simulate::simulate_deposit_data(
+                sender,
+                &deposit.encode(ContractId::zeroed()),
+                &bridge_config
+            ),
+            DepositOutcome::Mint {
+                to: Identity::Address(Address::from_str(TO).unwrap()),
+                amount: 10u64.pow(PROXY_TOKEN_DECIMALS.into()),
+                callback: false,
+            }
+        );
+    }
+}
+
+mod deposit_recipients {
+    use super::*;
+
+    use crate::env::{
+        DepositForwarderContract, DepositRecord, DepositRecorderContract, SharesCreditedEvent,
+        StakingVaultContract,
+    };
+    use fuels::{
+        accounts::wallet::WalletUnlocked,
+        prelude::{Bech32ContractId, Contract},
+        types::Bytes,
+    };
+    use primitive_types::U256 as Unsigned256;
+
+    /// Shares the staking vault keeps out of its first deposit
+    const DEAD_SHARES: u64 = 1_000;
+
+    /// The deposit amount minting `units` of the smallest proxy token unit
+    fn proxy_units(config: &env::TestConfig, units: u64) -> Unsigned256 {
+        config.min_amount * Unsigned256::from(units)
+    }
+
+    /// A deposit of `amount` to `recipient` going through the callback path
+    async fn callback_deposit(
+        recipient: ContractId,
+        amount: Unsigned256,
+        extra_data: Vec<u8>,
+    ) -> (u64, Vec<u8>) {
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *recipient,
+            amount,
+            None,
+            true,
+            Some(extra_data),
+        )
+        .await;
+        assert!(message.1.len() > 161);
+        message
+    }
+
+    async fn deploy(contract: Contract, wallet: &WalletUnlocked) -> Bech32ContractId {
+        contract
+            .deploy(wallet, TxParameters::default())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn recorder_records_each_deposit() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let recorder = env::deposit_recorder(env::precalculate_test_contract_id(None));
+        let recorder_id = recorder.contract_id();
+
+        let mut test_env = BridgeTestEnv::builder()
+            .messages([
+                callback_deposit(recorder_id, config.test_amount, vec![1u8, 2u8, 3u8]).await,
+                callback_deposit(recorder_id, config.max_amount, vec![9u8; 40]).await,
+            ])
+            .recipient_contract(recorder_id)
+            .build()
+            .await;
+        let recorder = DepositRecorderContract::new(
+            deploy(recorder, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+
+        test_env.relay(0).await;
+        test_env.relay(1).await;
+
+        let first = config.fuel_equivalent_amount(config.test_amount);
+        let second = config.fuel_equivalent_amount(config.max_amount);
+        let methods = recorder.methods();
+        assert_eq!(methods.deposit_count().call().await.unwrap().value, 2);
+        assert_eq!(
+            methods.get_deposit(0).call().await.unwrap().value,
+            Some(DepositRecord {
+                token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+                from: Bits256::from_hex_str(FROM).unwrap(),
+                amount: first,
+            })
+        );
+        assert_eq!(
+            methods.get_deposit(1).call().await.unwrap().value,
+            Some(DepositRecord {
+                token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+                from: Bits256::from_hex_str(FROM).unwrap(),
+                amount: second,
+            })
+        );
+        assert_eq!(methods.get_deposit(2).call().await.unwrap().value, None);
+        assert_eq!(
+            methods.get_extra_data(0).call().await.unwrap().value,
+            Bytes(vec![1u8, 2u8, 3u8])
+        );
+        assert_eq!(
+            methods.get_extra_data(1).call().await.unwrap().value,
+            Bytes(vec![9u8; 40])
+        );
+        assert_eq!(
+            methods.total_received().call().await.unwrap().value,
+            first + second
+        );
+        assert_eq!(
+            test_env
+                .balance_of(&Identity::ContractId(recorder_id))
+                .await,
+            first + second
+        );
+    }
+
+    #[tokio::test]
+    async fn recorder_rejects_calls_not_from_the_bridge() {
+        let test_env = BridgeTestEnv::builder().build().await;
+        let recorder = env::deposit_recorder(test_env.client().contract_id());
+        let recorder = DepositRecorderContract::new(
+            deploy(recorder, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+
+        let error = recorder
+            .methods()
+            .process_message(0)
+            .call()
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            RevertReason::from_error(&recorder.log_decoder(), &error).unwrap(),
+            Some(RevertReason::FailedRequire)
+        );
+        assert_eq!(
+            recorder
+                .methods()
+                .deposit_count()
+                .call()
+                .await
+                .unwrap()
+                .value,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn staking_vault_credits_shares_on_arrival() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let vault = env::staking_vault(env::precalculate_test_contract_id(None));
+        let vault_id = vault.contract_id();
+        let staker = WalletUnlocked::new_random(None);
+        let other_staker = WalletUnlocked::new_random(None);
+        let first = 5_000_000;
+        let second = 7_000_000;
+
+        let mut test_env = BridgeTestEnv::builder()
+            .messages([
+                callback_deposit(
+                    vault_id,
+                    proxy_units(&config, first),
+                    staker.address().hash().to_vec(),
+                )
+                .await,
+                callback_deposit(
+                    vault_id,
+                    proxy_units(&config, second),
+                    other_staker.address().hash().to_vec(),
+                )
+                .await,
+            ])
+            .recipient_contract(vault_id)
+            .build()
+            .await;
+        let vault = StakingVaultContract::new(
+            deploy(vault, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+
+        let staker_id = Identity::Address(staker.address().into());
+        let other_staker_id = Identity::Address(other_staker.address().into());
+
+        test_env.relay(0).await;
+        let credited = test_env.last_receipts().to_vec();
+        test_env.relay(1).await;
+
+        // the first deposit pays for the dead shares
+        let events = vault
+            .log_decoder()
+            .decode_logs_with_type::<SharesCreditedEvent>(&credited)
+            .unwrap();
+        assert_eq!(
+            events,
+            vec![SharesCreditedEvent {
+                owner: staker_id.clone(),
+                from: Bits256::from_hex_str(FROM).unwrap(),
+                amount: first,
+                shares: first - DEAD_SHARES,
+            }]
+        );
+
+        // nothing was earned between the deposits, so shares stay at one per token
+        let methods = vault.methods();
+        assert_eq!(
+            methods.shares_of(staker_id).call().await.unwrap().value,
+            first - DEAD_SHARES
+        );
+        assert_eq!(
+            methods
+                .shares_of(other_staker_id)
+                .call()
+                .await
+                .unwrap()
+                .value,
+            second
+        );
+        assert_eq!(
+            methods.total_shares().call().await.unwrap().value,
+            first + second
+        );
+        assert_eq!(
+            methods.total_assets().call().await.unwrap().value,
+            first + second
+        );
+        assert_eq!(
+            test_env.balance_of(&Identity::ContractId(vault_id)).await,
+            first + second
+        );
+        assert_eq!(
+            methods.preview_redeem(second).call().await.unwrap().value,
+            second
+        );
+    }
+
+    #[tokio::test]
+    async fn staking_vault_first_depositor_cannot_take_later_deposits() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let vault = env::staking_vault(env::precalculate_test_contract_id(None));
+        let vault_id = vault.contract_id();
+        let attacker = env::setup_wallet();
+        let victim = WalletUnlocked::new_random(None);
+        let victim_id = Identity::Address(victim.address().into());
+        let deposited = 1_000_000;
+        let donated = deposited;
+
+        // The attacker stakes just enough for one share and bridges the tokens to donate
+        let (donation, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *attacker.address().hash(),
+            proxy_units(&config, donated),
+            None,
+            false,
+            None,
+        )
+        .await;
+        let mut test_env = BridgeTestEnv::builder()
+            .wallet(attacker.clone())
+            .messages([
+                callback_deposit(
+                    vault_id,
+                    proxy_units(&config, DEAD_SHARES + 1),
+                    attacker.address().hash().to_vec(),
+                )
+                .await,
+                donation,
+                callback_deposit(
+                    vault_id,
+                    proxy_units(&config, deposited),
+                    victim.address().hash().to_vec(),
+                )
+                .await,
+            ])
+            .recipient_contract(vault_id)
+            .build()
+            .await;
+        let vault = StakingVaultContract::new(
+            deploy(vault, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+        test_env.relay(0).await;
+        test_env.relay(1).await;
+
+        let methods = vault.methods();
+        assert_eq!(
+            methods
+                .shares_of(Identity::Address(attacker.address().into()))
+                .call()
+                .await
+                .unwrap()
+                .value,
+            1
+        );
+        methods
+            .add_rewards()
+            .call_params(CallParameters::new(donated, test_env.asset_id(), 0))
+            .unwrap()
+            .call()
+            .await
+            .unwrap();
+
+        // Without the dead shares the victim's deposit would round down to no shares
+        // and the attacker's single share would be worth both the donation and the deposit
+        test_env.relay(2).await;
+        let victim_shares = methods.shares_of(victim_id).call().await.unwrap().value;
+        assert!(victim_shares > 0);
+        let victim_value = methods
+            .preview_redeem(victim_shares)
+            .call()
+            .await
+            .unwrap()
+            .value;
+        assert!(victim_value > deposited - deposited / 1_000);
+
+        // The donation went almost entirely to the dead shares
+        let attacker_value = methods.preview_redeem(1).call().await.unwrap().value;
+        assert!(attacker_value < donated / 100);
+    }
+
+    #[tokio::test]
+    async fn staking_vault_rejects_deposit_worth_no_shares() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let vault = env::staking_vault(env::precalculate_test_contract_id(None));
+        let vault_id = vault.contract_id();
+        let staker = WalletUnlocked::new_random(None);
+
+        let mut test_env = BridgeTestEnv::builder()
+            .message(
+                callback_deposit(
+                    vault_id,
+                    proxy_units(&config, DEAD_SHARES),
+                    staker.address().hash().to_vec(),
+                )
+                .await,
+            )
+            .recipient_contract(vault_id)
+            .build()
+            .await;
+        let vault = StakingVaultContract::new(
+            deploy(vault, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+
+        // A first deposit covering only the dead shares stays pending
+        let error = test_env.try_relay(0).await.unwrap_err();
+        assert_eq!(
+            test_env.client().decode_revert(&error).unwrap(),
+            Some(RevertReason::FailedRequire)
+        );
+        let pending = test_env
+            .provider()
+            .get_messages(&test_env.client().relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            vault.methods().total_shares().call().await.unwrap().value,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn staker_redeems_shares_for_tokens() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let vault = env::staking_vault(env::precalculate_test_contract_id(None));
+        let vault_id = vault.contract_id();
+        let staker = env::setup_wallet();
+        let staker_id = Identity::Address(staker.address().into());
+
+        let mut test_env = BridgeTestEnv::builder()
+            .wallet(staker.clone())
+            .message(
+                callback_deposit(
+                    vault_id,
+                    config.test_amount,
+                    staker.address().hash().to_vec(),
+                )
+                .await,
+            )
+            .recipient_contract(vault_id)
+            .build()
+            .await;
+        let vault = StakingVaultContract::new(
+            deploy(vault, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+        test_env.relay(0).await;
+
+        let staked = config.fuel_equivalent_amount(config.test_amount);
+        let redeemed = staked / 2;
+        vault
+            .methods()
+            .redeem(redeemed)
+            .append_variable_outputs(1)
+            .call()
+            .await
+            .unwrap();
+
+        let methods = vault.methods();
+        assert_eq!(test_env.balance_of(&staker_id).await, redeemed);
+        assert_eq!(
+            methods.shares_of(staker_id).call().await.unwrap().value,
+            staked - DEAD_SHARES - redeemed
+        );
+        assert_eq!(
+            methods.total_assets().call().await.unwrap().value,
+            staked - redeemed
+        );
+
+        // cannot redeem more shares than are left
+        let error = methods
+            .redeem(staked)
+            .append_variable_outputs(1)
+            .call()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            RevertReason::from_error(&vault.log_decoder(), &error).unwrap(),
+            Some(RevertReason::FailedRequire)
+        );
+    }
+
+    #[tokio::test]
+    async fn forwarder_sends_deposit_to_address_in_extra_data() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let forwarder = env::deposit_forwarder(env::precalculate_test_contract_id(None));
+        let forwarder_id = forwarder.contract_id();
+        let final_address = Address::from_str(TO).unwrap();
+
+        let mut test_env = BridgeTestEnv::builder()
+            .message(
+                callback_deposit(forwarder_id, config.test_amount, final_address.to_vec()).await,
+            )
+            .recipient_contract(forwarder_id)
+            .build()
+            .await;
+        let forwarder = DepositForwarderContract::new(
+            deploy(forwarder, test_env.wallet()).await,
+            test_env.wallet().clone(),
+        );
+        test_env.relay(0).await;
+
+        let forwarded = config.fuel_equivalent_amount(config.test_amount);
+        assert_eq!(
+            test_env.balance_of(&Identity::Address(final_address)).await,
+            forwarded
+        );
+        assert_eq!(
+            test_env
+                .balance_of(&Identity::ContractId(forwarder_id))
+                .await,
+            0
+        );
+        assert_eq!(
+            forwarder
+                .methods()
+                .total_forwarded()
+                .call()
+                .await
+                .unwrap()
+                .value,
+            forwarded
+        );
+    }
+
+    #[tokio::test]
+    async fn forwarder_without_an_address_leaves_the_deposit_unrelayed() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let forwarder = env::deposit_forwarder(env::precalculate_test_contract_id(None));
+        let forwarder_id = forwarder.contract_id();
+
+        let test_env = BridgeTestEnv::builder()
+            .message(callback_deposit(forwarder_id, config.test_amount, vec![1u8; 8]).await)
+            .recipient_contract(forwarder_id)
+            .build()
+            .await;
+        deploy(forwarder, test_env.wallet()).await;
+        let client = test_env.client();
+        let messages = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap();
+
+        let result = client
+            .relay(
+                &messages[0],
+                Some(forwarder_id),
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await;
+
+        // the failed require is in the forwarder, so no bridge error is logged
+        assert_eq!(
+            client.decode_revert(&result.unwrap_err()).unwrap(),
+            Some(RevertReason::FailedRequire)
+        );
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+    }
+}
+
+mod token_registry {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{
+        abi::{TokenEntry, TokenRegistryError},
+        BridgeClient, TokenRegistryClient,
+    };
+    use fuels::prelude::Error;
+
+    fn registry_errors(registry: &TokenRegistryClient, error: &Error) -> Vec<TokenRegistryError> {
+        let Error::RevertTransactionError { receipts, .. } = error else {
+            panic!("expected a reverted call, got {error:?}");
+        };
+        registry
+            .contract()
+            .log_decoder()
+            .decode_logs_with_type::<TokenRegistryError>(receipts)
+            .unwrap()
+    }
+
+    fn token_config(name: &str, decimals: u8) -> TokenConfig {
+        TokenConfig {
+            gateway: Bits256::from_hex_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+            decimals,
+            bridged_token_decimals: BRIDGED_TOKEN_DECIMALS,
+            name: name.to_string(),
+            symbol: "MYTKN".to_string(),
+        }
+    }
+
+    /// The configuration the test bridge contract is deployed with
+    fn test_token_config() -> TokenConfig {
+        token_config("MY_TOKEN", PROXY_TOKEN_DECIMALS)
+    }
+
+    async fn approve(registry: &TokenRegistryClient, config: &TokenConfig) {
+        registry
+            .approve(
+                env::TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+                config,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn registers_and_looks_up_bridge_contracts() {
+        let test_env = BridgeTestEnv::builder().build().await;
+        let wallet = test_env.wallet().clone();
+        let registry = env::deploy_token_registry(wallet.clone(), wallet.address().into()).await;
+        let bridge = test_env.client().contract_id();
+
+        approve(&registry, &test_token_config()).await;
+        let expected_root = registry
+            .expected_bytecode_root(env::TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY, bridge)
+            .await
+            .unwrap();
+        assert_eq!(
+            registry
+                .approved_bytecode_root(Bits256::from_hex_str(BRIDGED_TOKEN).unwrap())
+                .await
+                .unwrap(),
+            Some(expected_root)
+        );
+        registry
+            .register(bridge, TxParameters::default())
+            .await
+            .unwrap();
+
+        let entry = TokenEntry {
+            token: Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+            bridge,
+            asset_id: Bits256(*test_env.asset_id()),
+            decimals: PROXY_TOKEN_DECIMALS,
+            bridged_token_decimals: BRIDGED_TOKEN_DECIMALS,
+        };
+        assert_eq!(
+            registry.by_token(entry.token).await.unwrap(),
+            Some(entry.clone())
+        );
+        assert_eq!(
+            registry.by_bridge(bridge).await.unwrap(),
+            Some(entry.clone())
+        );
+        assert_eq!(
+            registry.by_asset(test_env.asset_id()).await.unwrap(),
+            Some(entry)
+        );
+        assert_eq!(
+            registry
+                .by_token(Bits256::from_hex_str(TO).unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn registers_after_the_metadata_message() {
+        let metadata = env::construct_metadata_msg_data(
+            BRIDGED_TOKEN,
+            "Wrapped Ether",
+            "WETH",
+            BRIDGED_TOKEN_DECIMALS,
+        );
+        let mut test_env = BridgeTestEnv::builder().message(metadata).build().await;
+        let wallet = test_env.wallet().clone();
+        let registry = env::deploy_token_registry(wallet.clone(), wallet.address().into()).await;
+        let bridge = test_env.client().contract_id();
+        test_env.relay(0).await;
+
+        // the contract now reports the registered name, the configured one is still readable
+        let contract = test_env.client().contract().methods();
+        let name = contract.name().simulate().await.unwrap().value;
+        assert_eq!(name.to_trimmed_str(), "Wrapped Ether");
+        let config = test_env.client().config().await.unwrap();
+        assert_eq!(config.name, "MY_TOKEN");
+        assert_eq!(config.symbol, "MYTKN");
+        assert_eq!(TokenConfig::from(&config), test_token_config());
+
+        approve(&registry, &test_token_config()).await;
+        registry
+            .register(bridge, TxParameters::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            registry
+                .by_bridge(bridge)
+                .await
+                .unwrap()
+                .map(|entry| entry.bridge),
+            Some(bridge)
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_owner_can_approve_and_register() {
+        let test_env = BridgeTestEnv::builder().wallets(2).build().await;
+        let owner = test_env.wallets()[0].clone();
+        let registry = env::deploy_token_registry(owner.clone(), owner.address().into()).await;
+        let other = TokenRegistryClient::new(registry.contract_id(), test_env.wallets()[1].clone());
+        let bridge = test_env.client().contract_id();
+
+        let error = other
+            .approve(
+                env::TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+                &test_token_config(),
+                TxParameters::default(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::Unauthorized]
+        );
+
+        approve(&registry, &test_token_config()).await;
+        let error = other
+            .register(bridge, TxParameters::default())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::Unauthorized]
+        );
+        assert_eq!(registry.by_bridge(bridge).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_token_without_an_approved_root() {
+        let test_env = BridgeTestEnv::builder().build().await;
+        let wallet = test_env.wallet().clone();
+        let registry = env::deploy_token_registry(wallet.clone(), wallet.address().into()).await;
+        let bridge = test_env.client().contract_id();
+
+        let error = registry
+            .register(bridge, TxParameters::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::BytecodeNotApproved]
+        );
+        assert_eq!(registry.by_bridge(bridge).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_bytecode_not_built_from_the_approved_binary() {
+        let test_env = BridgeTestEnv::builder().build().await;
+        let wallet = test_env.wallet().clone();
+        let registry = env::deploy_token_registry(wallet.clone(), wallet.address().into()).await;
+        let bridge = test_env.client().contract_id();
+
+        // the approved binary with another configuration for the same token
+        approve(&registry, &token_config("OTHER_TOKEN", 6)).await;
+        let error = registry
+            .register(bridge, TxParameters::default())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::BytecodeMismatch]
+        );
+        assert_eq!(registry.by_bridge(bridge).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_second_bridge_for_the_same_token() {
+        let test_env = BridgeTestEnv::builder().build().await;
+        let wallet = test_env.wallet().clone();
+        let registry = env::deploy_token_registry(wallet.clone(), wallet.address().into()).await;
+        let first = test_env.client().contract_id();
+        let second_config = token_config("SECOND_TOKEN", PROXY_TOKEN_DECIMALS);
+        let second =
+            env::deploy_bridge_client(wallet.clone(), Some(second_config.configurables().unwrap()))
+                .await
+                .contract_id();
+
+        approve(&registry, &test_token_config()).await;
+        registry
+            .register(first, TxParameters::default())
+            .await
+            .unwrap();
+
+        // the approval cannot be swapped once the token is registered
+        let error = registry
+            .approve(
+                env::TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+                &second_config,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::TokenAlreadyRegistered]
+        );
+        let error = registry
+            .register(second, TxParameters::default())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            registry_errors(&registry, &error),
+            vec![TokenRegistryError::TokenAlreadyRegistered]
+        );
+
+        assert_eq!(
+            registry
+                .by_token(Bits256::from_hex_str(BRIDGED_TOKEN).unwrap())
+                .await
+                .unwrap()
+                .map(|entry| entry.bridge),
+            Some(first)
+        );
+        assert_eq!(registry.by_bridge(second).await.unwrap(), None);
+    }
+}
+
+mod metadata {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{simulate_deposit, DepositOutcome, RefundReason};
+    use fuels::{prelude::Error, tx::Nonce};
+    use primitive_types::U256 as Unsigned256;
+
+    async fn relay(test_env: &BridgeTestEnv, index: usize) -> Result<Vec<Receipt>, Error> {
+        let client = test_env.client();
+        let message = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|message| message.nonce == Nonce::from(index as u64))
+            .unwrap();
+
+        client
+            .relay(
+                &message,
+                None,
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await
+    }
+
+    async fn deposit_to_address(amount: Unsigned256) -> (u64, Vec<u8>) {
+        let (message, _, _) = env::construct_msg_data(
+            BRIDGED_TOKEN,
+            FROM,
+            *Address::from_str(TO).unwrap(),
+            amount,
+            None,
+            false,
+            None,
+        )
+        .await;
+        message
+    }
+
+    #[tokio::test]
+    async fn gateway_registers_metadata_once() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let metadata = env::construct_metadata_msg_data(
+            BRIDGED_TOKEN,
+            "Wrapped Ether",
+            "WETH",
+            BRIDGED_TOKEN_DECIMALS,
+        );
+        let test_env = BridgeTestEnv::builder()
+            .messages([
+                metadata.clone(),
+                metadata,
+                deposit_to_address(config.test_amount).await,
+            ])
+            .build()
+            .await;
+        let client = test_env.client();
+
+        let receipts = relay(&test_env, 0).await.unwrap();
+        let registered = client
+            .contract()
+            .log_decoder()
+            .decode_logs_with_type::<env::MetadataRegisteredEvent>(&receipts)
+            .unwrap();
+        assert_eq!(registered.len(), 1);
+        assert_eq!(
+            registered[0].token,
+            Bits256::from_hex_str(BRIDGED_TOKEN).unwrap()
+        );
+        assert_eq!(registered[0].decimals, BRIDGED_TOKEN_DECIMALS);
+
+        // the registered name and symbol replace the configured ones
+        let methods = client.contract().methods();
+        let name = methods.name().simulate().await.unwrap().value;
+        let symbol = methods.symbol().simulate().await.unwrap().value;
+        assert_eq!(name.to_trimmed_str(), "Wrapped Ether");
+        assert_eq!(symbol.to_trimmed_str(), "WETH");
+        let bridge_config = client.config().await.unwrap();
+        assert_eq!(bridge_config.name, "MY_TOKEN");
+        assert_eq!(bridge_config.decimals, PROXY_TOKEN_DECIMALS);
+        assert_eq!(bridge_config.metadata.unwrap().name, registered[0].name);
+
+        let error = relay(&test_env, 1).await.unwrap_err();
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::MetadataAlreadyRegistered
+            ))
+        );
+
+        // deposits matching the registered metadata are still minted
+        relay(&test_env, 2).await.unwrap();
+        assert_eq!(
+            test_env
+                .balance_of(&Identity::Address(Address::from_str(TO).unwrap()))
+                .await,
+            config.fuel_equivalent_amount(config.test_amount)
+        );
+    }
+
+    #[tokio::test]
+    async fn only_the_gateway_can_register_metadata() {
+        let test_env = BridgeTestEnv::builder()
+            .message(env::construct_metadata_msg_data(
+                BRIDGED_TOKEN,
+                "Wrapped Ether",
+                "WETH",
+                BRIDGED_TOKEN_DECIMALS,
+            ))
+            .sender("0x0000000000000000000000000000000000000000000000000000000000005555")
+            .build()
+            .await;
+        let client = test_env.client();
+
+        let error = relay(&test_env, 0).await.unwrap_err();
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::UnauthorizedSender
+            ))
+        );
+        assert_eq!(client.config().await.unwrap().metadata, None);
+    }
+
+    #[tokio::test]
+    async fn truncated_metadata_reverts() {
+        let mut metadata = env::construct_metadata_msg_data(
+            BRIDGED_TOKEN,
+            "Wrapped Ether",
+            "WETH",
+            BRIDGED_TOKEN_DECIMALS,
+        );
+        metadata.1.pop();
+        let test_env = BridgeTestEnv::builder().message(metadata).build().await;
+        let client = test_env.client();
+
+        let error = relay(&test_env, 0).await.unwrap_err();
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::MessageDataTooShort
+            ))
+        );
+        assert_eq!(client.config().await.unwrap().metadata, None);
+    }
+
+    #[tokio::test]
+    async fn deposits_are_refunded_after_mismatched_metadata() {
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        // the gateway reports other decimals than the contract converts amounts with
+        let metadata = env::construct_metadata_msg_data(
+            BRIDGED_TOKEN,
+            "Wrapped Ether",
+            "WETH",
+            BRIDGED_TOKEN_DECIMALS - 1,
+        );
+        let test_env = BridgeTestEnv::builder()
+            .messages([metadata, deposit_to_address(config.test_amount).await])
+            .build()
+            .await;
+        let client = test_env.client();
+
+        relay(&test_env, 0).await.unwrap();
+
+        let message = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .remove(0);
+        assert!(matches!(
+            simulate_deposit(&message, &client.config().await.unwrap()),
+            DepositOutcome::Refund {
+                reason: RefundReason::MetadataMismatch,
+                ..
+            }
+        ));
+
+        let events = client
+            .decode_events(&relay(&test_env, 1).await.unwrap())
+            .unwrap();
+        assert!(events.deposits.is_empty());
+        assert_eq!(events.refunds_registered.len(), 1);
+        assert_eq!(
+            events.refunds_registered[0].amount,
+            Bits256(env::encode_hex(config.test_amount))
+        );
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+    }
+}
+
+mod native {
+    use super::*;
+
+    use bridge_fungible_token_sdk::{
+        simulate_deposit, withdrawal::MINT_WRAPPED_SELECTOR, DepositOutcome,
+    };
+    use fuels::{prelude::Error, test_helpers::DEFAULT_COIN_AMOUNT, tx::Nonce};
+
+    const NATIVE_ASSET: AssetId = AssetId::new([7u8; 32]);
+    const LOCKED_AMOUNT: u64 = 1_000;
+
+    async fn relay(test_env: &BridgeTestEnv, index: usize) -> Result<Vec<Receipt>, Error> {
+        let client = test_env.client();
+        let message = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|message| message.nonce == Nonce::from(index as u64))
+            .unwrap();
+
+        client
+            .relay(
+                &message,
+                None,
+                &env::get_gas_coins(test_env.wallet()).await,
+                TxParameters::default(),
+            )
+            .await
+    }
+
+    /// A bridge with the releases pending and `LOCKED_AMOUNT` of the native asset owned by the wallet
+    async fn native_env(releases: Vec<(u64, Vec<u8>)>) -> BridgeTestEnv {
+        BridgeTestEnv::builder()
+            .coin(LOCKED_AMOUNT, NATIVE_ASSET)
+            .messages(releases)
+            .build()
+            .await
+    }
+
+    #[tokio::test]
+    async fn lock_sends_a_mint_wrapped_message() {
+        let test_env = native_env(vec![]).await;
+        let client = test_env.client();
+        let to = Bits256::from_hex_str(FROM).unwrap();
+
+        let response = client
+            .lock(NATIVE_ASSET, to, LOCKED_AMOUNT, TxParameters::default())
+            .await
+            .unwrap();
+
+        let events = client.decode_events(&response.receipts).unwrap();
+        assert_eq!(events.locks.len(), 1);
+        assert_eq!(events.locks[0].to, to);
+        assert_eq!(events.locks[0].asset, ContractId::new(*NATIVE_ASSET));
+        assert_eq!(events.locks[0].amount, LOCKED_AMOUNT);
+
+        let receipt = response
+            .receipts
+            .iter()
+            .find(|receipt| matches!(receipt, Receipt::MessageOut { .. }))
+            .unwrap();
+        let message = WithdrawalMessage::decode(receipt, MINT_WRAPPED_SELECTOR)
+            .unwrap()
+            .ensure_route(
+                client.contract_id(),
+                Address::from_str(BRIDGED_TOKEN_GATEWAY).unwrap(),
+            )
+            .unwrap();
+        assert_eq!(message.to, to);
+        assert_eq!(message.token, Bits256(*NATIVE_ASSET));
+        assert_eq!(message.token_amount, LOCKED_AMOUNT.into());
+        assert_eq!(
+            WithdrawalMessage::try_from(receipt).unwrap_err(),
+            WithdrawalMessageError::InvalidSelector(MINT_WRAPPED_SELECTOR)
+        );
+
+        // locked coins are kept apart from the proxy token supply
+        assert_eq!(
+            client.locked_balance(NATIVE_ASSET).await.unwrap(),
+            LOCKED_AMOUNT
+        );
+        assert_eq!(client.total_supply().await.unwrap(), 0u64.into());
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::ContractId(client.contract_id()), NATIVE_ASSET)
+                .await,
+            LOCKED_AMOUNT
+        );
+    }
+
+    #[tokio::test]
+    async fn proxy_tokens_cannot_be_locked() {
+        let wallet = env::setup_wallet();
+        let config = env::generate_test_config((BRIDGED_TOKEN_DECIMALS, PROXY_TOKEN_DECIMALS));
+        let test_env = BridgeTestEnv::builder()
+            .message(
+                env::construct_msg_data(
+                    BRIDGED_TOKEN,
+                    FROM,
+                    *wallet.address().hash(),
+                    config.test_amount,
+                    None,
+                    false,
+                    None,
+                )
+                .await
+                .0,
+            )
+            .build()
+            .await;
+        let client = test_env.client();
+        relay(&test_env, 0).await.unwrap();
+
+        let error = client
+            .lock(
+                test_env.asset_id(),
+                Bits256::from_hex_str(FROM).unwrap(),
+                config.fuel_equivalent_amount(config.test_amount),
+                TxParameters::default(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::IncorrectAssetDeposited
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn release_message_unlocks_coins() {
+        let to = *Address::from_str(TO).unwrap();
+        let release = env::construct_release_msg_data(NATIVE_ASSET, to, LOCKED_AMOUNT / 4);
+        let test_env = native_env(vec![release]).await;
+        let client = test_env.client();
+        client
+            .lock(
+                NATIVE_ASSET,
+                Bits256::from_hex_str(FROM).unwrap(),
+                LOCKED_AMOUNT,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+
+        let message = test_env
+            .provider()
+            .get_messages(&client.relay_bytecode().predicate_root())
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            simulate_deposit(&message, &client.config().await.unwrap()),
+            DepositOutcome::Release {
+                to: Identity::Address(Address::new(to)),
+                asset: NATIVE_ASSET,
+                amount: LOCKED_AMOUNT / 4,
+            }
+        );
+
+        let events = client
+            .decode_events(&relay(&test_env, 0).await.unwrap())
+            .unwrap();
+        assert_eq!(events.releases.len(), 1);
+        assert_eq!(
+            events.releases[0].from,
+            Bits256::from_hex_str(FROM).unwrap()
+        );
+        assert!(events.deposits.is_empty());
+
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::Address(Address::new(to)), NATIVE_ASSET)
+                .await,
+            LOCKED_AMOUNT / 4
+        );
+        assert_eq!(
+            client.locked_balance(NATIVE_ASSET).await.unwrap(),
+            LOCKED_AMOUNT - LOCKED_AMOUNT / 4
+        );
+    }
+
+    #[tokio::test]
+    async fn release_exceeding_locked_balance_reverts() {
+        let to = *Address::from_str(TO).unwrap();
+        let test_env = native_env(vec![
+            env::construct_release_msg_data(NATIVE_ASSET, to, LOCKED_AMOUNT + 1),
+            env::construct_release_msg_data(NATIVE_ASSET, to, LOCKED_AMOUNT),
+        ])
+        .await;
+        let client = test_env.client();
+        client
+            .lock(
+                NATIVE_ASSET,
+                Bits256::from_hex_str(FROM).unwrap(),
+                LOCKED_AMOUNT,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+
+        let error = relay(&test_env, 0).await.unwrap_err();
+        assert_eq!(
+            client.decode_revert(&error).unwrap(),
+            Some(RevertReason::Bridge(
+                BridgeFungibleTokenError::ReleaseExceedsLocked
+            ))
+        );
+        assert_eq!(
+            client.locked_balance(NATIVE_ASSET).await.unwrap(),
+            LOCKED_AMOUNT
+        );
+
+        // releasing everything that is locked still goes through
+        relay(&test_env, 1).await.unwrap();
+        assert_eq!(client.locked_balance(NATIVE_ASSET).await.unwrap(), 0);
+    }
+}
+
+mod non_fungible {
+    use super::*;
+
+    use crate::env::{
+        non_fungible::{
+            BridgeNonFungibleTokenError, DepositEvent, RefundRegisteredEvent, WithdrawalEvent,
+        },
+        DepositRecorderContract,
+    };
+    use fuels::prelude::{Bech32ContractId, Error};
+    use primitive_types::U256 as Unsigned256;
+
+    const TOKEN_ID: u64 = 1;
+    const URI: &str = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi/1";
+
+    fn nft_errors(test_env: &BridgeTestEnv, error: &Error) -> Vec<BridgeNonFungibleTokenError> {
+        let Error::RevertTransactionError { receipts, .. } = error else {
+            panic!("expected a reverted relay, got {error:?}");
+        };
+        test_env
+            .non_fungible_bridge()
+            .unwrap()
+            .log_decoder()
+            .decode_logs_with_type::<BridgeNonFungibleTokenError>(receipts)
+            .unwrap()
+    }
+
+    fn nft_asset_id() -> AssetId {
+        AssetId::new(*env::precalculate_non_fungible_contract_id())
+    }
+
+    /// Decode the message the call sent to the gateway
+    fn withdrawal_message(receipts: &[Receipt]) -> WithdrawalMessage {
+        let receipt = receipts
+            .iter()
+            .find(|receipt| matches!(receipt, Receipt::MessageOut { .. }))
+            .unwrap();
+        env::decode_withdrawal_message(
+            receipt,
+            &Bech32ContractId::from(env::precalculate_non_fungible_contract_id()),
+        )
+    }
+
+    #[tokio::test]
+    async fn deposit_mints_a_single_coin_with_the_uri() {
+        let to = *Address::from_str(TO).unwrap();
+        let mut test_env = BridgeTestEnv::builder()
+            .message(env::construct_nft_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                to,
+                TOKEN_ID.into(),
+                URI,
+                false,
+            ))
+            .non_fungible_bridge()
+            .build()
+            .await;
+
+        let receipts = test_env.relay(0).await;
+        let nft = test_env.non_fungible_bridge().unwrap();
+        let deposits = nft
+            .log_decoder()
+            .decode_logs_with_type::<DepositEvent>(&receipts)
+            .unwrap();
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].to, Identity::Address(Address::new(to)));
+        assert_eq!(
+            deposits[0].token_id,
+            Bits256(env::encode_hex(TOKEN_ID.into()))
+        );
+
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::Address(Address::new(to)), nft_asset_id())
+                .await,
+            1
+        );
+        let methods = nft.methods();
+        assert_eq!(methods.total_supply().simulate().await.unwrap().value, 1);
+        assert_eq!(
+            methods.token_uri().simulate().await.unwrap().value.0,
+            URI.as_bytes()
+        );
+        // the fungible bridge relayed through the same environment is untouched
+        assert_eq!(test_env.client().total_supply().await.unwrap(), 0u64.into());
+    }
+
+    #[tokio::test]
+    async fn deposit_to_contract_transfers_without_calling_it() {
+        // the recorder would record a call from the non-fungible bridge
+        let recorder = env::deposit_recorder(env::precalculate_non_fungible_contract_id());
+        let recorder_id = recorder.contract_id();
+        let mut test_env = BridgeTestEnv::builder()
+            .message(env::construct_nft_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                *recorder_id,
+                TOKEN_ID.into(),
+                URI,
+                true,
+            ))
+            .non_fungible_bridge()
+            .recipient_contract(recorder_id)
+            .build()
+            .await;
+        let recorder = DepositRecorderContract::new(
+            recorder
+                .deploy(test_env.wallet(), TxParameters::default())
+                .await
+                .unwrap(),
+            test_env.wallet().clone(),
+        );
+
+        test_env.relay(0).await;
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::ContractId(recorder_id), nft_asset_id())
+                .await,
+            1
+        );
+        assert_eq!(
+            recorder
+                .methods()
+                .deposit_count()
+                .call()
+                .await
+                .unwrap()
+                .value,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn deposit_of_another_token_registers_refund() {
+        let to = *Address::from_str(TO).unwrap();
+        let other_id = Unsigned256::from(TOKEN_ID + 1);
+        let mut test_env = BridgeTestEnv::builder()
+            .message(env::construct_nft_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                to,
+                other_id,
+                URI,
+                false,
+            ))
+            .non_fungible_bridge()
+            .build()
+            .await;
+
+        let receipts = test_env.relay(0).await;
+        let nft = test_env.non_fungible_bridge().unwrap();
+        let refunds = nft
+            .log_decoder()
+            .decode_logs_with_type::<RefundRegisteredEvent>(&receipts)
+            .unwrap();
+        assert_eq!(refunds.len(), 1);
+        assert_eq!(refunds[0].from, Bits256::from_hex_str(FROM).unwrap());
+        assert_eq!(refunds[0].token_id, Bits256(env::encode_hex(other_id)));
+        assert_eq!(
+            nft.methods().total_supply().simulate().await.unwrap().value,
+            0
+        );
+
+        // the refund sends the token back to the depositor through the gateway
+        let response = nft
+            .methods()
+            .claim_refund(refunds[0].from, refunds[0].token, refunds[0].token_id)
+            .call()
+            .await
+            .unwrap();
+        let message = withdrawal_message(&response.receipts);
+        assert_eq!(message.to, refunds[0].from);
+        assert_eq!(message.token, refunds[0].token);
+        assert_eq!(message.token_amount, other_id);
+
+        let error = nft
+            .methods()
+            .claim_refund(refunds[0].from, refunds[0].token, refunds[0].token_id)
+            .call()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            nft_errors(&test_env, &error),
+            vec![BridgeNonFungibleTokenError::NoRefundAvailable]
+        );
+    }
+
+    #[tokio::test]
+    async fn deposit_while_bridged_registers_refund() {
+        let to = *Address::from_str(TO).unwrap();
+        let deposit =
+            env::construct_nft_msg_data(BRIDGED_TOKEN, FROM, to, TOKEN_ID.into(), URI, false);
+        let mut test_env = BridgeTestEnv::builder()
+            .messages([deposit.clone(), deposit])
+            .non_fungible_bridge()
+            .build()
+            .await;
+
+        test_env.relay(0).await;
+        let receipts = test_env.relay(1).await;
+        let nft = test_env.non_fungible_bridge().unwrap();
+        assert_eq!(
+            nft.log_decoder()
+                .decode_logs_with_type::<RefundRegisteredEvent>(&receipts)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::Address(Address::new(to)), nft_asset_id())
+                .await,
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn withdraw_burns_the_coin_and_releases_the_token() {
+        let wallet = env::setup_wallet();
+        let mut test_env = BridgeTestEnv::builder()
+            .message(env::construct_nft_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                *wallet.address().hash(),
+                TOKEN_ID.into(),
+                URI,
+                false,
+            ))
+            .non_fungible_bridge()
+            .build()
+            .await;
+        test_env.relay(0).await;
+
+        let nft = test_env.non_fungible_bridge().unwrap();
+        let to = Bits256::from_hex_str(FROM).unwrap();
+        let response = nft
+            .methods()
+            .withdraw(to)
+            .call_params(CallParameters::new(1, nft_asset_id(), 5000))
+            .unwrap()
+            .call()
+            .await
+            .unwrap();
+
+        let withdrawals = nft
+            .log_decoder()
+            .decode_logs_with_type::<WithdrawalEvent>(&response.receipts)
+            .unwrap();
+        assert_eq!(withdrawals.len(), 1);
+        assert_eq!(withdrawals[0].to, to);
+
+        let message = withdrawal_message(&response.receipts);
+        assert_eq!(message.to, to);
+        assert_eq!(message.token, Bits256::from_hex_str(BRIDGED_TOKEN).unwrap());
+        assert_eq!(message.token_amount, TOKEN_ID.into());
+
+        assert_eq!(
+            nft.methods().total_supply().simulate().await.unwrap().value,
+            0
+        );
+        assert_eq!(
+            test_env
+                .balance_of_asset(&Identity::Address(wallet.address().into()), nft_asset_id())
+                .await,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_deposits_not_from_the_gateway() {
+        let mut test_env = BridgeTestEnv::builder()
+            .message(env::construct_nft_msg_data(
+                BRIDGED_TOKEN,
+                FROM,
+                *Address::from_str(TO).unwrap(),
+                TOKEN_ID.into(),
+                URI,
+                false,
+            ))
+            .sender("0x0000000000000000000000000000000000000000000000000000000000005555")
+            .non_fungible_bridge()
+            .build()
+            .await;
+
+        let error = test_env.try_relay(0).await.unwrap_err();
+        assert_eq!(
+            nft_errors(&test_env, &error),
+            vec![BridgeNonFungibleTokenError::UnauthorizedSender]
+        );
+        assert_eq!(
+            test_env
+                .non_fungible_bridge()
+                .unwrap()
+                .methods()
+                .total_supply()
+                .simulate()
+                .await
+                .unwrap()
+                .value,
+            0
+        );
+    }
 }
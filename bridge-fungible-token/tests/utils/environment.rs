@@ -1,6 +1,4 @@
-use crate::utils::builder;
-
-use std::{mem::size_of, num::ParseIntError, result::Result as StdResult, str::FromStr};
+use std::{mem::size_of, str::FromStr};
 
 use fuel_core_types::{
     fuel_tx::{Bytes32, Input, Output, Receipt, TxPointer, UtxoId},
@@ -13,29 +11,56 @@ use fuels::{
     },
     prelude::{
         abigen, setup_custom_assets_coins, setup_test_provider, Address, AssetConfig, AssetId,
-        Bech32ContractId, Config, Contract, ContractId, LoadConfiguration, Provider,
-        ScriptTransaction, TxParameters,
+        Bech32Address, Bech32ContractId, Config, Contract, ContractId, Error, LoadConfiguration,
+        Provider, ScriptTransaction, TxParameters,
     },
     test_helpers::{setup_single_message, DEFAULT_COIN_AMOUNT},
-    types::{message::Message, Bits256},
+    types::{
+        coin_type::CoinType,
+        message::Message,
+        traits::{Parameterize, Tokenizable},
+        Bits256, Identity,
+    },
 };
 use primitive_types::U256 as Unsigned256;
-use sha3::{Digest, Keccak256};
+
+pub use bridge_fungible_token_sdk::abi::{
+    BridgeFungibleTokenContract, BridgeFungibleTokenContractConfigurables, DepositEvent,
+    MetadataRegisteredEvent, RefundRegisteredEvent, WithdrawalEvent,
+};
+use bridge_fungible_token_sdk::{
+    builder::{self, RelayGas},
+    decimals, BridgeClient, DepositMessage, MetadataMessage, RelayBytecode, ReleaseMessage,
+    TokenRegistryClient, WithdrawalMessage,
+};
 
 const CONTRACT_MESSAGE_PREDICATE_BINARY: &str =
     "../bridge-message-predicates/contract_message_predicate.bin";
+const CONTRACT_MESSAGE_SCRIPT_BINARY: &str =
+    "../bridge-message-predicates/contract_message_script.bin";
 const MESSAGE_SENDER_ADDRESS: &str =
     "0x00000000000000000000000096c53cd98B7297564716a8f2E1de2C83928Af2fe";
-const TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+pub const TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
     "../bridge-fungible-token/out/debug/bridge_fungible_token.bin";
 const DEPOSIT_RECIPIENT_CONTRACT_BINARY: &str =
     "../test-deposit-recipient-contract/out/debug/test_deposit_recipient_contract.bin";
+const DEPOSIT_RECORDER_CONTRACT_BINARY: &str =
+    "../deposit-recorder-contract/out/debug/deposit_recorder_contract.bin";
+const STAKING_VAULT_CONTRACT_BINARY: &str =
+    "../staking-vault-contract/out/debug/staking_vault_contract.bin";
+const DEPOSIT_FORWARDER_CONTRACT_BINARY: &str =
+    "../deposit-forwarder-contract/out/debug/deposit_forwarder_contract.bin";
+const TOKEN_REGISTRY_CONTRACT_BINARY: &str =
+    "../bridge-token-registry/out/debug/bridge_token_registry.bin";
+const MALICIOUS_RECIPIENT_CONTRACT_BINARY: &str =
+    "../test-malicious-recipient-contract/out/debug/test_malicious_recipient_contract.bin";
+const BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+    "../bridge-non-fungible-token/out/debug/bridge_non_fungible_token.bin";
+
+/// Gas limit of relays built without a dry run
+const RELAY_GAS_LIMIT: u64 = 30_000_000;
 
 abigen!(
-    Contract(
-        name = "BridgeFungibleTokenContract",
-        abi = "./bridge-fungible-token/out/debug/bridge_fungible_token-abi.json",
-    ),
     Predicate(
         name = "ContractMessagePredicate",
         abi = "./bridge-message-predicates/contract_message_predicate-abi.json"
@@ -49,11 +74,29 @@ abigen!(
         abi =
             "./test-deposit-recipient-contract/out/debug/test_deposit_recipient_contract-abi.json",
     ),
+    Contract(
+        name = "DepositRecorderContract",
+        abi = "./deposit-recorder-contract/out/debug/deposit_recorder_contract-abi.json",
+    ),
+    Contract(
+        name = "StakingVaultContract",
+        abi = "./staking-vault-contract/out/debug/staking_vault_contract-abi.json",
+    ),
+    Contract(
+        name = "DepositForwarderContract",
+        abi = "./deposit-forwarder-contract/out/debug/deposit_forwarder_contract-abi.json",
+    ),
+    Contract(
+        name = "MaliciousRecipientContract",
+        abi =
+            "./test-malicious-recipient-contract/out/debug/test_malicious_recipient_contract-abi.json",
+    ),
 );
 
 pub struct TestConfig {
+    pub bridged_token_decimals: u8,
+    pub proxy_token_decimals: u8,
     pub adjustment_factor: Unsigned256,
-    pub adjustment_is_div: bool,
     pub min_amount: Unsigned256,
     pub max_amount: Unsigned256,
     pub test_amount: Unsigned256,
@@ -65,73 +108,41 @@ pub struct TestConfig {
 
 impl TestConfig {
     pub fn fuel_equivalent_amount(&self, amount: Unsigned256) -> u64 {
-        if self.adjustment_is_div {
-            (amount * self.adjustment_factor).as_u64()
-        } else {
-            (amount / self.adjustment_factor).as_u64()
-        }
+        decimals::adjust_deposit_decimals(
+            amount,
+            self.proxy_token_decimals,
+            self.bridged_token_decimals,
+        )
+        .unwrap()
     }
 }
 
-fn keccak_hash<B>(data: B) -> Bytes32
-where
-    B: AsRef<[u8]>,
-{
-    let mut hasher = Keccak256::new();
-    hasher.update(data);
-    <[u8; Bytes32::LEN]>::from(hasher.finalize()).into()
-}
-
 pub fn generate_test_config(decimals: (u8, u8)) -> TestConfig {
-    let bridged_token_decimals = Unsigned256::from(decimals.0);
-    let proxy_token_decimals = Unsigned256::from(decimals.1);
+    let (bridged_token_decimals, proxy_token_decimals) = decimals;
     let one = Unsigned256::from(1);
 
-    let adjustment_factor = match (bridged_token_decimals, proxy_token_decimals) {
-        (bridged_token_decimals, proxy_token_decimals)
-            if bridged_token_decimals > proxy_token_decimals =>
-        {
-            Unsigned256::from(10).pow(bridged_token_decimals - proxy_token_decimals)
-        }
-        (bridged_token_decimals, proxy_token_decimals)
-            if bridged_token_decimals < proxy_token_decimals =>
-        {
-            Unsigned256::from(10).pow(proxy_token_decimals - bridged_token_decimals)
-        }
-        _ => one,
-    };
-
-    let adjustment_is_div = bridged_token_decimals < proxy_token_decimals;
+    let adjustment_factor = decimals::pow_10(bridged_token_decimals.abs_diff(proxy_token_decimals));
 
-    let min_amount = if bridged_token_decimals > proxy_token_decimals {
-        Unsigned256::from(1) * adjustment_factor
-    } else {
-        one
-    };
-
-    let max_amount = match (bridged_token_decimals, proxy_token_decimals) {
-        (bridged_token_decimals, proxy_token_decimals)
-            if bridged_token_decimals > proxy_token_decimals =>
-        {
-            Unsigned256::from(u64::MAX) * adjustment_factor
-        }
-        (bridged_token_decimals, proxy_token_decimals)
-            if bridged_token_decimals < proxy_token_decimals =>
-        {
-            Unsigned256::from(u64::MAX) / adjustment_factor
-        }
-        (_, _) => one,
-    };
+    // the boundaries come from the reference implementation the contract is tested against
+    let min_amount = decimals::min_deposit(proxy_token_decimals, bridged_token_decimals).unwrap();
+    let max_amount = decimals::max_deposit(proxy_token_decimals, bridged_token_decimals).unwrap();
 
     let test_amount = (min_amount + max_amount) / Unsigned256::from(2);
     let not_enough = min_amount - one;
     let overflow_1 = max_amount + one;
     let overflow_2 = max_amount + (one << 160);
     let overflow_3 = max_amount + (one << 224);
+    assert!(decimals::adjust_deposit_decimals(
+        overflow_1,
+        proxy_token_decimals,
+        bridged_token_decimals
+    )
+    .is_err());
 
     TestConfig {
+        bridged_token_decimals,
+        proxy_token_decimals,
         adjustment_factor,
-        adjustment_is_div,
         min_amount,
         test_amount,
         max_amount,
@@ -157,155 +168,446 @@ pub fn setup_wallet() -> WalletUnlocked {
     wallet
 }
 
-/// Sets up a test fuel environment with a funded wallet
-pub async fn setup_environment(
-    wallet: &mut WalletUnlocked,
+/// Bindings of the non-fungible bridge, kept apart as its event names match the fungible ones
+pub mod non_fungible {
+    use fuels::prelude::abigen;
+
+    abigen!(Contract(
+        name = "BridgeNonFungibleTokenContract",
+        abi = "./bridge-non-fungible-token/out/debug/bridge_non_fungible_token-abi.json",
+    ));
+}
+
+use non_fungible::BridgeNonFungibleTokenContract;
+
+/// Builds a [`BridgeTestEnv`]
+#[derive(Default)]
+pub struct BridgeTestEnvBuilder {
+    wallet: Option<WalletUnlocked>,
+    wallet_count: usize,
     coins: Vec<(Word, AssetId)>,
+    owned_coins: Vec<(Bech32Address, Word, AssetId)>,
     messages: Vec<(Word, Vec<u8>)>,
-    deposit_contract: Option<ContractId>,
-    sender: Option<&str>,
+    recipient_contracts: Vec<ContractId>,
+    deposit_recipient: bool,
+    non_fungible_bridge: bool,
+    sender: Option<Address>,
     configurables: Option<BridgeFungibleTokenContractConfigurables>,
-) -> (
-    BridgeFungibleTokenContract<WalletUnlocked>,
-    Vec<Input>,
-    Vec<Input>,
-    Vec<Input>,
-    Bech32ContractId,
-    Provider,
-) {
-    // Generate coins for wallet
-    let asset_configs: Vec<AssetConfig> = coins
-        .iter()
-        .map(|coin| AssetConfig {
-            id: coin.1,
-            num_coins: 1,
-            coin_amount: coin.0,
-        })
-        .collect();
-    let all_coins = setup_custom_assets_coins(wallet.address(), &asset_configs[..]);
-
-    // Generate messages
-    let message_nonce = Word::default();
-    let message_sender = match sender {
-        Some(v) => Address::from_str(v).unwrap(),
-        None => Address::from_str(MESSAGE_SENDER_ADDRESS).unwrap(),
-    };
+}
 
-    let predicate = Predicate::load_from(CONTRACT_MESSAGE_PREDICATE_BINARY).unwrap();
-    let predicate_root = predicate.address();
+impl BridgeTestEnvBuilder {
+    /// Uses the given wallet instead of [`setup_wallet`] as the first wallet
+    pub fn wallet(mut self, wallet: WalletUnlocked) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
 
-    let mut all_messages: Vec<Message> = vec![];
-    for msg in messages {
-        all_messages.push(setup_single_message(
-            &message_sender.into(),
-            predicate_root,
-            msg.0,
-            message_nonce.into(),
-            msg.1.clone(),
-        ))
+    /// Total number of wallets, every wallet after the first gets a single base asset coin
+    pub fn wallets(mut self, count: usize) -> Self {
+        self.wallet_count = count;
+        self
     }
 
-    let (provider, _) = setup_test_provider(
-        all_coins.clone(),
-        all_messages.clone(),
-        Some(Config::local_node()),
-        None,
-    )
-    .await;
+    /// Adds a coin owned by the first wallet, on top of the base asset coin it starts with
+    pub fn coin(mut self, amount: Word, asset_id: AssetId) -> Self {
+        self.coins.push((amount, asset_id));
+        self
+    }
 
-    wallet.set_provider(provider.clone());
+    pub fn coins(mut self, coins: impl IntoIterator<Item = (Word, AssetId)>) -> Self {
+        self.coins.extend(coins);
+        self
+    }
 
-    let test_contract_id = match configurables {
-        Some(config) => Contract::load_from(
-            TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
-            LoadConfiguration::default().set_configurables(config),
+    /// Adds a coin owned by any address, such as a predicate root
+    pub fn coin_for(mut self, owner: Bech32Address, amount: Word, asset_id: AssetId) -> Self {
+        self.owned_coins.push((owner, amount, asset_id));
+        self
+    }
+
+    /// Adds a message for the contract message predicate, as built by [`construct_msg_data`]
+    pub fn message(mut self, message: (Word, Vec<u8>)) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    pub fn messages(mut self, messages: impl IntoIterator<Item = (Word, Vec<u8>)>) -> Self {
+        self.messages.extend(messages);
+        self
+    }
+
+    /// Adds a contract input to every relay
+    pub fn recipient_contract(mut self, id: ContractId) -> Self {
+        self.recipient_contracts.push(id);
+        self
+    }
+
+    /// Deploys the deposit recipient test contract and adds it to every relay
+    pub fn deposit_recipient(mut self) -> Self {
+        self.deposit_recipient = true;
+        self
+    }
+
+    /// Also deploys the non-fungible bridge without configurables and adds it to every relay
+    pub fn non_fungible_bridge(mut self) -> Self {
+        self.non_fungible_bridge = true;
+        self
+    }
+
+    /// Sends the messages from `sender` instead of the gateway
+    pub fn sender(mut self, sender: &str) -> Self {
+        self.sender = Some(Address::from_str(sender).unwrap());
+        self
+    }
+
+    pub fn configurables(
+        mut self,
+        configurables: BridgeFungibleTokenContractConfigurables,
+    ) -> Self {
+        self.configurables = Some(configurables);
+        self
+    }
+
+    /// Starts a node with the coins and messages and deploys the test contract
+    pub async fn build(self) -> BridgeTestEnv {
+        let mut wallets = vec![self.wallet.unwrap_or_else(setup_wallet)];
+        wallets.extend((1..self.wallet_count).map(|_| WalletUnlocked::new_random(None)));
+
+        // Generate coins for the wallets
+        let asset_configs: Vec<AssetConfig> = self
+            .coins
+            .iter()
+            .map(|coin| AssetConfig {
+                id: coin.1,
+                num_coins: 1,
+                coin_amount: coin.0,
+            })
+            .collect();
+        let wallet_coins = setup_custom_assets_coins(wallets[0].address(), &asset_configs[..]);
+
+        let mut all_coins = wallet_coins.clone();
+        for wallet in &wallets[1..] {
+            all_coins.extend(setup_custom_assets_coins(
+                wallet.address(),
+                &[AssetConfig {
+                    id: AssetId::default(),
+                    num_coins: 1,
+                    coin_amount: DEFAULT_COIN_AMOUNT,
+                }],
+            ));
+        }
+        for (owner, amount, asset_id) in &self.owned_coins {
+            all_coins.extend(setup_custom_assets_coins(
+                owner,
+                &[AssetConfig {
+                    id: *asset_id,
+                    num_coins: 1,
+                    coin_amount: *amount,
+                }],
+            ));
+        }
+
+        // Generate messages
+        let message_nonce = Word::default();
+        let message_sender = self
+            .sender
+            .unwrap_or_else(|| Address::from_str(MESSAGE_SENDER_ADDRESS).unwrap());
+
+        let predicate = Predicate::load_from(CONTRACT_MESSAGE_PREDICATE_BINARY).unwrap();
+        let predicate_root = predicate.address();
+
+        let mut all_messages: Vec<Message> = vec![];
+        for (index, msg) in self.messages.into_iter().enumerate() {
+            // every message needs its own nonce to be spendable in the same environment
+            all_messages.push(setup_single_message(
+                &message_sender.into(),
+                predicate_root,
+                msg.0,
+                (message_nonce + index as Word).into(),
+                msg.1.clone(),
+            ))
+        }
+
+        let (provider, _) = setup_test_provider(
+            all_coins,
+            all_messages.clone(),
+            Some(Config::local_node()),
+            None,
         )
-        .unwrap()
-        .deploy(&wallet.clone(), TxParameters::default())
-        .await
-        .unwrap(),
-        None => Contract::load_from(
+        .await;
+
+        for wallet in wallets.iter_mut() {
+            wallet.set_provider(provider.clone());
+        }
+        let wallet = wallets[0].clone();
+
+        let load_configuration = match self.configurables {
+            Some(config) => LoadConfiguration::default().set_configurables(config),
+            None => LoadConfiguration::default(),
+        };
+        let contract_id = Contract::load_from(
             TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
-            LoadConfiguration::default(),
+            load_configuration,
         )
         .unwrap()
-        .deploy(&wallet.clone(), TxParameters::default())
+        .deploy(&wallet, TxParameters::default())
         .await
-        .unwrap(),
-    };
+        .unwrap();
+
+        let contract = BridgeFungibleTokenContract::new(contract_id.clone(), wallet.clone());
+        let client = get_bridge_client(wallet.clone(), contract_id.clone());
+
+        let mut recipient_contracts = self.recipient_contracts;
+        let non_fungible = if self.non_fungible_bridge {
+            let id = Contract::load_from(
+                BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+                LoadConfiguration::default(),
+            )
+            .unwrap()
+            .deploy(&wallet, TxParameters::default())
+            .await
+            .unwrap();
+            recipient_contracts.push(id.clone().into());
+            Some(BridgeNonFungibleTokenContract::new(id, wallet.clone()))
+        } else {
+            None
+        };
+        let deposit_recipient = if self.deposit_recipient {
+            let (instance, id) = get_deposit_recipient_contract_instance(wallet.clone()).await;
+            recipient_contracts.push(id);
+            Some(instance)
+        } else {
+            None
+        };
+
+        // Build inputs for provided coins
+        let coin_inputs = wallet_coins
+            .into_iter()
+            .map(|coin| {
+                Input::coin_signed(
+                    coin.utxo_id,
+                    coin.owner.into(),
+                    coin.amount,
+                    coin.asset_id,
+                    Default::default(),
+                    0,
+                    coin.maturity.into(),
+                )
+            })
+            .collect();
+
+        // Build inputs for provided messages
+        let message_inputs = all_messages
+            .into_iter()
+            .map(|message| {
+                if message.data.is_empty() {
+                    Input::message_coin_predicate(
+                        message.sender.into(),
+                        message.recipient.into(),
+                        message.amount,
+                        message.nonce,
+                        predicate.code().to_vec(),
+                        vec![],
+                    )
+                } else {
+                    Input::message_data_predicate(
+                        message.sender.into(),
+                        message.recipient.into(),
+                        message.amount,
+                        message.nonce,
+                        message.data,
+                        predicate.code().to_vec(),
+                        vec![],
+                    )
+                }
+            })
+            .collect();
+
+        // Build contract inputs
+        let contract_inputs = std::iter::once(ContractId::from(contract_id.clone()))
+            .chain(recipient_contracts)
+            .map(|id| {
+                Input::contract(
+                    UtxoId::new(Bytes32::zeroed(), 0u8),
+                    Bytes32::zeroed(),
+                    Bytes32::zeroed(),
+                    TxPointer::default(),
+                    id,
+                )
+            })
+            .collect();
+
+        BridgeTestEnv {
+            wallets,
+            provider,
+            contract,
+            contract_id,
+            client,
+            deposit_recipient,
+            non_fungible,
+            contract_inputs,
+            coin_inputs,
+            message_inputs,
+            last_receipts: vec![],
+        }
+    }
+}
+
+/// A running test node with the test contract deployed and messages waiting to be relayed
+pub struct BridgeTestEnv {
+    wallets: Vec<WalletUnlocked>,
+    provider: Provider,
+    contract: BridgeFungibleTokenContract<WalletUnlocked>,
+    contract_id: Bech32ContractId,
+    client: BridgeClient,
+    deposit_recipient: Option<DepositRecipientContract<WalletUnlocked>>,
+    non_fungible: Option<BridgeNonFungibleTokenContract<WalletUnlocked>>,
+    contract_inputs: Vec<Input>,
+    coin_inputs: Vec<Input>,
+    message_inputs: Vec<Input>,
+    last_receipts: Vec<Receipt>,
+}
+
+impl BridgeTestEnv {
+    /// A builder whose first wallet owns a single base asset coin of `DEFAULT_COIN_AMOUNT`
+    pub fn builder() -> BridgeTestEnvBuilder {
+        BridgeTestEnvBuilder {
+            wallet_count: 1,
+            coins: vec![(DEFAULT_COIN_AMOUNT, AssetId::default())],
+            ..Default::default()
+        }
+    }
+
+    /// The first wallet, which owns the configured coins and deployed the contracts
+    pub fn wallet(&self) -> &WalletUnlocked {
+        &self.wallets[0]
+    }
+
+    pub fn wallets(&self) -> &[WalletUnlocked] {
+        &self.wallets
+    }
+
+    pub fn provider(&self) -> &Provider {
+        &self.provider
+    }
+
+    pub fn contract(&self) -> &BridgeFungibleTokenContract<WalletUnlocked> {
+        &self.contract
+    }
+
+    pub fn contract_id(&self) -> &Bech32ContractId {
+        &self.contract_id
+    }
+
+    /// The proxy token minted by the test contract
+    pub fn asset_id(&self) -> AssetId {
+        AssetId::new(*self.contract_id.hash())
+    }
+
+    pub fn client(&self) -> &BridgeClient {
+        &self.client
+    }
 
-    let test_contract = BridgeFungibleTokenContract::new(test_contract_id.clone(), wallet.clone());
+    /// The deposit recipient contract, if the builder was asked to deploy it
+    pub fn deposit_recipient(&self) -> Option<&DepositRecipientContract<WalletUnlocked>> {
+        self.deposit_recipient.as_ref()
+    }
+
+    pub fn non_fungible_bridge(&self) -> Option<&BridgeNonFungibleTokenContract<WalletUnlocked>> {
+        self.non_fungible.as_ref()
+    }
+
+    pub fn contract_inputs(&self) -> &[Input] {
+        &self.contract_inputs
+    }
+
+    pub fn coin_inputs(&self) -> &[Input] {
+        &self.coin_inputs
+    }
+
+    pub fn message_inputs(&self) -> &[Input] {
+        &self.message_inputs
+    }
+
+    /// Relays the message at `index` to the test contract, paying gas from the first wallet
+    pub async fn relay(&mut self, index: usize) -> Vec<Receipt> {
+        self.try_relay(index).await.unwrap()
+    }
+
+    /// Like [`Self::relay`], returning the error when the relay reverts
+    pub async fn try_relay(&mut self, index: usize) -> Result<Vec<Receipt>, Error> {
+        let gas_coins = get_gas_coins(self.wallet()).await;
+        let script_bytecode = std::fs::read(CONTRACT_MESSAGE_SCRIPT_BINARY).unwrap();
+        let mut tx = builder::build_contract_message_tx(
+            &script_bytecode,
+            self.message_inputs[index].clone(),
+            self.contract_inputs.clone(),
+            &RelayGas::from(&gas_coins[..]),
+            &generate_variable_output(),
+            TxParameters::new(0, RELAY_GAS_LIMIT, 0),
+        )?;
+
+        self.wallet().sign_transaction(&mut tx)?;
+        let receipts = self.provider.send_transaction(&tx).await?;
+        self.last_receipts = receipts.clone();
+        Ok(receipts)
+    }
 
-    // Build inputs for provided coins
-    let coin_inputs = all_coins
+    /// Receipts of the last relay
+    pub fn last_receipts(&self) -> &[Receipt] {
+        &self.last_receipts
+    }
+
+    /// Logs of type `T` emitted by the test contract during the last relay
+    pub fn last_logs<T: Tokenizable + Parameterize + 'static>(&self) -> Vec<T> {
+        self.contract
+            .log_decoder()
+            .decode_logs_with_type::<T>(&self.last_receipts)
+            .unwrap()
+    }
+
+    /// Proxy token balance of an address or contract
+    pub async fn balance_of(&self, owner: &Identity) -> u64 {
+        self.balance_of_asset(owner, self.asset_id()).await
+    }
+
+    pub async fn balance_of_asset(&self, owner: &Identity, asset_id: AssetId) -> u64 {
+        match owner {
+            Identity::Address(address) => self
+                .provider
+                .get_asset_balance(&(*address).into(), asset_id)
+                .await
+                .unwrap(),
+            Identity::ContractId(contract_id) => self
+                .provider
+                .get_contract_asset_balance(&(*contract_id).into(), asset_id)
+                .await
+                .unwrap(),
+        }
+    }
+}
+
+/// Spendable coins to pay for a relay, for when the coins of the environment
+/// may already have been used by earlier calls
+pub async fn get_gas_coins(wallet: &WalletUnlocked) -> Vec<Input> {
+    wallet
+        .get_spendable_resources(AssetId::default(), 1)
+        .await
+        .unwrap()
         .into_iter()
-        .map(|coin| {
-            Input::coin_signed(
+        .filter_map(|resource| match resource {
+            CoinType::Coin(coin) => Some(Input::coin_signed(
                 coin.utxo_id,
                 coin.owner.into(),
                 coin.amount,
                 coin.asset_id,
-                Default::default(),
+                TxPointer::default(),
                 0,
                 coin.maturity.into(),
-            )
+            )),
+            CoinType::Message(_) => None,
         })
-        .collect();
-
-    // Build inputs for provided messages
-    let message_inputs = all_messages
-        .into_iter()
-        .map(|message| {
-            if message.data.is_empty() {
-                Input::message_coin_predicate(
-                    message.sender.into(),
-                    message.recipient.into(),
-                    message.amount,
-                    message.nonce,
-                    predicate.code().to_vec(),
-                    vec![],
-                )
-            } else {
-                Input::message_data_predicate(
-                    message.sender.into(),
-                    message.recipient.into(),
-                    message.amount,
-                    message.nonce,
-                    message.data,
-                    predicate.code().to_vec(),
-                    vec![],
-                )
-            }
-        })
-        .collect();
-
-    // Build contract inputs
-    let mut contract_inputs = vec![Input::contract(
-        UtxoId::new(Bytes32::zeroed(), 0u8),
-        Bytes32::zeroed(),
-        Bytes32::zeroed(),
-        TxPointer::default(),
-        test_contract_id.clone().into(),
-    )];
-
-    if let Some(id) = deposit_contract {
-        contract_inputs.push(Input::contract(
-            UtxoId::new(Bytes32::zeroed(), 0u8),
-            Bytes32::zeroed(),
-            Bytes32::zeroed(),
-            TxPointer::default(),
-            id,
-        ));
-    }
-
-    (
-        test_contract,
-        contract_inputs,
-        coin_inputs,
-        message_inputs,
-        test_contract_id,
-        provider,
-    )
+        .collect()
 }
 
 /// Relays a message-to-contract message
@@ -317,14 +619,16 @@ pub async fn relay_message_to_contract(
     optional_outputs: &[Output],
 ) -> Vec<Receipt> {
     // Build transaction
+    let script_bytecode = std::fs::read(CONTRACT_MESSAGE_SCRIPT_BINARY).unwrap();
     let mut tx = builder::build_contract_message_tx(
+        &script_bytecode,
         message,
         contracts,
-        gas_coins,
+        &RelayGas::from(gas_coins),
         optional_outputs,
-        TxParameters::default(),
+        TxParameters::new(0, RELAY_GAS_LIMIT, 0),
     )
-    .await;
+    .unwrap();
 
     // Sign transaction and call
     sign_and_call_tx(wallet, &mut tx).await
@@ -350,41 +654,85 @@ pub async fn precalculate_deposit_id() -> ContractId {
     compiled.contract_id()
 }
 
-/// Prefixes the given bytes with the test contract ID
-pub async fn prefix_contract_id(
-    mut data: Vec<u8>,
+/// Computes the test contract ID for the given configurables
+pub fn precalculate_test_contract_id(
     config: Option<BridgeFungibleTokenContractConfigurables>,
-) -> Vec<u8> {
-    // Compute the test contract ID
-    let compiled_contract = match config {
-        Some(c) => Contract::load_from(
-            TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
-            LoadConfiguration::default().set_configurables(c),
-        )
-        .unwrap(),
-        None => Contract::load_from(
-            TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
-            LoadConfiguration::default(),
-        )
-        .unwrap(),
-    };
+) -> ContractId {
+    BridgeClient::precompute_contract_id(TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY, config)
+        .unwrap()
+}
 
-    let test_contract_id = compiled_contract.contract_id();
+/// Computes the id of the non-fungible bridge deployed without configurables
+pub fn precalculate_non_fungible_contract_id() -> ContractId {
+    Contract::load_from(
+        BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .contract_id()
+}
 
-    // Turn contract id into array with the given data appended to it
-    let test_contract_id: [u8; 32] = test_contract_id.into();
-    let mut test_contract_id = test_contract_id.to_vec();
-    test_contract_id.append(&mut data);
-    test_contract_id
+/// A deposit of the token `token_id` of `token` for the non-fungible bridge deployed
+/// without configurables, the recipient kind is always given as the URI follows it
+pub fn construct_nft_msg_data(
+    token: &str,
+    from: &str,
+    to: [u8; 32],
+    token_id: Unsigned256,
+    uri: &str,
+    deposit_to_contract: bool,
+) -> (u64, Vec<u8>) {
+    let mut data = precalculate_non_fungible_contract_id().to_vec();
+    data.extend_from_slice(&Bits256::from_hex_str(token).unwrap().0);
+    data.extend_from_slice(&Bits256::from_hex_str(from).unwrap().0);
+    data.extend_from_slice(&to);
+    data.extend_from_slice(&encode_hex(token_id));
+    data.push(u8::from(deposit_to_contract));
+    data.extend_from_slice(uri.as_bytes());
+
+    (100, data)
+}
+
+/// Loads the contract message predicate and script used to relay deposits
+pub fn relay_bytecode() -> RelayBytecode {
+    RelayBytecode::load_from(
+        CONTRACT_MESSAGE_PREDICATE_BINARY,
+        CONTRACT_MESSAGE_SCRIPT_BINARY,
+    )
+    .unwrap()
+}
+
+/// Deploys the test contract with the given configurables and connects a bridge client to it
+pub async fn deploy_bridge_client(
+    wallet: WalletUnlocked,
+    configurables: Option<BridgeFungibleTokenContractConfigurables>,
+) -> BridgeClient {
+    BridgeClient::deploy(
+        wallet,
+        TEST_BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        configurables,
+        TxParameters::default(),
+        relay_bytecode(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Deploys the token registry, letting `owner` register bridge contracts
+pub async fn deploy_token_registry(wallet: WalletUnlocked, owner: Address) -> TokenRegistryClient {
+    TokenRegistryClient::deploy(
+        wallet,
+        TOKEN_REGISTRY_CONTRACT_BINARY,
+        owner,
+        TxParameters::default(),
+    )
+    .await
+    .unwrap()
 }
 
-/// Quickly converts the given hex string into a u8 vector
-pub fn decode_hex(s: &str) -> Vec<u8> {
-    let data: StdResult<Vec<u8>, ParseIntError> = (2..s.len())
-        .step_by(2)
-        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
-        .collect();
-    data.unwrap()
+/// Connects a bridge client to the test contract with the given wallet
+pub fn get_bridge_client(wallet: WalletUnlocked, contract_id: Bech32ContractId) -> BridgeClient {
+    BridgeClient::new(contract_id, wallet, relay_bytecode())
 }
 
 pub async fn get_fungible_token_instance(
@@ -444,43 +792,122 @@ pub async fn construct_msg_data(
     // TODO: https://github.com/FuelLabs/bridge-fungible-token/issues/61
     extra_data: Option<Vec<u8>>,
 ) -> ((u64, Vec<u8>), (u64, AssetId), Option<ContractId>) {
-    let mut message_data = Vec::with_capacity(5);
-    message_data.append(&mut decode_hex(token));
-    message_data.append(&mut decode_hex(from));
-    message_data.append(&mut to.to_vec());
-    message_data.append(&mut encode_hex(amount).to_vec());
-
-    let mut deposit_recipient: Option<ContractId> = None;
-
-    if deposit_to_contract {
-        let hash = keccak_hash("DEPOSIT_TO_CONTRACT");
-        let mut byte: Vec<u8> = vec![0u8];
-        byte.copy_from_slice(&hash[..1]);
-        message_data.append(&mut byte);
-        deposit_recipient = Option::Some(ContractId::new(to));
-    };
-
-    if let Some(mut data) = extra_data {
-        message_data.append(&mut data);
+    let token = Bits256::from_hex_str(token).unwrap();
+    let from = Bits256::from_hex_str(from).unwrap();
+    let extra_data = extra_data.unwrap_or_default();
+
+    let (deposit, deposit_recipient) = if deposit_to_contract {
+        let recipient = ContractId::new(to);
+        (
+            DepositMessage::to_contract(token, from, recipient, amount, extra_data),
+            Some(recipient),
+        )
+    } else {
+        let mut deposit = DepositMessage::to_address(token, from, Bits256(to), amount);
+        deposit.extra_data = extra_data;
+        (deposit, None)
     };
 
-    let message_data = prefix_contract_id(message_data, config).await;
+    let message_data = deposit.encode(precalculate_test_contract_id(config));
     let message = (100, message_data);
     let coin = (DEFAULT_COIN_AMOUNT, AssetId::default());
 
     (message, coin, deposit_recipient)
 }
 
+/// A message registering the token metadata on the test contract deployed without configurables
+pub fn construct_metadata_msg_data(
+    token: &str,
+    name: &str,
+    symbol: &str,
+    decimals: u8,
+) -> (u64, Vec<u8>) {
+    let token = Bits256::from_hex_str(token).unwrap();
+    let metadata = MetadataMessage::new(token, name, symbol, decimals).unwrap();
+
+    (100, metadata.encode(precalculate_test_contract_id(None)))
+}
+
+/// A message releasing locked coins of `asset` to the address `to` from the test contract
+/// deployed without configurables
+pub fn construct_release_msg_data(asset: AssetId, to: [u8; 32], amount: u64) -> (u64, Vec<u8>) {
+    let from =
+        Bits256::from_hex_str("0x0000000000000000000000008888888888888888888888888888888888888888")
+            .unwrap();
+    let release = ReleaseMessage::to_address(asset, from, Bits256(to), amount);
+
+    (100, release.encode(precalculate_test_contract_id(None)))
+}
+
 pub fn generate_variable_output() -> Vec<Output> {
     vec![Output::variable(Address::zeroed(), 0, AssetId::default())]
 }
 
-pub fn parse_output_message_data(data: &[u8]) -> (Vec<u8>, Bits256, Bits256, Unsigned256) {
-    let selector = &data[0..4];
-    let to: [u8; 32] = data[4..36].try_into().unwrap();
-    let token_array: [u8; 32] = data[36..68].try_into().unwrap();
-    let token = Bits256(token_array);
-    let amount_array: [u8; 32] = data[68..100].try_into().unwrap();
-    let amount: Unsigned256 = Unsigned256::from_big_endian(amount_array.as_ref());
-    (selector.to_vec(), Bits256(to), token, amount)
+/// Decodes a withdrawal message and checks it was sent from the test contract to the gateway
+pub fn decode_withdrawal_message(
+    receipt: &Receipt,
+    test_contract_id: &Bech32ContractId,
+) -> WithdrawalMessage {
+    WithdrawalMessage::try_from(receipt)
+        .unwrap()
+        .ensure_route(
+            test_contract_id.into(),
+            Address::from_str(MESSAGE_SENDER_ADDRESS).unwrap(),
+        )
+        .unwrap()
+}
+
+/// Loads the example deposit recorder, accepting deposits from the given bridge contract
+pub fn deposit_recorder(bridge: ContractId) -> Contract {
+    let configurables = DepositRecorderContractConfigurables::new().set_BRIDGE(Bits256(*bridge));
+    Contract::load_from(
+        DEPOSIT_RECORDER_CONTRACT_BINARY,
+        LoadConfiguration::default().set_configurables(configurables),
+    )
+    .unwrap()
+}
+
+/// Loads the example staking vault, accepting deposits from the given bridge contract
+pub fn staking_vault(bridge: ContractId) -> Contract {
+    let configurables = StakingVaultContractConfigurables::new().set_BRIDGE(Bits256(*bridge));
+    Contract::load_from(
+        STAKING_VAULT_CONTRACT_BINARY,
+        LoadConfiguration::default().set_configurables(configurables),
+    )
+    .unwrap()
+}
+
+/// Loads the example deposit forwarder, accepting deposits from the given bridge contract
+pub fn deposit_forwarder(bridge: ContractId) -> Contract {
+    let configurables = DepositForwarderContractConfigurables::new().set_BRIDGE(Bits256(*bridge));
+    Contract::load_from(
+        DEPOSIT_FORWARDER_CONTRACT_BINARY,
+        LoadConfiguration::default().set_configurables(configurables),
+    )
+    .unwrap()
+}
+
+/// What the malicious recipient test contract does when the bridge calls it back
+#[derive(Debug, Clone, Copy)]
+pub enum Attack {
+    /// Calls `process_message` on the bridge again with the same message
+    Reenter = 0,
+    /// Withdraws the tokens it was just sent
+    Withdraw = 1,
+    /// Loops until it runs out of gas
+    BurnGas = 2,
+    /// Reverts with code 42
+    Revert = 3,
+}
+
+/// Loads the malicious recipient test contract, making the given attack on the given bridge contract
+pub fn malicious_recipient(bridge: ContractId, attack: Attack) -> Contract {
+    let configurables = MaliciousRecipientContractConfigurables::new()
+        .set_BRIDGE(Bits256(*bridge))
+        .set_ATTACK(attack as u64);
+    Contract::load_from(
+        MALICIOUS_RECIPIENT_CONTRACT_BINARY,
+        LoadConfiguration::default().set_configurables(configurables),
+    )
+    .unwrap()
 }
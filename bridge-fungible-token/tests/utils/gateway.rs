@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use bridge_fungible_token_sdk::{DepositMessage, WithdrawalMessage, WithdrawalMessageError};
+use fuels::{
+    prelude::{Address, ContractId},
+    tx::{MessageId, Receipt},
+    types::Bits256,
+};
+use primitive_types::U256 as Unsigned256;
+
+/// Base asset attached to each deposit message, matching `construct_msg_data`
+const DEPOSIT_MESSAGE_AMOUNT: u64 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GatewayError {
+    InsufficientBalance {
+        token: Bits256,
+        account: Bits256,
+    },
+    /// The bridge contract is releasing more than was deposited through it
+    InsufficientCustody {
+        token: Bits256,
+        bridge_contract: ContractId,
+    },
+    AlreadyFinalized(MessageId),
+    InvalidMessage(WithdrawalMessageError),
+}
+
+/// A model of the base layer ERC-20 gateway, tracking token balances and what
+/// it holds in custody for each bridge contract
+pub struct MockGateway {
+    address: Address,
+    balances: HashMap<([u8; 32], [u8; 32]), Unsigned256>,
+    custody: HashMap<([u8; 32], ContractId), Unsigned256>,
+    finalized: HashSet<MessageId>,
+}
+
+impl MockGateway {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: Address::from_str(address).unwrap(),
+            balances: HashMap::new(),
+            custody: HashMap::new(),
+            finalized: HashSet::new(),
+        }
+    }
+
+    /// Credit a base layer account with tokens
+    pub fn mint(&mut self, token: Bits256, account: Bits256, amount: Unsigned256) {
+        *self.balances.entry((token.0, account.0)).or_default() += amount;
+    }
+
+    pub fn balance_of(&self, token: Bits256, account: Bits256) -> Unsigned256 {
+        self.balances
+            .get(&(token.0, account.0))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Tokens locked in the gateway for the given bridge contract
+    pub fn custodied(&self, token: Bits256, bridge_contract: ContractId) -> Unsigned256 {
+        self.custody
+            .get(&(token.0, bridge_contract))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Lock the deposited tokens and return the message sent to the bridge contract,
+    /// as `(amount, data)` for `BridgeTestEnvBuilder::message`
+    pub fn deposit(
+        &mut self,
+        bridge_contract: ContractId,
+        deposit: &DepositMessage,
+    ) -> Result<(u64, Vec<u8>), GatewayError> {
+        let balance = self
+            .balances
+            .entry((deposit.token.0, deposit.from.0))
+            .or_default();
+        if *balance < deposit.amount {
+            return Err(GatewayError::InsufficientBalance {
+                token: deposit.token,
+                account: deposit.from,
+            });
+        }
+        *balance -= deposit.amount;
+        *self
+            .custody
+            .entry((deposit.token.0, bridge_contract))
+            .or_default() += deposit.amount;
+
+        Ok((DEPOSIT_MESSAGE_AMOUNT, deposit.encode(bridge_contract)))
+    }
+
+    /// Release the tokens of a withdrawal or refund message sent by a bridge contract
+    pub fn finalize_withdrawal(
+        &mut self,
+        receipt: &Receipt,
+    ) -> Result<WithdrawalMessage, GatewayError> {
+        let message = WithdrawalMessage::try_from(receipt).map_err(GatewayError::InvalidMessage)?;
+        if message.recipient != self.address {
+            return Err(GatewayError::InvalidMessage(
+                WithdrawalMessageError::UnexpectedRecipient {
+                    expected: self.address,
+                    actual: message.recipient,
+                },
+            ));
+        }
+
+        let id = message.message_id();
+        if self.finalized.contains(&id) {
+            return Err(GatewayError::AlreadyFinalized(id));
+        }
+
+        let custodied = self
+            .custody
+            .entry((message.token.0, message.sender))
+            .or_default();
+        if *custodied < message.token_amount {
+            return Err(GatewayError::InsufficientCustody {
+                token: message.token,
+                bridge_contract: message.sender,
+            });
+        }
+        *custodied -= message.token_amount;
+        self.finalized.insert(id);
+        self.mint(message.token, message.to, message.token_amount);
+
+        Ok(message)
+    }
+}
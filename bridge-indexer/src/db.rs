@@ -0,0 +1,572 @@
+use std::path::Path;
+
+use fuels::prelude::ContractId;
+use fuels::tx::Bytes32;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    block_height INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS deposits (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS deposits_by_recipient ON deposits (recipient);
+CREATE TABLE IF NOT EXISTS withdrawals (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE TABLE IF NOT EXISTS refunds (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    originator TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS refunds_by_originator ON refunds (originator);
+CREATE TABLE IF NOT EXISTS refund_claims (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    originator TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE TABLE IF NOT EXISTS locks (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS locks_by_asset ON locks (asset);
+CREATE TABLE IF NOT EXISTS releases (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS releases_by_recipient ON releases (recipient);
+";
+
+/// Upgrades of databases created by earlier versions, the one at index `i` takes a
+/// database from `user_version` `i` to `i + 1`. A new database gets `SCHEMA` directly
+/// and starts at the latest version.
+const MIGRATIONS: &[&str] = &[
+    // 1: refund claims record the asset they release. Claims indexed before that are
+    // matched to the latest earlier refund of the same amount, as they used to be
+    "
+ALTER TABLE refund_claims ADD COLUMN asset TEXT NOT NULL DEFAULT '';
+UPDATE refund_claims SET asset = COALESCE((
+    SELECT r.asset FROM refunds r
+    WHERE r.contract_id = refund_claims.contract_id
+    AND r.originator = refund_claims.originator
+    AND r.amount = refund_claims.amount
+    AND (r.block_height, r.tx_index, r.receipt_index)
+        < (refund_claims.block_height, refund_claims.tx_index, refund_claims.receipt_index)
+    ORDER BY r.block_height DESC, r.tx_index DESC, r.receipt_index DESC
+    LIMIT 1
+), '');
+",
+    // 2: locks and releases of Fuel-native assets
+    "
+CREATE TABLE IF NOT EXISTS locks (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS locks_by_asset ON locks (asset);
+CREATE TABLE IF NOT EXISTS releases (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    sender TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE INDEX IF NOT EXISTS releases_by_recipient ON releases (recipient);
+",
+];
+
+/// Where an event was found on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventLocation {
+    pub tx_id: Bytes32,
+    pub block_height: u64,
+    /// Position of the transaction in the block
+    pub tx_index: usize,
+    /// Position of the receipt in the transaction
+    pub receipt_index: usize,
+    pub contract_id: ContractId,
+}
+
+/// A decoded bridge event, with ids and addresses as 0x prefixed hex and
+/// base layer amounts as decimal strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexedEvent {
+    Deposit {
+        location: EventLocation,
+        recipient: String,
+        sender: String,
+        amount: u64,
+    },
+    Withdrawal {
+        location: EventLocation,
+        recipient: String,
+        sender: String,
+        amount: u64,
+    },
+    RefundRegistered {
+        location: EventLocation,
+        originator: String,
+        asset: String,
+        amount: String,
+    },
+    /// A message sent by `claim_refund`, which logs no event of its own
+    RefundClaimed {
+        location: EventLocation,
+        originator: String,
+        asset: String,
+        amount: String,
+    },
+    /// Fuel-native coins locked for their wrapped token on the base layer
+    Lock {
+        location: EventLocation,
+        recipient: String,
+        sender: String,
+        asset: String,
+        amount: u64,
+    },
+    /// Locked coins released after their wrapped token was burned on the base layer
+    Release {
+        location: EventLocation,
+        recipient: String,
+        sender: String,
+        asset: String,
+        amount: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositRecord {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub contract_id: String,
+    pub recipient: String,
+    pub sender: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalRecord {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub contract_id: String,
+    pub recipient: String,
+    pub sender: String,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefundRecord {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub contract_id: String,
+    pub originator: String,
+    pub asset: String,
+    /// Amount in base layer decimals
+    pub amount: String,
+}
+
+/// A lock or release of a Fuel-native asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeAssetRecord {
+    pub tx_id: String,
+    pub block_height: u64,
+    pub contract_id: String,
+    pub recipient: String,
+    pub sender: String,
+    pub asset: String,
+    pub amount: u64,
+}
+
+/// Proxy token supply of a bridge contract at the end of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupplyPoint {
+    pub block_height: u64,
+    pub supply: u64,
+}
+
+pub struct IndexerDb {
+    conn: Connection,
+}
+
+impl IndexerDb {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self> {
+        let tx = conn.transaction()?;
+        let tables: u32 = tx.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+            [],
+            |row| row.get(0),
+        )?;
+        if tables == 0 {
+            tx.execute_batch(SCHEMA)?;
+        } else {
+            let version: u32 = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+            for migration in MIGRATIONS.iter().skip(version as usize) {
+                tx.execute_batch(migration)?;
+            }
+        }
+        tx.pragma_update(None, "user_version", MIGRATIONS.len() as u32)?;
+        tx.commit()?;
+        Ok(Self { conn })
+    }
+
+    /// The schema version of the database, the number of migrations applied to it
+    pub fn schema_version(&self) -> Result<u32> {
+        self.conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+    }
+
+    /// The last block that was indexed
+    pub fn indexed_height(&self) -> Result<Option<u64>> {
+        self.conn
+            .query_row("SELECT block_height FROM cursor WHERE id = 0", [], |row| {
+                row.get(0)
+            })
+            .optional()
+    }
+
+    /// Store the events of a block and move the cursor past it, all or nothing
+    pub fn insert_block(&mut self, block_height: u64, events: &[IndexedEvent]) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        for event in events {
+            match event {
+                IndexedEvent::Deposit {
+                    location,
+                    recipient,
+                    sender,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO deposits VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        recipient,
+                        sender,
+                        amount.to_string(),
+                    ],
+                )?,
+                IndexedEvent::Withdrawal {
+                    location,
+                    recipient,
+                    sender,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO withdrawals VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        recipient,
+                        sender,
+                        amount.to_string(),
+                    ],
+                )?,
+                IndexedEvent::RefundRegistered {
+                    location,
+                    originator,
+                    asset,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO refunds VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        originator,
+                        asset,
+                        amount,
+                    ],
+                )?,
+                IndexedEvent::RefundClaimed {
+                    location,
+                    originator,
+                    asset,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO refund_claims
+                     (tx_id, receipt_index, block_height, tx_index, contract_id, originator, asset, amount)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        originator,
+                        asset,
+                        amount,
+                    ],
+                )?,
+                IndexedEvent::Lock {
+                    location,
+                    recipient,
+                    sender,
+                    asset,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO locks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        recipient,
+                        sender,
+                        asset,
+                        amount.to_string(),
+                    ],
+                )?,
+                IndexedEvent::Release {
+                    location,
+                    recipient,
+                    sender,
+                    asset,
+                    amount,
+                } => tx.execute(
+                    "INSERT OR IGNORE INTO releases VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        hex(&location.tx_id),
+                        location.receipt_index,
+                        location.block_height,
+                        location.tx_index,
+                        hex(&location.contract_id),
+                        recipient,
+                        sender,
+                        asset,
+                        amount.to_string(),
+                    ],
+                )?,
+            };
+        }
+        tx.execute(
+            "INSERT INTO cursor VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET block_height = MAX(block_height, excluded.block_height)",
+            params![block_height],
+        )?;
+        tx.commit()
+    }
+
+    /// Every deposit minted to the given address or contract
+    pub fn deposits_to(&self, recipient: &str) -> Result<Vec<DepositRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tx_id, block_height, contract_id, recipient, sender, amount FROM deposits
+             WHERE recipient = ?1 ORDER BY block_height, tx_index, receipt_index",
+        )?;
+        let rows = stmt.query_map([recipient.to_lowercase()], |row| {
+            Ok(DepositRecord {
+                tx_id: row.get(0)?,
+                block_height: row.get(1)?,
+                contract_id: row.get(2)?,
+                recipient: row.get(3)?,
+                sender: row.get(4)?,
+                amount: amount(row, 5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Every withdrawal made by the given contract
+    pub fn withdrawals_from(&self, contract_id: &ContractId) -> Result<Vec<WithdrawalRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tx_id, block_height, contract_id, recipient, sender, amount FROM withdrawals
+             WHERE contract_id = ?1 ORDER BY block_height, tx_index, receipt_index",
+        )?;
+        let rows = stmt.query_map([hex(contract_id)], |row| {
+            Ok(WithdrawalRecord {
+                tx_id: row.get(0)?,
+                block_height: row.get(1)?,
+                contract_id: row.get(2)?,
+                recipient: row.get(3)?,
+                sender: row.get(4)?,
+                amount: amount(row, 5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Every lock of the given Fuel-native asset
+    pub fn locks_of(&self, asset: &str) -> Result<Vec<NativeAssetRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tx_id, block_height, contract_id, recipient, sender, asset, amount FROM locks
+             WHERE asset = ?1 ORDER BY block_height, tx_index, receipt_index",
+        )?;
+        let rows = stmt.query_map([asset.to_lowercase()], native_asset_record)?;
+        rows.collect()
+    }
+
+    /// Every release of locked coins to the given address or contract
+    pub fn releases_to(&self, recipient: &str) -> Result<Vec<NativeAssetRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tx_id, block_height, contract_id, recipient, sender, asset, amount FROM releases
+             WHERE recipient = ?1 ORDER BY block_height, tx_index, receipt_index",
+        )?;
+        let rows = stmt.query_map([recipient.to_lowercase()], native_asset_record)?;
+        rows.collect()
+    }
+
+    /// Refunds registered for the given base layer address that have not been claimed.
+    ///
+    /// A claim releases every refund of the same originator and asset registered before it.
+    pub fn outstanding_refunds(&self, originator: &str) -> Result<Vec<RefundRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT r.tx_id, r.block_height, r.contract_id, r.originator, r.asset, r.amount
+             FROM refunds r
+             WHERE r.originator = ?1
+             AND NOT EXISTS (
+                 SELECT 1 FROM refund_claims c
+                 WHERE c.contract_id = r.contract_id
+                 AND c.originator = r.originator
+                 AND c.asset = r.asset
+                 AND (c.block_height, c.tx_index, c.receipt_index)
+                     > (r.block_height, r.tx_index, r.receipt_index)
+             )
+             ORDER BY r.block_height, r.tx_index, r.receipt_index",
+        )?;
+        let rows = stmt.query_map([originator.to_lowercase()], |row| {
+            Ok(RefundRecord {
+                tx_id: row.get(0)?,
+                block_height: row.get(1)?,
+                contract_id: row.get(2)?,
+                originator: row.get(3)?,
+                asset: row.get(4)?,
+                amount: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// The proxy token supply of a bridge contract after every block that changed it
+    pub fn supply_history(&self, contract_id: &ContractId) -> Result<Vec<SupplyPoint>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT block_height, amount, 1 FROM deposits WHERE contract_id = ?1
+             UNION ALL
+             SELECT block_height, amount, 0 FROM withdrawals WHERE contract_id = ?1
+             ORDER BY block_height",
+        )?;
+        let changes = stmt
+            .query_map([hex(contract_id)], |row| {
+                Ok((
+                    row.get::<_, u64>(0)?,
+                    amount(row, 1)?,
+                    row.get::<_, bool>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut history: Vec<SupplyPoint> = vec![];
+        let mut supply = 0u64;
+        for (block_height, amount, minted) in changes {
+            supply = if minted {
+                supply.wrapping_add(amount)
+            } else {
+                supply.wrapping_sub(amount)
+            };
+            match history.last_mut() {
+                Some(point) if point.block_height == block_height => point.supply = supply,
+                _ => history.push(SupplyPoint {
+                    block_height,
+                    supply,
+                }),
+            }
+        }
+        Ok(history)
+    }
+}
+
+/// Format an id as 0x prefixed lowercase hex, the form every id is stored in
+pub fn hex(id: &[u8; 32]) -> String {
+    format!("{:#x}", Bytes32::new(*id))
+}
+
+fn native_asset_record(row: &Row) -> Result<NativeAssetRecord> {
+    Ok(NativeAssetRecord {
+        tx_id: row.get(0)?,
+        block_height: row.get(1)?,
+        contract_id: row.get(2)?,
+        recipient: row.get(3)?,
+        sender: row.get(4)?,
+        asset: row.get(5)?,
+        amount: amount(row, 6)?,
+    })
+}
+
+/// Amounts are stored as text since SQLite integers are signed
+fn amount(row: &Row, index: usize) -> Result<u64> {
+    let text: String = row.get(index)?;
+    text.parse().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
@@ -0,0 +1,227 @@
+use crate::db::{hex, EventLocation, IndexedEvent, IndexerDb};
+
+use std::collections::HashMap;
+
+use bridge_fungible_token_sdk::{
+    abi::BridgeFungibleTokenContract, BridgeEvents, WithdrawalMessage,
+};
+use fuel_core_types::fuel_tx::{Receipt, ScriptExecutionResult};
+use fuels::{
+    accounts::wallet::WalletUnlocked,
+    prelude::{ContractId, Provider},
+    programs::logs::LogDecoder,
+    tx::Bytes32,
+    types::{errors::Error as FuelsError, Bits256, Identity},
+};
+use primitive_types::U256 as Unsigned256;
+
+#[derive(Debug, thiserror::Error)]
+pub enum IndexerError {
+    #[error(transparent)]
+    Fuels(#[from] FuelsError),
+    #[error(transparent)]
+    Db(#[from] rusqlite::Error),
+    #[error("no block at height {0}")]
+    MissingBlock(u64),
+}
+
+/// Walks the blocks of a node and stores the events of the given bridge contracts
+pub struct Indexer {
+    provider: Provider,
+    log_decoders: HashMap<ContractId, LogDecoder>,
+    db: IndexerDb,
+}
+
+impl Indexer {
+    pub fn new(provider: Provider, bridge_contracts: &[ContractId], db: IndexerDb) -> Self {
+        // the log decoder only needs the contract id, the wallet is never used
+        let wallet = WalletUnlocked::new_random(None);
+        let log_decoders = bridge_contracts
+            .iter()
+            .map(|id| {
+                let contract = BridgeFungibleTokenContract::new(*id, wallet.clone());
+                (*id, contract.log_decoder())
+            })
+            .collect();
+
+        Self {
+            provider,
+            log_decoders,
+            db,
+        }
+    }
+
+    pub fn db(&self) -> &IndexerDb {
+        &self.db
+    }
+
+    /// Index every block after the last indexed one, or from `start_height` on
+    /// a fresh database, up to the latest block. Returns the number of blocks indexed
+    pub async fn sync(&mut self, start_height: u64) -> Result<u64, IndexerError> {
+        let from = match self.db.indexed_height()? {
+            Some(height) => height + 1,
+            None => start_height,
+        };
+        let latest = self.provider.latest_block_height().await?;
+
+        for height in from..=latest {
+            self.index_block(height).await?;
+        }
+        Ok((latest + 1).saturating_sub(from))
+    }
+
+    /// Decode and store the events of a single block
+    pub async fn index_block(&mut self, height: u64) -> Result<(), IndexerError> {
+        let block = self
+            .provider
+            .block_by_height(height)
+            .await?
+            .ok_or(IndexerError::MissingBlock(height))?;
+
+        let mut events = vec![];
+        for (tx_index, tx_id) in block.transactions.iter().enumerate() {
+            let receipts = self.provider.get_receipts(tx_id).await?;
+            // logs of reverted transactions never took effect
+            if !succeeded(&receipts) {
+                continue;
+            }
+            events.extend(self.decode_tx(*tx_id, height, tx_index, &receipts)?);
+        }
+
+        self.db.insert_block(height, &events)?;
+        Ok(())
+    }
+
+    fn decode_tx(
+        &self,
+        tx_id: Bytes32,
+        block_height: u64,
+        tx_index: usize,
+        receipts: &[Receipt],
+    ) -> Result<Vec<IndexedEvent>, IndexerError> {
+        let location = |receipt_index, contract_id| EventLocation {
+            tx_id,
+            block_height,
+            tx_index,
+            receipt_index,
+            contract_id,
+        };
+        let claimed =
+            |(receipt_index, message): (usize, WithdrawalMessage)| IndexedEvent::RefundClaimed {
+                location: location(receipt_index, message.sender),
+                originator: bits_hex(&message.to),
+                asset: bits_hex(&message.token),
+                amount: message.token_amount.to_string(),
+            };
+
+        // `withdraw` sends its message right before logging the withdrawal, a
+        // message that is not followed by a withdrawal was sent by `claim_refund`.
+        // Messages sent by `lock` call another gateway function and are never pending
+        let mut pending_messages: HashMap<ContractId, (usize, WithdrawalMessage)> = HashMap::new();
+        let mut events = vec![];
+
+        for (receipt_index, receipt) in receipts.iter().enumerate() {
+            let contract_id = match receipt {
+                Receipt::MessageOut { sender, .. } => ContractId::new(**sender),
+                Receipt::Log { id, .. } | Receipt::LogData { id, .. } => *id,
+                _ => continue,
+            };
+            let Some(log_decoder) = self.log_decoders.get(&contract_id) else {
+                continue;
+            };
+
+            if let Receipt::MessageOut { .. } = receipt {
+                if let Some(pending) = pending_messages.remove(&contract_id) {
+                    events.push(claimed(pending));
+                }
+                if let Ok(message) = WithdrawalMessage::try_from(receipt) {
+                    pending_messages.insert(contract_id, (receipt_index, message));
+                }
+                continue;
+            }
+
+            let decoded = BridgeEvents::decode(log_decoder, std::slice::from_ref(receipt))?;
+            for deposit in decoded.deposits {
+                events.push(IndexedEvent::Deposit {
+                    location: location(receipt_index, contract_id),
+                    recipient: identity_hex(&deposit.to),
+                    sender: bits_hex(&deposit.from),
+                    amount: deposit.amount,
+                });
+            }
+            for withdrawal in decoded.withdrawals {
+                pending_messages.remove(&contract_id);
+                events.push(IndexedEvent::Withdrawal {
+                    location: location(receipt_index, contract_id),
+                    recipient: bits_hex(&withdrawal.to),
+                    sender: identity_hex(&withdrawal.from),
+                    amount: withdrawal.amount,
+                });
+            }
+            for lock in decoded.locks {
+                events.push(IndexedEvent::Lock {
+                    location: location(receipt_index, contract_id),
+                    recipient: bits_hex(&lock.to),
+                    sender: identity_hex(&lock.from),
+                    asset: hex(&lock.asset),
+                    amount: lock.amount,
+                });
+            }
+            for release in decoded.releases {
+                events.push(IndexedEvent::Release {
+                    location: location(receipt_index, contract_id),
+                    recipient: identity_hex(&release.to),
+                    sender: bits_hex(&release.from),
+                    asset: hex(&release.asset),
+                    amount: release.amount,
+                });
+            }
+            for refund in decoded.refunds_registered {
+                events.push(IndexedEvent::RefundRegistered {
+                    location: location(receipt_index, contract_id),
+                    originator: bits_hex(&refund.from),
+                    asset: bits_hex(&refund.asset),
+                    amount: Unsigned256::from_big_endian(&refund.amount.0).to_string(),
+                });
+            }
+        }
+
+        events.extend(pending_messages.into_values().map(claimed));
+        events.sort_by_key(|event| location_of(event).receipt_index);
+        Ok(events)
+    }
+}
+
+fn succeeded(receipts: &[Receipt]) -> bool {
+    receipts.iter().any(|receipt| {
+        matches!(
+            receipt,
+            Receipt::ScriptResult {
+                result: ScriptExecutionResult::Success,
+                ..
+            }
+        )
+    })
+}
+
+fn location_of(event: &IndexedEvent) -> &EventLocation {
+    match event {
+        IndexedEvent::Deposit { location, .. }
+        | IndexedEvent::Withdrawal { location, .. }
+        | IndexedEvent::RefundRegistered { location, .. }
+        | IndexedEvent::RefundClaimed { location, .. }
+        | IndexedEvent::Lock { location, .. }
+        | IndexedEvent::Release { location, .. } => location,
+    }
+}
+
+fn bits_hex(bits: &Bits256) -> String {
+    hex(&bits.0)
+}
+
+fn identity_hex(identity: &Identity) -> String {
+    match identity {
+        Identity::Address(address) => hex(address),
+        Identity::ContractId(contract_id) => hex(contract_id),
+    }
+}
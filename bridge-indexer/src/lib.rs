@@ -0,0 +1,12 @@
+//! Indexes the events of bridge contracts into a SQLite database, so deposits, refunds,
+//! locks of Fuel-native assets and the proxy token supply can be queried without
+//! reading receipts.
+
+pub mod db;
+pub mod indexer;
+
+pub use db::{
+    DepositRecord, EventLocation, IndexedEvent, IndexerDb, NativeAssetRecord, RefundRecord,
+    SupplyPoint, WithdrawalRecord,
+};
+pub use indexer::{Indexer, IndexerError};
@@ -0,0 +1,143 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use bridge_indexer::{Indexer, IndexerDb};
+use clap::{Parser, Subcommand};
+use fuels::prelude::{ContractId, Provider};
+
+/// Indexes bridge contract events into SQLite and queries them
+#[derive(Parser)]
+struct Cli {
+    /// The SQLite database file
+    #[arg(long, global = true, default_value = "bridge-events.sqlite")]
+    database: PathBuf,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Follow a node and index the events of the given bridge contracts
+    Index {
+        /// URL of the fuel-core node
+        #[arg(long, default_value = "http://127.0.0.1:4000")]
+        node_url: String,
+        /// Bridge contract to index, can be repeated
+        #[arg(long = "bridge-contract", required = true)]
+        bridge_contracts: Vec<String>,
+        /// Height to start from when the database is empty
+        #[arg(long, default_value_t = 0)]
+        start_height: u64,
+        /// Seconds between polls of the node
+        #[arg(long, default_value_t = 5)]
+        poll_interval: u64,
+    },
+    /// List the deposits minted to an address or contract
+    Deposits {
+        #[arg(long)]
+        recipient: String,
+    },
+    /// List the refunds of a base layer address that have not been claimed
+    Refunds {
+        #[arg(long)]
+        originator: String,
+    },
+    /// List the locks of a Fuel-native asset
+    Locks {
+        #[arg(long)]
+        asset: String,
+    },
+    /// List the releases of locked coins to an address or contract
+    Releases {
+        #[arg(long)]
+        recipient: String,
+    },
+    /// Show the proxy token supply of a bridge contract over time
+    Supply {
+        #[arg(long)]
+        contract: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let db = IndexerDb::open(&cli.database)?;
+
+    match cli.command {
+        Command::Index {
+            node_url,
+            bridge_contracts,
+            start_height,
+            poll_interval,
+        } => {
+            let provider = Provider::connect(&node_url).await?;
+            let bridge_contracts = bridge_contracts
+                .iter()
+                .map(|id| ContractId::from_str(id))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut indexer = Indexer::new(provider, &bridge_contracts, db);
+            loop {
+                let indexed = indexer.sync(start_height).await?;
+                if indexed > 0 {
+                    println!(
+                        "indexed {indexed} blocks, up to height {}",
+                        indexer.db().indexed_height()?.unwrap_or_default()
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(poll_interval)).await;
+            }
+        }
+        Command::Deposits { recipient } => {
+            for deposit in db.deposits_to(&recipient)? {
+                println!(
+                    "{}\t{}\t{}\tfrom {}\t{}",
+                    deposit.block_height,
+                    deposit.tx_id,
+                    deposit.contract_id,
+                    deposit.sender,
+                    deposit.amount
+                );
+            }
+        }
+        Command::Refunds { originator } => {
+            for refund in db.outstanding_refunds(&originator)? {
+                println!(
+                    "{}\t{}\t{}\tasset {}\t{}",
+                    refund.block_height,
+                    refund.tx_id,
+                    refund.contract_id,
+                    refund.asset,
+                    refund.amount
+                );
+            }
+        }
+        Command::Locks { asset } => {
+            for lock in db.locks_of(&asset)? {
+                println!(
+                    "{}\t{}\t{}\tto {}\t{}",
+                    lock.block_height, lock.tx_id, lock.contract_id, lock.recipient, lock.amount
+                );
+            }
+        }
+        Command::Releases { recipient } => {
+            for release in db.releases_to(&recipient)? {
+                println!(
+                    "{}\t{}\t{}\tasset {}\t{}",
+                    release.block_height,
+                    release.tx_id,
+                    release.contract_id,
+                    release.asset,
+                    release.amount
+                );
+            }
+        }
+        Command::Supply { contract } => {
+            let contract_id = ContractId::from_str(&contract)?;
+            for point in db.supply_history(&contract_id)? {
+                println!("{}\t{}", point.block_height, point.supply);
+            }
+        }
+    }
+    Ok(())
+}
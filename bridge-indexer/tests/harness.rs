@@ -0,0 +1,194 @@
+mod utils {
+    pub mod environment;
+}
+
+use bridge_indexer::{db::hex, Indexer, IndexerDb, SupplyPoint};
+use fuels::{
+    accounts::{wallet::WalletUnlocked, ViewOnlyAccount},
+    prelude::{AssetId, TxParameters},
+    types::Bits256,
+};
+use primitive_types::U256 as Unsigned256;
+use utils::environment as env;
+
+#[tokio::test]
+async fn indexes_deposits_withdrawals_and_refunds() {
+    let mut wallet = WalletUnlocked::new_random(None);
+    let amount = Unsigned256::from(10).pow(Unsigned256::from(10));
+    let messages = vec![
+        env::deposit_to_address(wallet.address(), amount),
+        env::deposit_to_address(wallet.address(), env::overflowing_amount()),
+    ];
+
+    // Set up the environment and relay both messages
+    let client = env::setup(&mut wallet, messages).await;
+    let provider = wallet.provider().unwrap().clone();
+    env::relay_all(&client, &provider).await;
+
+    let mut indexer = Indexer::new(
+        provider.clone(),
+        &[client.contract_id()],
+        IndexerDb::open_in_memory().unwrap(),
+    );
+    indexer.sync(0).await.unwrap();
+
+    let minted = env::minted(amount);
+    let recipient = hex(&wallet.address().hash());
+    let deposits = indexer.db().deposits_to(&recipient).unwrap();
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].amount, minted);
+    assert_eq!(deposits[0].sender, env::FROM.to_lowercase());
+
+    let refunds = indexer.db().outstanding_refunds(env::FROM).unwrap();
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].asset, env::BRIDGED_TOKEN.to_lowercase());
+    assert_eq!(refunds[0].amount, env::overflowing_amount().to_string());
+
+    // Withdraw half of the deposit and claim the refund
+    client
+        .withdraw(
+            Bits256::from_hex_str(env::FROM).unwrap(),
+            minted / 2,
+            TxParameters::new(0, 30_000_000, 0),
+        )
+        .await
+        .unwrap();
+    client
+        .claim_refund(
+            Bits256::from_hex_str(env::FROM).unwrap(),
+            Bits256::from_hex_str(env::BRIDGED_TOKEN).unwrap(),
+        )
+        .await
+        .unwrap();
+    indexer.sync(0).await.unwrap();
+
+    assert!(indexer
+        .db()
+        .outstanding_refunds(env::FROM)
+        .unwrap()
+        .is_empty());
+    let withdrawals = indexer
+        .db()
+        .withdrawals_from(&client.contract_id())
+        .unwrap();
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!(withdrawals[0].amount, minted / 2);
+    assert_eq!(withdrawals[0].recipient, env::FROM.to_lowercase());
+
+    let history = indexer.db().supply_history(&client.contract_id()).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].supply, minted);
+    assert_eq!(
+        history.last(),
+        Some(&SupplyPoint {
+            block_height: withdrawals[0].block_height,
+            supply: minted - minted / 2,
+        })
+    );
+
+    // Nothing is indexed twice
+    assert_eq!(indexer.sync(0).await.unwrap(), 0);
+    assert_eq!(indexer.db().deposits_to(&recipient).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn indexes_locks_and_releases() {
+    let mut wallet = WalletUnlocked::new_random(None);
+    let recipient = WalletUnlocked::new_random(None);
+    let locked = 5_000;
+    let released = 3_000;
+    let messages = vec![env::release_to_address(
+        AssetId::default(),
+        recipient.address(),
+        released,
+    )];
+
+    // Lock the base asset, then relay the release of part of it
+    let client = env::setup(&mut wallet, messages).await;
+    let provider = wallet.provider().unwrap().clone();
+    client
+        .lock(
+            AssetId::default(),
+            Bits256::from_hex_str(env::FROM).unwrap(),
+            locked,
+            TxParameters::default(),
+        )
+        .await
+        .unwrap();
+    env::relay_all(&client, &provider).await;
+
+    let mut indexer = Indexer::new(
+        provider.clone(),
+        &[client.contract_id()],
+        IndexerDb::open_in_memory().unwrap(),
+    );
+    indexer.sync(0).await.unwrap();
+
+    let asset = hex(&AssetId::default());
+    let locks = indexer.db().locks_of(&asset).unwrap();
+    assert_eq!(locks.len(), 1);
+    assert_eq!(locks[0].amount, locked);
+    assert_eq!(locks[0].recipient, env::FROM.to_lowercase());
+    assert_eq!(locks[0].sender, hex(&wallet.address().hash()));
+
+    let releases = indexer
+        .db()
+        .releases_to(&hex(&recipient.address().hash()))
+        .unwrap();
+    assert_eq!(releases.len(), 1);
+    assert_eq!(releases[0].amount, released);
+    assert_eq!(releases[0].asset, asset);
+    assert_eq!(releases[0].sender, env::FROM.to_lowercase());
+}
+
+#[test]
+fn migrates_refund_claims_without_an_asset() {
+    let path =
+        std::env::temp_dir().join(format!("indexer-migration-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // A database written before refund claims recorded their asset
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "
+CREATE TABLE refunds (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    originator TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+CREATE TABLE refund_claims (
+    tx_id TEXT NOT NULL,
+    receipt_index INTEGER NOT NULL,
+    block_height INTEGER NOT NULL,
+    tx_index INTEGER NOT NULL,
+    contract_id TEXT NOT NULL,
+    originator TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (tx_id, receipt_index)
+);
+INSERT INTO refunds VALUES ('0x01', 0, 1, 0, '0xc0', '0xf0', '0xa1', '5');
+INSERT INTO refunds VALUES ('0x02', 0, 2, 0, '0xc0', '0xf0', '0xa2', '7');
+INSERT INTO refund_claims VALUES ('0x03', 0, 3, 0, '0xc0', '0xf0', '5');
+",
+    )
+    .unwrap();
+    drop(conn);
+
+    let db = IndexerDb::open(&path).unwrap();
+    assert_eq!(db.schema_version().unwrap(), 2);
+
+    // The claim is matched to the refund of the same amount and stays claimed
+    let refunds = db.outstanding_refunds("0xf0").unwrap();
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].asset, "0xa2");
+
+    // Opening again applies nothing more
+    drop(db);
+    assert_eq!(IndexerDb::open(&path).unwrap().schema_version().unwrap(), 2);
+}
@@ -0,0 +1,169 @@
+use std::str::FromStr;
+
+use bridge_fungible_token_sdk::{
+    decimals, BridgeClient, DepositMessage, RelayBytecode, ReleaseMessage,
+};
+use fuel_core_types::{
+    fuel_tx::{Input, TxPointer},
+    fuel_types::Word,
+};
+use fuels::{
+    accounts::{wallet::WalletUnlocked, ViewOnlyAccount},
+    prelude::{
+        setup_custom_assets_coins, setup_test_provider, Address, AssetConfig, AssetId,
+        Bech32Address, Config, Provider, TxParameters,
+    },
+    test_helpers::{setup_single_message, DEFAULT_COIN_AMOUNT},
+    types::{coin_type::CoinType, message::Message, Bits256},
+};
+use primitive_types::U256 as Unsigned256;
+
+const CONTRACT_MESSAGE_PREDICATE_BINARY: &str =
+    "../bridge-message-predicates/contract_message_predicate.bin";
+const CONTRACT_MESSAGE_SCRIPT_BINARY: &str =
+    "../bridge-message-predicates/contract_message_script.bin";
+const BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+    "../bridge-fungible-token/out/debug/bridge_fungible_token.bin";
+const MESSAGE_SENDER_ADDRESS: &str =
+    "0x00000000000000000000000096c53cd98B7297564716a8f2E1de2C83928Af2fe";
+
+/// The token and decimals of the bridge contract deployed without configurables
+pub const BRIDGED_TOKEN: &str =
+    "0x00000000000000000000000000000000000000000000000000000000deadbeef";
+pub const BRIDGED_TOKEN_DECIMALS: u8 = 18;
+pub const PROXY_TOKEN_DECIMALS: u8 = 9;
+pub const FROM: &str = "0x0000000000000000000000008888888888888888888888888888888888888888";
+
+/// Base asset attached to every message from the gateway
+const MESSAGE_AMOUNT: Word = 100;
+
+/// Starts a node with a base asset coin for `wallet` and the given message data sent by
+/// the gateway to the contract message predicate, then deploys the bridge contract
+/// without configurables
+pub async fn setup(wallet: &mut WalletUnlocked, messages: Vec<Vec<u8>>) -> BridgeClient {
+    let coins = setup_custom_assets_coins(
+        wallet.address(),
+        &[AssetConfig {
+            id: AssetId::default(),
+            num_coins: 1,
+            coin_amount: DEFAULT_COIN_AMOUNT,
+        }],
+    );
+
+    let sender = Address::from_str(MESSAGE_SENDER_ADDRESS).unwrap();
+    let relay = relay_bytecode();
+    let messages: Vec<Message> = messages
+        .into_iter()
+        .enumerate()
+        .map(|(nonce, data)| {
+            setup_single_message(
+                &sender.into(),
+                &relay.predicate_root(),
+                MESSAGE_AMOUNT,
+                (nonce as Word).into(),
+                data,
+            )
+        })
+        .collect();
+
+    let (provider, _) =
+        setup_test_provider(coins, messages, Some(Config::local_node()), None).await;
+    wallet.set_provider(provider);
+
+    BridgeClient::deploy(
+        wallet.clone(),
+        BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        None,
+        TxParameters::default(),
+        relay,
+    )
+    .await
+    .unwrap()
+}
+
+/// Relays every message waiting at the contract message predicate, paying with the
+/// client's wallet
+pub async fn relay_all(client: &BridgeClient, provider: &Provider) {
+    let messages = provider
+        .get_messages(&client.relay_bytecode().predicate_root())
+        .await
+        .unwrap();
+    for message in &messages {
+        client
+            .relay(
+                message,
+                None,
+                gas_coins(client.wallet()).await,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+    }
+}
+
+/// The wallet's base asset coins, as inputs paying for a relay
+async fn gas_coins(wallet: &WalletUnlocked) -> Vec<Input> {
+    wallet
+        .get_spendable_resources(AssetId::default(), 1)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter_map(|resource| match resource {
+            CoinType::Coin(coin) => Some(Input::coin_signed(
+                coin.utxo_id,
+                coin.owner.into(),
+                coin.amount,
+                coin.asset_id,
+                TxPointer::default(),
+                0,
+                coin.maturity.into(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn relay_bytecode() -> RelayBytecode {
+    RelayBytecode::load_from(
+        CONTRACT_MESSAGE_PREDICATE_BINARY,
+        CONTRACT_MESSAGE_SCRIPT_BINARY,
+    )
+    .unwrap()
+}
+
+/// A deposit of `amount` of the bridged token to the address `to`
+pub fn deposit_to_address(to: &Bech32Address, amount: Unsigned256) -> Vec<u8> {
+    let bridge_contract_id =
+        BridgeClient::precompute_contract_id(BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY, None).unwrap();
+    DepositMessage::to_address(
+        Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+        Bits256::from_hex_str(FROM).unwrap(),
+        Bits256(*to.hash()),
+        amount,
+    )
+    .encode(bridge_contract_id)
+}
+
+/// A release of `amount` of the locked Fuel-native `asset` to the address `to`
+pub fn release_to_address(asset: AssetId, to: &Bech32Address, amount: u64) -> Vec<u8> {
+    let bridge_contract_id =
+        BridgeClient::precompute_contract_id(BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY, None).unwrap();
+    ReleaseMessage::to_address(
+        asset,
+        Bits256::from_hex_str(FROM).unwrap(),
+        Bits256(*to.hash()),
+        amount,
+    )
+    .encode(bridge_contract_id)
+}
+
+/// The proxy tokens minted for a deposit of `amount`
+pub fn minted(amount: Unsigned256) -> u64 {
+    decimals::adjust_deposit_decimals(amount, PROXY_TOKEN_DECIMALS, BRIDGED_TOKEN_DECIMALS).unwrap()
+}
+
+/// The smallest deposit that overflows a u64 of proxy tokens, it is refunded
+pub fn overflowing_amount() -> Unsigned256 {
+    (Unsigned256::from(u64::MAX) + 1)
+        * decimals::pow_10(BRIDGED_TOKEN_DECIMALS - PROXY_TOKEN_DECIMALS)
+}
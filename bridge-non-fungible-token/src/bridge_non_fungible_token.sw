@@ -0,0 +1,212 @@
+contract;
+
+mod data;
+mod errors;
+mod events;
+mod utils;
+
+use contract_message_receiver::MessageReceiver;
+use reentrancy::reentrancy_guard;
+use errors::BridgeNonFungibleTokenError;
+use events::{DepositEvent, RefundRegisteredEvent, WithdrawalEvent};
+use std::{
+    bytes::Bytes,
+    call_frames::{
+        contract_id,
+        msg_asset_id,
+    },
+    context::msg_amount,
+    inputs::{
+        input_message_data_length,
+        input_message_sender,
+    },
+    message::send_message,
+    storage::storage_bytes::*,
+    token::{
+        burn,
+        mint,
+        transfer,
+    },
+};
+use utils::{encode_data, parse_message_data, URI_OFFSET};
+
+// There is a single asset per contract, as minting sub-assets is not available at this
+// toolchain version, so each contract bridges one token id of a collection and mints a supply
+// of 1 while it is bridged.
+storage {
+    refunds: StorageMap<(b256, b256, b256), bool> = StorageMap {},
+    minted: bool = false,
+    token_uri: StorageBytes = StorageBytes {},
+}
+
+configurable {
+    BRIDGED_TOKEN_GATEWAY: b256 = 0x00000000000000000000000096c53cd98B7297564716a8f2E1de2C83928Af2fe,
+    BRIDGED_TOKEN: b256 = 0x00000000000000000000000000000000000000000000000000000000deadbeef,
+    BRIDGED_TOKEN_ID: b256 = 0x0000000000000000000000000000000000000000000000000000000000000001,
+    NAME: str[64] = "MY_NFT                                                          ",
+    SYMBOL: str[32] = "MYNFT                           ",
+}
+
+abi NonFungibleBridge {
+    /// Claim a refund for an incorrectly bridged token if one has been registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `originator` - the address entitled to a refund
+    /// * `token` - the collection of the token to be refunded back to the originator
+    /// * `token_id` - the id of the token to be refunded back to the originator
+    #[storage(read, write)]
+    fn claim_refund(originator: b256, token: b256, token_id: b256);
+
+    /// Withdraw the token back to the base layer and burn its proxy coin.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - the address which is the destination of the transfer
+    ///
+    /// # Reverts
+    ///
+    /// * When no coins were sent with call
+    /// * When the wrong asset was sent with the call
+    #[payable]
+    #[storage(read, write)]
+    fn withdraw(to: b256);
+
+    /// Get the bridged collection
+    fn bridged_token() -> b256;
+
+    /// Get the id of the bridged token within its collection
+    fn bridged_token_id() -> b256;
+
+    /// Get the address of the gateway that holds the bridged tokens
+    fn bridged_token_gateway() -> b256;
+
+    /// Get the URI sent with the last deposit, empty before the first one
+    #[storage(read)]
+    fn token_uri() -> Bytes;
+
+    /// Get the supply of the proxy coin, 1 while the token is bridged
+    #[storage(read)]
+    fn total_supply() -> u64;
+
+    /// Get the name of the collection
+    fn name() -> str[64];
+
+    /// Get the symbol of the collection
+    fn symbol() -> str[32];
+}
+
+// Implement the process_message function required to be a message receiver
+impl MessageReceiver for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn process_message(msg_idx: u8) {
+        // Protect against reentrancy attacks that could allow replaying messages
+        reentrancy_guard();
+
+        let input_sender = input_message_sender(msg_idx);
+        require(input_sender.value == BRIDGED_TOKEN_GATEWAY, BridgeNonFungibleTokenError::UnauthorizedSender);
+
+        // the data is read at fixed offsets up to the URI
+        require(input_message_data_length(msg_idx) >= URI_OFFSET, BridgeNonFungibleTokenError::MessageDataTooShort);
+
+        let message_data = parse_message_data(msg_idx);
+
+        // register a refund if the token is not the one bridged by this contract, or it is already bridged
+        if (message_data.token != BRIDGED_TOKEN || message_data.token_id != BRIDGED_TOKEN_ID || storage.minted.read()) {
+            register_refund(message_data.from, message_data.token, message_data.token_id);
+            return;
+        };
+
+        // mint the single proxy coin & update storage
+        storage.minted.write(true);
+        storage.token_uri.write_slice(message_data.uri);
+        mint(1);
+        // a contract recipient only receives the coin, the URI leaves no room for extra data to
+        // request a call to its `process_message`
+        transfer(1, contract_id(), message_data.to);
+
+        log(DepositEvent {
+            to: message_data.to,
+            from: message_data.from,
+            token_id: message_data.token_id,
+        });
+    }
+}
+
+impl NonFungibleBridge for Contract {
+    #[storage(read, write)]
+    fn claim_refund(originator: b256, token: b256, token_id: b256) {
+        let key = (originator, token, token_id);
+        require(storage.refunds.get(key).try_read().unwrap_or(false), BridgeNonFungibleTokenError::NoRefundAvailable);
+
+        // reset the refund
+        storage.refunds.insert(key, false);
+
+        // send a message to unlock the refunded token on the base layer gateway contract
+        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(originator, token_id, token), 0);
+    }
+
+    #[payable]
+    #[storage(read, write)]
+    fn withdraw(to: b256) {
+        let amount = msg_amount();
+        let origin_contract_id = msg_asset_id();
+        require(amount != 0, BridgeNonFungibleTokenError::NoCoinsSent);
+        require(origin_contract_id == contract_id(), BridgeNonFungibleTokenError::IncorrectAssetDeposited);
+
+        // burn the proxy coin, at most one exists
+        storage.minted.write(false);
+        burn(amount);
+
+        // send a message to unlock the token on the base layer gateway contract
+        let sender = msg_sender().unwrap();
+        send_message(BRIDGED_TOKEN_GATEWAY, encode_data(to, BRIDGED_TOKEN_ID, BRIDGED_TOKEN), 0);
+        log(WithdrawalEvent {
+            to: to,
+            from: sender,
+            token_id: BRIDGED_TOKEN_ID,
+        });
+    }
+
+    fn bridged_token() -> b256 {
+        BRIDGED_TOKEN
+    }
+
+    fn bridged_token_id() -> b256 {
+        BRIDGED_TOKEN_ID
+    }
+
+    fn bridged_token_gateway() -> b256 {
+        BRIDGED_TOKEN_GATEWAY
+    }
+
+    #[storage(read)]
+    fn token_uri() -> Bytes {
+        storage.token_uri.read_slice().unwrap_or(Bytes::new())
+    }
+
+    #[storage(read)]
+    fn total_supply() -> u64 {
+        if storage.minted.read() { 1 } else { 0 }
+    }
+
+    fn name() -> str[64] {
+        NAME
+    }
+
+    fn symbol() -> str[32] {
+        SYMBOL
+    }
+}
+
+// Storage-dependant private functions
+#[storage(read, write)]
+fn register_refund(from: b256, token: b256, token_id: b256) {
+    storage.refunds.insert((from, token, token_id), true);
+    log(RefundRegisteredEvent {
+        from,
+        token,
+        token_id,
+    });
+}
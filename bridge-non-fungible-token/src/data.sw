@@ -0,0 +1,11 @@
+library;
+
+use std::bytes::Bytes;
+
+pub struct MessageData {
+    token: b256,
+    from: b256,
+    to: Identity,
+    token_id: b256,
+    uri: Bytes,
+}
@@ -0,0 +1,9 @@
+library;
+
+pub enum BridgeNonFungibleTokenError {
+    UnauthorizedSender: (),
+    IncorrectAssetDeposited: (),
+    NoCoinsSent: (),
+    NoRefundAvailable: (),
+    MessageDataTooShort: (),
+}
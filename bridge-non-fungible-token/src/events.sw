@@ -0,0 +1,19 @@
+library;
+
+pub struct RefundRegisteredEvent {
+    from: b256,
+    token: b256,
+    token_id: b256,
+}
+
+pub struct DepositEvent {
+    to: Identity,
+    from: b256,
+    token_id: b256,
+}
+
+pub struct WithdrawalEvent {
+    to: b256,
+    from: Identity,
+    token_id: b256,
+}
@@ -0,0 +1,63 @@
+library;
+
+use std::{
+    bytes::Bytes,
+    inputs::{
+        input_message_data,
+        input_message_data_length,
+    },
+};
+
+use ::data::MessageData;
+
+/// Offset of the token URI in a deposit: 32 + 32 + 32 + 32 + 32 + 1
+pub const URI_OFFSET: u64 = 161;
+
+/// Read the bytes passed as message data into an in-memory representation using the MessageData type.
+/// note: callers must check the message is at least `URI_OFFSET` bytes long first
+pub fn parse_message_data(msg_idx: u8) -> MessageData {
+    let to: b256 = input_message_data(msg_idx, 32 + 32 + 32).into();
+
+    // unlike fungible deposits the recipient kind is always given, as the URI makes the length vary
+    let deposit_to_contract = input_message_data(msg_idx, 32 + 32 + 32 + 32 + 32).get(0).unwrap() != 0u8;
+
+    let len = input_message_data_length(msg_idx);
+    let mut uri = Bytes::new();
+    if (len > URI_OFFSET) {
+        // only keep the bytes belonging to the message
+        let (data, _) = input_message_data(msg_idx, URI_OFFSET).split_at(len - URI_OFFSET);
+        uri = data;
+    }
+
+    MessageData {
+        token: input_message_data(msg_idx, 32).into(),
+        from: input_message_data(msg_idx, 32 + 32).into(),
+        to: if deposit_to_contract {
+            Identity::ContractId(ContractId::from(to))
+        } else {
+            Identity::Address(Address::from(to))
+        },
+        token_id: input_message_data(msg_idx, 32 + 32 + 32 + 32).into(),
+        uri,
+    }
+}
+
+/// Encode the data to be passed out of the contract when sending a message
+pub fn encode_data(to: b256, token_id: b256, bridged_token: b256) -> Bytes {
+    // capacity is 4 + 32 + 32 + 32 = 100
+    let mut data = Bytes::with_capacity(100);
+
+    // first, we push the selector 1 byte at a time
+    // the function selector for finalizeWithdrawal on the base layer ERC-721 gateway contract:
+    // finalizeWithdrawal(address,address,uint256) = 0x53ef1461
+    data.push(0x53u8);
+    data.push(0xefu8);
+    data.push(0x14u8);
+    data.push(0x61u8);
+
+    data.append(Bytes::from(to));
+    data.append(Bytes::from(bridged_token));
+    data.append(Bytes::from(token_id));
+
+    data
+}
@@ -0,0 +1,10 @@
+//! Relays deposit messages waiting at the contract message predicate to the
+//! bridge contracts they target.
+
+pub mod plan;
+pub mod relayer;
+pub mod store;
+
+pub use plan::{RelayPlan, SkipReason};
+pub use relayer::{PollSummary, Relayer, RelayerConfig, RelayerError, Sponsor};
+pub use store::{RelayRecord, RelayStore};
@@ -0,0 +1,133 @@
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use bridge_fungible_token_sdk::RelayBytecode;
+use bridge_relayer::{PollSummary, RelayStore, Relayer, RelayerConfig, Sponsor};
+use clap::Parser;
+use fuels::{
+    accounts::{fuel_crypto::SecretKey, predicate::Predicate, wallet::WalletUnlocked},
+    prelude::{Address, ContractId, Provider, TxParameters},
+};
+
+/// Relays pending deposit messages from the contract message predicate to the bridge contracts
+#[derive(Parser)]
+struct Args {
+    /// URL of the fuel-core node
+    #[arg(long, default_value = "http://127.0.0.1:4000")]
+    node_url: String,
+    /// Secret key of the wallet submitting relays, it pays for them unless there is a sponsor
+    #[arg(long, env = "RELAYER_SECRET_KEY", hide_env_values = true)]
+    secret_key: String,
+    /// Bridge contract to relay deposits for, can be repeated
+    #[arg(
+        long = "bridge-contract",
+        required_unless_present = "non_fungible_contracts"
+    )]
+    bridge_contracts: Vec<String>,
+    /// Non-fungible bridge contract to relay deposits for, can be repeated
+    #[arg(long = "non-fungible-contract")]
+    non_fungible_contracts: Vec<String>,
+    #[arg(
+        long,
+        default_value = "bridge-message-predicates/contract_message_predicate.bin"
+    )]
+    predicate: PathBuf,
+    #[arg(
+        long,
+        default_value = "bridge-message-predicates/contract_message_script.bin"
+    )]
+    script: PathBuf,
+    /// File recording which messages have been relayed
+    #[arg(long, default_value = "relayer-state.json")]
+    state: PathBuf,
+    /// Seconds between polls of the node
+    #[arg(long, default_value_t = 5)]
+    poll_interval: u64,
+    /// Attempts per message before giving up on it
+    #[arg(long, default_value_t = 5)]
+    max_attempts: u32,
+    /// Seconds before retrying a failed message, doubled after every further failure
+    #[arg(long, default_value_t = 10)]
+    retry_backoff: u64,
+    /// The longest wait between two attempts of a message, in seconds
+    #[arg(long, default_value_t = 600)]
+    max_retry_backoff: u64,
+    #[arg(long, default_value_t = 1)]
+    gas_price: u64,
+    /// Total amount of base asset that may be spent on fees
+    #[arg(long)]
+    gas_budget: u64,
+    /// Predicate whose coins pay for relays instead of the wallet
+    #[arg(long)]
+    sponsor_predicate: Option<PathBuf>,
+    /// Address receiving unspent gas from the sponsor, defaults to the sponsor predicate root
+    #[arg(long, requires = "sponsor_predicate")]
+    sponsor_change_to: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let provider = Provider::connect(&args.node_url).await?;
+    let wallet = WalletUnlocked::new_from_private_key(
+        SecretKey::from_str(&args.secret_key)?,
+        Some(provider),
+    );
+    let bridge_contracts = args
+        .bridge_contracts
+        .iter()
+        .map(|id| ContractId::from_str(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let non_fungible_contracts = args
+        .non_fungible_contracts
+        .iter()
+        .map(|id| ContractId::from_str(id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sponsor = match &args.sponsor_predicate {
+        Some(path) => {
+            let predicate = std::fs::read(path)?;
+            let change_to = match &args.sponsor_change_to {
+                Some(address) => Address::from_str(address)?,
+                None => Predicate::from_code(predicate.clone()).address().into(),
+            };
+            Some(Sponsor {
+                predicate,
+                predicate_data: vec![],
+                change_to,
+            })
+        }
+        None => None,
+    };
+
+    let config = RelayerConfig {
+        bridge_contracts,
+        non_fungible_contracts,
+        max_attempts: args.max_attempts,
+        retry_backoff: Duration::from_secs(args.retry_backoff),
+        max_retry_backoff: Duration::from_secs(args.max_retry_backoff),
+        tx_params: TxParameters::default().set_gas_price(args.gas_price),
+        gas_budget: args.gas_budget,
+        sponsor,
+    };
+    let relay = RelayBytecode::load_from(&args.predicate, &args.script)?;
+    let store = RelayStore::open(&args.state)?;
+
+    let mut relayer = Relayer::new(wallet, relay, config, store);
+    loop {
+        let summary = relayer.poll().await?;
+        if summary != PollSummary::default() {
+            println!(
+                "relayed {}, failed {}, skipped {}, fees spent {}",
+                summary.relayed,
+                summary.failed,
+                summary.skipped,
+                relayer.store().fees_spent
+            );
+        }
+        if summary.budget_exhausted {
+            eprintln!("gas budget of {} exhausted", args.gas_budget);
+        }
+        tokio::time::sleep(Duration::from_secs(args.poll_interval)).await;
+    }
+}
@@ -0,0 +1,132 @@
+use bridge_fungible_token_sdk::deposit::{
+    metadata_message_marker, release_message_marker, DEPOSIT_TO_ADDRESS_DATA_LEN,
+    METADATA_MESSAGE_DATA_LEN,
+};
+use fuels::prelude::ContractId;
+
+/// Offset of the deposit recipient in the message data
+const RECIPIENT_OFFSET: usize = 96;
+/// Offset of the word holding a deposit's token or the marker of another message type
+const MESSAGE_TYPE_OFFSET: usize = 32;
+/// Offset of the byte telling whether a non-fungible deposit goes to a contract, the
+/// token URI follows it
+const NON_FUNGIBLE_RECIPIENT_KIND_OFFSET: usize = 160;
+
+/// Why a message is never going to be relayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The data is too short to hold a target contract and a deposit, release or metadata,
+    /// or a non-fungible deposit without its recipient kind
+    Malformed { len: usize },
+    /// The target contract is not one of the bridge contracts served by this relayer
+    UnknownContract(ContractId),
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Malformed { len } => write!(f, "malformed message of {len} bytes"),
+            SkipReason::UnknownContract(id) => write!(f, "unknown target contract {id}"),
+        }
+    }
+}
+
+/// What a relay transaction for a bridge message needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayPlan {
+    /// The bridge contract named in the first 32 bytes of the message data
+    pub bridge_contract: ContractId,
+    /// The contract receiving the minted or released coins, when sending to a contract
+    pub recipient_contract: Option<ContractId>,
+    /// Number of variable outputs needed to transfer the minted or released coins to an address
+    pub variable_outputs: usize,
+}
+
+impl RelayPlan {
+    /// Work out the inputs and outputs needed to relay a message with the given data
+    pub fn for_message(data: &[u8]) -> Result<Self, SkipReason> {
+        if data.len() < DEPOSIT_TO_ADDRESS_DATA_LEN {
+            return Err(SkipReason::Malformed { len: data.len() });
+        }
+
+        let bridge_contract = target_contract(data).unwrap();
+        let message_type = &data[MESSAGE_TYPE_OFFSET..MESSAGE_TYPE_OFFSET + 32];
+
+        // a metadata message only touches the bridge contract
+        if message_type == metadata_message_marker() {
+            if data.len() < METADATA_MESSAGE_DATA_LEN {
+                return Err(SkipReason::Malformed { len: data.len() });
+            }
+            return Ok(Self {
+                bridge_contract,
+                recipient_contract: None,
+                variable_outputs: 0,
+            });
+        }
+
+        // a release message has the fields of a deposit after its marker
+        let fields_offset = if message_type == release_message_marker() {
+            32
+        } else {
+            0
+        };
+        let len = data.len() - fields_offset;
+        if len < DEPOSIT_TO_ADDRESS_DATA_LEN {
+            return Err(SkipReason::Malformed { len: data.len() });
+        }
+
+        // when depositing or releasing to an address, the fields are 160 bytes.
+        // when depositing or releasing to a contract, they are 161 bytes or more
+        // and the contract has to be an input of the transaction.
+        if len == DEPOSIT_TO_ADDRESS_DATA_LEN {
+            Ok(Self {
+                bridge_contract,
+                recipient_contract: None,
+                variable_outputs: 1,
+            })
+        } else {
+            let recipient_offset = fields_offset + RECIPIENT_OFFSET;
+            let recipient = data[recipient_offset..recipient_offset + 32]
+                .try_into()
+                .unwrap();
+            Ok(Self {
+                bridge_contract,
+                recipient_contract: Some(ContractId::new(recipient)),
+                variable_outputs: 0,
+            })
+        }
+    }
+
+    /// Work out the inputs and outputs needed to relay a deposit to a non-fungible bridge.
+    ///
+    /// Its data always gives the recipient kind, as the token URI after it makes the length vary.
+    pub fn for_non_fungible_message(data: &[u8]) -> Result<Self, SkipReason> {
+        if data.len() <= NON_FUNGIBLE_RECIPIENT_KIND_OFFSET {
+            return Err(SkipReason::Malformed { len: data.len() });
+        }
+
+        let bridge_contract = target_contract(data).unwrap();
+        if data[NON_FUNGIBLE_RECIPIENT_KIND_OFFSET] == 0 {
+            Ok(Self {
+                bridge_contract,
+                recipient_contract: None,
+                variable_outputs: 1,
+            })
+        } else {
+            let recipient = data[RECIPIENT_OFFSET..RECIPIENT_OFFSET + 32]
+                .try_into()
+                .unwrap();
+            Ok(Self {
+                bridge_contract,
+                recipient_contract: Some(ContractId::new(recipient)),
+                variable_outputs: 0,
+            })
+        }
+    }
+}
+
+/// The contract named in the first 32 bytes of the message data, `None` when it is shorter
+pub fn target_contract(data: &[u8]) -> Option<ContractId> {
+    let id: [u8; 32] = data.get(..32)?.try_into().unwrap();
+    Some(ContractId::new(id))
+}
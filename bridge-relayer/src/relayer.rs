@@ -0,0 +1,331 @@
+use crate::{
+    plan::{target_contract, RelayPlan, SkipReason},
+    store::{RelayRecord, RelayStore},
+};
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+
+use bridge_fungible_token_sdk::{
+    builder::{self, GasEstimate, RelayGas},
+    client::contract_input,
+    RelayBytecode,
+};
+use fuel_core_types::fuel_tx::{Input, Output, Receipt, TxPointer};
+use fuels::{
+    accounts::{wallet::WalletUnlocked, Signer, ViewOnlyAccount},
+    prelude::{Address, AssetId, ContractId, ScriptTransaction, TxParameters},
+    types::{coin_type::CoinType, errors::Error as FuelsError, message::Message},
+};
+
+#[derive(Debug, Clone)]
+pub struct RelayerConfig {
+    /// The bridge contracts this relayer serves, messages for any other contract are skipped
+    pub bridge_contracts: Vec<ContractId>,
+    /// The non-fungible bridge contracts this relayer serves, their deposits carry a token URI
+    pub non_fungible_contracts: Vec<ContractId>,
+    /// How many times a message is attempted before it is given up on
+    pub max_attempts: u32,
+    /// How long to wait before retrying a failed message, doubled after every further failure
+    pub retry_backoff: Duration,
+    /// The longest wait between two attempts of a message
+    pub max_retry_backoff: Duration,
+    /// Gas price of the relay transactions
+    pub tx_params: TxParameters,
+    /// Total amount of base asset the relayer may spend on fees
+    pub gas_budget: u64,
+    /// Pays the fees instead of the relayer wallet when set
+    pub sponsor: Option<Sponsor>,
+}
+
+/// A predicate whose coins pay the relay fees
+/// note: it must only accept a change output to itself, or any relay can take its coins
+#[derive(Debug, Clone)]
+pub struct Sponsor {
+    pub predicate: Vec<u8>,
+    pub predicate_data: Vec<u8>,
+    /// Receives the unspent gas, usually the predicate root itself
+    pub change_to: Address,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RelayerError {
+    #[error(transparent)]
+    Fuels(#[from] FuelsError),
+    #[error("failed to persist the relayer state: {0}")]
+    Store(#[from] std::io::Error),
+}
+
+/// What happened during a single poll of the node
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PollSummary {
+    pub relayed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Set when a message was left pending because relaying it could exceed the gas budget
+    pub budget_exhausted: bool,
+}
+
+pub struct Relayer {
+    wallet: WalletUnlocked,
+    relay: RelayBytecode,
+    config: RelayerConfig,
+    store: RelayStore,
+    /// Messages for contracts this relayer does not serve. They are not recorded in the store,
+    /// so they are picked up once the relayer is configured to serve their contract
+    unknown_contract: HashSet<String>,
+    /// When each failed message may be attempted again
+    retry_at: HashMap<String, Instant>,
+}
+
+/// A relay that did not go through
+struct RelayFailure {
+    error: FuelsError,
+    /// The fee charged when the transaction made it on chain and reverted, zero otherwise
+    fee: u64,
+}
+
+impl From<FuelsError> for RelayFailure {
+    fn from(error: FuelsError) -> Self {
+        Self { error, fee: 0 }
+    }
+}
+
+impl Relayer {
+    pub fn new(
+        wallet: WalletUnlocked,
+        relay: RelayBytecode,
+        config: RelayerConfig,
+        store: RelayStore,
+    ) -> Self {
+        Self {
+            wallet,
+            relay,
+            config,
+            store,
+            unknown_contract: HashSet::new(),
+            retry_at: HashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &RelayStore {
+        &self.store
+    }
+
+    /// Relay every message waiting at the contract message predicate that has not been handled yet
+    pub async fn poll(&mut self) -> Result<PollSummary, RelayerError> {
+        let provider = self.wallet.provider()?.clone();
+        let messages = provider.get_messages(&self.relay.predicate_root()).await?;
+
+        let mut summary = PollSummary::default();
+        for message in messages {
+            let nonce = message.nonce.to_string();
+            let attempts = match self.store.get(&nonce) {
+                None => 0,
+                Some(RelayRecord::Failed { attempts, .. })
+                    if *attempts < self.config.max_attempts =>
+                {
+                    *attempts
+                }
+                Some(_) => continue,
+            };
+            if self
+                .retry_at
+                .get(&nonce)
+                .map_or(false, |at| Instant::now() < *at)
+            {
+                continue;
+            }
+
+            let plan = match self.plan(&message) {
+                Ok(plan) => plan,
+                Err(SkipReason::UnknownContract(_)) => {
+                    if self.unknown_contract.insert(nonce) {
+                        summary.skipped += 1;
+                    }
+                    continue;
+                }
+                Err(reason) => {
+                    let reason = reason.to_string();
+                    self.store.record(nonce, RelayRecord::Skipped { reason });
+                    self.store.save()?;
+                    summary.skipped += 1;
+                    continue;
+                }
+            };
+
+            // A relay that fails its dry run counts as a failed attempt
+            let estimate = self
+                .build_relay_tx(&message, &plan, &RelayGas::Coins(vec![]))
+                .await
+                .map(|(_, estimate)| estimate);
+            if let Ok(estimate) = &estimate {
+                if self.store.fees_spent.saturating_add(estimate.max_fee) > self.config.gas_budget {
+                    summary.budget_exhausted = true;
+                    break;
+                }
+            }
+
+            let attempts = attempts + 1;
+            let result = match estimate {
+                Ok(estimate) => self.relay_message(&message, &plan, estimate.max_fee).await,
+                Err(e) => Err(e.into()),
+            };
+            let record = match result {
+                Ok(fee) => {
+                    summary.relayed += 1;
+                    self.retry_at.remove(&nonce);
+                    RelayRecord::Relayed { attempts, fee }
+                }
+                Err(failure) => {
+                    summary.failed += 1;
+                    self.retry_at
+                        .insert(nonce.clone(), Instant::now() + self.backoff(attempts));
+                    RelayRecord::Failed {
+                        attempts,
+                        error: failure.error.to_string(),
+                        fee: failure.fee,
+                    }
+                }
+            };
+            self.store.record(nonce, record);
+            self.store.save()?;
+        }
+
+        Ok(summary)
+    }
+
+    /// The wait before the next attempt of a message that failed `attempts` times
+    fn backoff(&self, attempts: u32) -> Duration {
+        let doublings = attempts.saturating_sub(1).min(31);
+        self.config
+            .retry_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.config.max_retry_backoff)
+    }
+
+    fn plan(&self, message: &Message) -> Result<RelayPlan, SkipReason> {
+        let non_fungible = target_contract(&message.data)
+            .map_or(false, |id| self.config.non_fungible_contracts.contains(&id));
+        if non_fungible {
+            return RelayPlan::for_non_fungible_message(&message.data);
+        }
+
+        let plan = RelayPlan::for_message(&message.data)?;
+        if !self.config.bridge_contracts.contains(&plan.bridge_contract) {
+            return Err(SkipReason::UnknownContract(plan.bridge_contract));
+        }
+        Ok(plan)
+    }
+
+    /// Build the relay transaction with its gas limit estimated by a dry run
+    async fn build_relay_tx(
+        &self,
+        message: &Message,
+        plan: &RelayPlan,
+        gas: &RelayGas,
+    ) -> Result<(ScriptTransaction, GasEstimate), FuelsError> {
+        let mut contracts = vec![contract_input(plan.bridge_contract)];
+        if let Some(id) = plan.recipient_contract {
+            contracts.push(contract_input(id));
+        }
+        let outputs =
+            vec![Output::variable(Address::zeroed(), 0, AssetId::default()); plan.variable_outputs];
+
+        builder::build_estimated_contract_message_tx(
+            self.wallet.provider()?,
+            &self.relay.script,
+            self.relay.message_input(message),
+            contracts,
+            gas,
+            &outputs,
+            self.config.tx_params,
+        )
+        .await
+    }
+
+    /// Inputs covering `amount` of fees, from the sponsor if there is one
+    async fn gas(&self, amount: u64) -> Result<RelayGas, FuelsError> {
+        if let Some(sponsor) = &self.config.sponsor {
+            let coins = builder::predicate_gas_coins(
+                self.wallet.provider()?,
+                &sponsor.predicate,
+                &sponsor.predicate_data,
+                amount,
+            )
+            .await?;
+            return Ok(RelayGas::Sponsor {
+                coins,
+                change_to: sponsor.change_to,
+            });
+        }
+
+        let resources = self
+            .wallet
+            .get_spendable_resources(AssetId::default(), amount)
+            .await?;
+        let coins = resources
+            .into_iter()
+            .filter_map(|resource| match resource {
+                CoinType::Coin(coin) => Some(Input::coin_signed(
+                    coin.utxo_id,
+                    coin.owner.into(),
+                    coin.amount,
+                    coin.asset_id,
+                    TxPointer::default(),
+                    0,
+                    coin.maturity.into(),
+                )),
+                CoinType::Message(message) if message.data.is_empty() => {
+                    Some(Input::message_coin_signed(
+                        message.sender.into(),
+                        message.recipient.into(),
+                        message.amount,
+                        message.nonce,
+                        0,
+                    ))
+                }
+                CoinType::Message(_) => None,
+            })
+            .collect();
+        Ok(RelayGas::Coins(coins))
+    }
+
+    /// Relay a message, returning the fee that was paid. A transaction that reverts on chain
+    /// is charged too, its failure carries that fee
+    async fn relay_message(
+        &self,
+        message: &Message,
+        plan: &RelayPlan,
+        max_fee: u64,
+    ) -> Result<u64, RelayFailure> {
+        let gas = self.gas(max_fee.max(1)).await?;
+        let (mut tx, _) = self.build_relay_tx(message, plan, &gas).await?;
+        self.wallet.sign_transaction(&mut tx)?;
+        let provider = self.wallet.provider()?;
+        let result = provider.send_transaction(&tx).await;
+        // only a transaction that made it on chain has receipts, and pays a fee
+        let receipts = match &result {
+            Ok(receipts) | Err(FuelsError::RevertTransactionError { receipts, .. }) => {
+                receipts.clone()
+            }
+            Err(_) => return Err(result.unwrap_err().into()),
+        };
+
+        let gas_used = receipts
+            .iter()
+            .find_map(|receipt| match receipt {
+                Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let consensus_parameters = provider.chain_info().await?.consensus_parameters;
+        let fee = builder::transaction_fee(&consensus_parameters, &tx, gas_used)?;
+        match result {
+            Ok(_) => Ok(fee),
+            Err(error) => Err(RelayFailure { error, fee }),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// What the relayer has done with a message, keyed by message nonce in the store
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RelayRecord {
+    Relayed {
+        attempts: u32,
+        fee: u64,
+    },
+    Failed {
+        attempts: u32,
+        error: String,
+        /// Fee charged for the last attempt, zero when it never made it on chain
+        #[serde(default)]
+        fee: u64,
+    },
+    Skipped {
+        reason: String,
+    },
+}
+
+impl RelayRecord {
+    pub fn attempts(&self) -> u32 {
+        match self {
+            RelayRecord::Relayed { attempts, .. } | RelayRecord::Failed { attempts, .. } => {
+                *attempts
+            }
+            RelayRecord::Skipped { .. } => 0,
+        }
+    }
+}
+
+/// Persistent record of every message the relayer has handled and the fees it has spent
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RelayStore {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Total fees paid for relay transactions, in base asset
+    pub fees_spent: u64,
+    pub messages: BTreeMap<String, RelayRecord>,
+}
+
+impl RelayStore {
+    /// Open the store at the given path, starting empty if the file does not exist yet
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut store: RelayStore = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => RelayStore::default(),
+            Err(e) => return Err(e),
+        };
+        store.path = path;
+        Ok(store)
+    }
+
+    pub fn get(&self, nonce: &str) -> Option<&RelayRecord> {
+        self.messages.get(nonce)
+    }
+
+    pub fn record(&mut self, nonce: String, record: RelayRecord) {
+        if let RelayRecord::Relayed { fee, .. } | RelayRecord::Failed { fee, .. } = record {
+            self.fees_spent += fee;
+        }
+        self.messages.insert(nonce, record);
+    }
+
+    /// Write the store to disk, replacing the previous file atomically
+    pub fn save(&self) -> io::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(tmp, &self.path)
+    }
+}
@@ -0,0 +1,401 @@
+mod utils {
+    pub mod environment;
+}
+
+use bridge_relayer::{PollSummary, RelayRecord, RelayStore, Relayer, RelayerConfig, Sponsor};
+use fuels::{
+    accounts::{wallet::WalletUnlocked, ViewOnlyAccount},
+    prelude::{AssetId, Bech32ContractId, ContractId, TxParameters},
+    test_helpers::DEFAULT_COIN_AMOUNT,
+    types::Bits256,
+};
+use primitive_types::U256 as Unsigned256;
+use std::{path::PathBuf, time::Duration};
+use utils::environment as env;
+
+fn store_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{name}-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn relayer_config(bridge_contracts: Vec<ContractId>) -> RelayerConfig {
+    RelayerConfig {
+        bridge_contracts,
+        non_fungible_contracts: vec![],
+        max_attempts: 3,
+        retry_backoff: Duration::ZERO,
+        max_retry_backoff: Duration::ZERO,
+        tx_params: TxParameters::default(),
+        gas_budget: u64::MAX,
+        sponsor: None,
+    }
+}
+
+/// One proxy token in base layer units
+fn one_token() -> Unsigned256 {
+    Unsigned256::from(10).pow(Unsigned256::from(
+        env::BRIDGED_TOKEN_DECIMALS - env::PROXY_TOKEN_DECIMALS,
+    ))
+}
+
+mod success {
+    use super::*;
+
+    #[tokio::test]
+    async fn relays_pending_deposits() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let address_amount = one_token() * 5;
+        let contract_amount = one_token() * 7;
+        let messages = vec![
+            env::deposit_to_address(wallet.address(), address_amount),
+            env::deposit_to_contract(env::deposit_recipient_id(), contract_amount),
+        ];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        let provider = env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+        let deposit_contract_id = env::deploy_deposit_recipient(&wallet).await;
+
+        let path = store_path("relays_pending_deposits");
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![bridge.contract_id()]),
+            RelayStore::open(&path).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 2);
+        assert_eq!(summary.failed, 0);
+        assert!(relayer
+            .store()
+            .messages
+            .values()
+            .all(|record| matches!(record, RelayRecord::Relayed { attempts: 1, .. })));
+
+        // Check both recipients received their tokens
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        let deposit_contract_balance = provider
+            .get_contract_asset_balance(
+                &Bech32ContractId::from(deposit_contract_id),
+                bridge.asset_id(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(balance, env::minted(address_amount));
+        assert_eq!(deposit_contract_balance, env::minted(contract_amount));
+
+        // A restarted relayer picks up its record and does nothing more
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![bridge.contract_id()]),
+            RelayStore::open(&path).unwrap(),
+        );
+        assert_eq!(relayer.store().messages.len(), 2);
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 0);
+        assert_eq!(summary.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn relays_token_metadata() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let amount = one_token() * 2;
+        // the name sits where a deposit to a contract has its recipient
+        let name = "Wrapped Ether";
+        let messages = vec![
+            env::metadata(name, "WETH"),
+            env::deposit_to_address(wallet.address(), amount),
+        ];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![bridge.contract_id()]),
+            RelayStore::open(store_path("relays_token_metadata")).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 2);
+        assert_eq!(summary.failed, 0);
+
+        let metadata = bridge.config().await.unwrap().metadata.unwrap();
+        assert_eq!(metadata.name.to_trimmed_str(), name);
+        assert_eq!(metadata.symbol.to_trimmed_str(), "WETH");
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        assert_eq!(balance, env::minted(amount));
+    }
+
+    #[tokio::test]
+    async fn relays_releases_of_locked_assets() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let recipient = WalletUnlocked::new_random(None);
+        let locked = 5_000;
+        let released = 3_000;
+        let messages = vec![env::release_to_address(
+            AssetId::default(),
+            recipient.address(),
+            released,
+        )];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        let provider = env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+        bridge
+            .lock(
+                AssetId::default(),
+                Bits256::from_hex_str(env::FROM).unwrap(),
+                locked,
+                TxParameters::default(),
+            )
+            .await
+            .unwrap();
+
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![bridge.contract_id()]),
+            RelayStore::open(store_path("relays_releases_of_locked_assets")).unwrap(),
+        );
+
+        // the released coins go to an address, which needs a variable output
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 1);
+        assert_eq!(summary.failed, 0);
+
+        let balance = provider
+            .get_asset_balance(recipient.address(), AssetId::default())
+            .await
+            .unwrap();
+        assert_eq!(balance, released);
+        assert_eq!(
+            bridge.locked_balance(AssetId::default()).await.unwrap(),
+            locked - released
+        );
+    }
+
+    #[tokio::test]
+    async fn relays_non_fungible_deposits() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let recipient = WalletUnlocked::new_random(None);
+        let messages = vec![env::non_fungible_deposit_to_address(
+            recipient.address(),
+            "ipfs://token/1",
+        )];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        let provider = env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_non_fungible_bridge(&wallet).await;
+
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            RelayerConfig {
+                non_fungible_contracts: vec![bridge],
+                ..relayer_config(vec![])
+            },
+            RelayStore::open(store_path("relays_non_fungible_deposits")).unwrap(),
+        );
+
+        // the data is longer than a fungible deposit to an address, but the flag after
+        // the token id says the coin goes to one
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 1);
+        assert_eq!(summary.failed, 0);
+
+        let balance = provider
+            .get_asset_balance(recipient.address(), AssetId::new(*bridge))
+            .await
+            .unwrap();
+        assert_eq!(balance, 1);
+    }
+
+    #[tokio::test]
+    async fn records_the_fee_charged_by_the_chain() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let messages = vec![env::deposit_to_address(wallet.address(), one_token())];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        let provider = env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+        let consensus_parameters = provider.chain_info().await.unwrap().consensus_parameters;
+
+        // At a gas price of one gas price factor a unit of gas costs one unit of base asset
+        let mut config = relayer_config(vec![bridge.contract_id()]);
+        config.tx_params =
+            TxParameters::default().set_gas_price(consensus_parameters.gas_price_factor);
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            config,
+            RelayStore::open(store_path("records_the_fee_charged_by_the_chain")).unwrap(),
+        );
+
+        let base_balance = wallet.get_asset_balance(&AssetId::default()).await.unwrap();
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 1);
+
+        // The recorded fee is what the relay took from the wallet, size fee included
+        let fee = match relayer.store().messages.values().next().unwrap() {
+            RelayRecord::Relayed { fee, .. } => *fee,
+            record => panic!("unexpected record {record:?}"),
+        };
+        let spent = base_balance - wallet.get_asset_balance(&AssetId::default()).await.unwrap();
+        assert!(fee > 0);
+        assert_eq!(fee, spent);
+        assert_eq!(relayer.store().fees_spent, fee);
+    }
+
+    #[tokio::test]
+    async fn sponsor_pays_for_unfunded_relayer() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let mut deployer = WalletUnlocked::new_random(None);
+        let amount = one_token() * 3;
+
+        // The contract message predicate accepts any relay, so coins it owns can sponsor them
+        let relay = env::relay_bytecode();
+        let sponsor_root = relay.predicate_root();
+        let coins = [
+            (deployer.address().clone(), DEFAULT_COIN_AMOUNT),
+            (sponsor_root.clone(), DEFAULT_COIN_AMOUNT),
+        ];
+        let messages = vec![env::deposit_to_address(wallet.address(), amount)];
+        let provider = env::launch_node(&mut deployer, &coins, messages).await;
+        wallet.set_provider(provider);
+        let bridge = env::deploy_bridge(&deployer).await;
+
+        let mut settings = relayer_config(vec![bridge.contract_id()]);
+        settings.tx_params = TxParameters::default().set_gas_price(1);
+        settings.sponsor = Some(Sponsor {
+            predicate: relay.predicate.clone(),
+            predicate_data: vec![],
+            change_to: (&sponsor_root).into(),
+        });
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            relay,
+            settings,
+            RelayStore::open(store_path("sponsor_pays_for_unfunded_relayer")).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 1);
+        assert_eq!(summary.failed, 0);
+
+        let base_balance = wallet.get_asset_balance(&AssetId::default()).await.unwrap();
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        assert_eq!(base_balance, 0);
+        assert_eq!(balance, env::minted(amount));
+    }
+}
+
+mod revert {
+    use super::*;
+
+    #[tokio::test]
+    async fn skips_malformed_and_unknown_messages() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let messages = vec![
+            env::deposit_to_address(wallet.address(), one_token()),
+            vec![7u8; 64],
+        ];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+
+        // The relayer serves a different bridge contract
+        let path = store_path("skips_malformed_and_unknown_messages");
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![ContractId::new([1u8; 32])]),
+            RelayStore::open(&path).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 0);
+        assert_eq!(summary.skipped, 2);
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        assert_eq!(balance, 0);
+
+        // Skips are only counted once per run
+        assert_eq!(relayer.poll().await.unwrap(), PollSummary::default());
+
+        // Only the malformed message is skipped for good, the deposit is relayed once the
+        // relayer serves its contract
+        assert_eq!(relayer.store().messages.len(), 1);
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            relayer_config(vec![bridge.contract_id()]),
+            RelayStore::open(&path).unwrap(),
+        );
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.relayed, 1);
+        assert_eq!(summary.skipped, 0);
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        assert_eq!(balance, env::minted(one_token()));
+    }
+
+    #[tokio::test]
+    async fn backs_off_before_retrying_a_failed_message() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        // the recipient contract is never deployed, so every dry run fails
+        let messages = vec![env::deposit_to_contract(
+            env::deposit_recipient_id(),
+            one_token(),
+        )];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+
+        let mut config = relayer_config(vec![bridge.contract_id()]);
+        config.retry_backoff = Duration::from_secs(600);
+        config.max_retry_backoff = Duration::from_secs(3600);
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            config,
+            RelayStore::open(store_path("backs_off_before_retrying_a_failed_message")).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert_eq!(summary.failed, 1);
+
+        // The next poll comes before the backoff is over
+        assert_eq!(relayer.poll().await.unwrap(), PollSummary::default());
+        let records: Vec<_> = relayer.store().messages.values().collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].attempts(), 1);
+        // the dry run failed, so nothing was spent
+        assert_eq!(relayer.store().fees_spent, 0);
+    }
+
+    #[tokio::test]
+    async fn stops_when_gas_budget_is_exhausted() {
+        let mut wallet = WalletUnlocked::new_random(None);
+        let messages = vec![env::deposit_to_address(wallet.address(), one_token())];
+        let coins = [(wallet.address().clone(), DEFAULT_COIN_AMOUNT)];
+        env::launch_node(&mut wallet, &coins, messages).await;
+        let bridge = env::deploy_bridge(&wallet).await;
+
+        let mut config = relayer_config(vec![bridge.contract_id()]);
+        config.tx_params = TxParameters::default().set_gas_price(1);
+        config.gas_budget = 0;
+        let mut relayer = Relayer::new(
+            wallet.clone(),
+            env::relay_bytecode(),
+            config,
+            RelayStore::open(store_path("stops_when_gas_budget_is_exhausted")).unwrap(),
+        );
+
+        let summary = relayer.poll().await.unwrap();
+        assert!(summary.budget_exhausted);
+        assert_eq!(summary.relayed, 0);
+        assert!(relayer.store().messages.is_empty());
+
+        let balance = wallet.get_asset_balance(&bridge.asset_id()).await.unwrap();
+        assert_eq!(balance, 0);
+    }
+}
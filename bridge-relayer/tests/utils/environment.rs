@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use bridge_fungible_token_sdk::{
+    decimals, BridgeClient, DepositMessage, MetadataMessage, RelayBytecode, ReleaseMessage,
+};
+use fuel_core_types::fuel_types::Word;
+use fuels::{
+    accounts::wallet::WalletUnlocked,
+    prelude::{
+        setup_custom_assets_coins, setup_test_provider, Address, AssetConfig, AssetId,
+        Bech32Address, Config, Contract, ContractId, LoadConfiguration, Provider, TxParameters,
+    },
+    test_helpers::setup_single_message,
+    types::{message::Message, Bits256},
+};
+use primitive_types::U256 as Unsigned256;
+
+const CONTRACT_MESSAGE_PREDICATE_BINARY: &str =
+    "../bridge-message-predicates/contract_message_predicate.bin";
+const CONTRACT_MESSAGE_SCRIPT_BINARY: &str =
+    "../bridge-message-predicates/contract_message_script.bin";
+const BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+    "../bridge-fungible-token/out/debug/bridge_fungible_token.bin";
+const BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY: &str =
+    "../bridge-non-fungible-token/out/debug/bridge_non_fungible_token.bin";
+const DEPOSIT_RECIPIENT_CONTRACT_BINARY: &str =
+    "../test-deposit-recipient-contract/out/debug/test_deposit_recipient_contract.bin";
+const MESSAGE_SENDER_ADDRESS: &str =
+    "0x00000000000000000000000096c53cd98B7297564716a8f2E1de2C83928Af2fe";
+
+/// The token and decimals of the bridge contract deployed without configurables
+pub const BRIDGED_TOKEN: &str =
+    "0x00000000000000000000000000000000000000000000000000000000deadbeef";
+pub const BRIDGED_TOKEN_DECIMALS: u8 = 18;
+pub const PROXY_TOKEN_DECIMALS: u8 = 9;
+pub const FROM: &str = "0x0000000000000000000000008888888888888888888888888888888888888888";
+
+/// The token id bridged by the non-fungible bridge deployed without configurables
+pub const BRIDGED_TOKEN_ID: &str =
+    "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+/// Base asset attached to every message from the gateway
+const MESSAGE_AMOUNT: Word = 100;
+
+/// Loads the contract message predicate and script used to relay deposits
+pub fn relay_bytecode() -> RelayBytecode {
+    RelayBytecode::load_from(
+        CONTRACT_MESSAGE_PREDICATE_BINARY,
+        CONTRACT_MESSAGE_SCRIPT_BINARY,
+    )
+    .unwrap()
+}
+
+/// Starts a node with a single base asset coin for each of `coins` and the given message
+/// data sent by the gateway to the contract message predicate, and connects `wallet` to it
+pub async fn launch_node(
+    wallet: &mut WalletUnlocked,
+    coins: &[(Bech32Address, Word)],
+    messages: Vec<Vec<u8>>,
+) -> Provider {
+    let all_coins = coins
+        .iter()
+        .flat_map(|(owner, amount)| {
+            setup_custom_assets_coins(
+                owner,
+                &[AssetConfig {
+                    id: AssetId::default(),
+                    num_coins: 1,
+                    coin_amount: *amount,
+                }],
+            )
+        })
+        .collect();
+
+    let sender = Address::from_str(MESSAGE_SENDER_ADDRESS).unwrap();
+    let predicate_root = relay_bytecode().predicate_root();
+    let all_messages: Vec<Message> = messages
+        .into_iter()
+        .enumerate()
+        .map(|(nonce, data)| {
+            setup_single_message(
+                &sender.into(),
+                &predicate_root,
+                MESSAGE_AMOUNT,
+                (nonce as Word).into(),
+                data,
+            )
+        })
+        .collect();
+
+    let (provider, _) =
+        setup_test_provider(all_coins, all_messages, Some(Config::local_node()), None).await;
+    wallet.set_provider(provider.clone());
+    provider
+}
+
+/// The id of the bridge contract deployed without configurables
+pub fn bridge_contract_id() -> ContractId {
+    BridgeClient::precompute_contract_id(BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY, None).unwrap()
+}
+
+/// Deploys the bridge contract without configurables and connects a client to it
+pub async fn deploy_bridge(wallet: &WalletUnlocked) -> BridgeClient {
+    BridgeClient::deploy(
+        wallet.clone(),
+        BRIDGE_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        None,
+        TxParameters::default(),
+        relay_bytecode(),
+    )
+    .await
+    .unwrap()
+}
+
+/// Deploys the non-fungible bridge contract without configurables
+pub async fn deploy_non_fungible_bridge(wallet: &WalletUnlocked) -> ContractId {
+    Contract::load_from(
+        BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .deploy(wallet, TxParameters::default())
+    .await
+    .unwrap()
+    .into()
+}
+
+/// The id of the deposit recipient test contract
+pub fn deposit_recipient_id() -> ContractId {
+    Contract::load_from(
+        DEPOSIT_RECIPIENT_CONTRACT_BINARY,
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .contract_id()
+}
+
+/// Deploys the deposit recipient test contract, which accepts deposits from any bridge
+pub async fn deploy_deposit_recipient(wallet: &WalletUnlocked) -> ContractId {
+    Contract::load_from(
+        DEPOSIT_RECIPIENT_CONTRACT_BINARY,
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .deploy(wallet, TxParameters::default())
+    .await
+    .unwrap()
+    .into()
+}
+
+/// A deposit of `amount` of the bridged token to the address `to`
+pub fn deposit_to_address(to: &Bech32Address, amount: Unsigned256) -> Vec<u8> {
+    DepositMessage::to_address(
+        Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+        Bits256::from_hex_str(FROM).unwrap(),
+        Bits256(*to.hash()),
+        amount,
+    )
+    .encode(bridge_contract_id())
+}
+
+/// A deposit of `amount` of the bridged token to the contract `to`, without a callback
+pub fn deposit_to_contract(to: ContractId, amount: Unsigned256) -> Vec<u8> {
+    DepositMessage::to_contract(
+        Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+        Bits256::from_hex_str(FROM).unwrap(),
+        to,
+        amount,
+        vec![],
+    )
+    .encode(bridge_contract_id())
+}
+
+/// A release of `amount` of the locked Fuel-native `asset` to the address `to`
+pub fn release_to_address(asset: AssetId, to: &Bech32Address, amount: u64) -> Vec<u8> {
+    ReleaseMessage::to_address(
+        asset,
+        Bits256::from_hex_str(FROM).unwrap(),
+        Bits256(*to.hash()),
+        amount,
+    )
+    .encode(bridge_contract_id())
+}
+
+/// A deposit of the token bridged by the non-fungible bridge to the address `to`, the
+/// recipient kind is always given as the URI follows it
+pub fn non_fungible_deposit_to_address(to: &Bech32Address, uri: &str) -> Vec<u8> {
+    let bridge_contract_id = Contract::load_from(
+        BRIDGE_NON_FUNGIBLE_TOKEN_CONTRACT_BINARY,
+        LoadConfiguration::default(),
+    )
+    .unwrap()
+    .contract_id();
+
+    let mut data = bridge_contract_id.to_vec();
+    data.extend_from_slice(&Bits256::from_hex_str(BRIDGED_TOKEN).unwrap().0);
+    data.extend_from_slice(&Bits256::from_hex_str(FROM).unwrap().0);
+    data.extend_from_slice(to.hash().as_ref());
+    data.extend_from_slice(&Bits256::from_hex_str(BRIDGED_TOKEN_ID).unwrap().0);
+    data.push(0);
+    data.extend_from_slice(uri.as_bytes());
+    data
+}
+
+/// The metadata of the bridged token as the gateway registers it
+pub fn metadata(name: &str, symbol: &str) -> Vec<u8> {
+    MetadataMessage::new(
+        Bits256::from_hex_str(BRIDGED_TOKEN).unwrap(),
+        name,
+        symbol,
+        BRIDGED_TOKEN_DECIMALS,
+    )
+    .unwrap()
+    .encode(bridge_contract_id())
+}
+
+/// The proxy tokens minted for a deposit of `amount`
+pub fn minted(amount: Unsigned256) -> u64 {
+    decimals::adjust_deposit_decimals(amount, PROXY_TOKEN_DECIMALS, BRIDGED_TOKEN_DECIMALS).unwrap()
+}
@@ -0,0 +1,166 @@
+contract;
+
+use fungible_bridge_abi::FungibleBridge;
+use FRC20_abi::FRC20;
+use std::{
+    constants::ZERO_B256,
+    external::bytecode_root,
+};
+
+configurable {
+    OWNER: b256 = ZERO_B256,
+}
+
+pub enum TokenRegistryError {
+    Unauthorized: (),
+    BytecodeNotApproved: (),
+    BytecodeMismatch: (),
+    TokenAlreadyRegistered: (),
+}
+
+/// Where a base layer token is bridged to on Fuel
+pub struct TokenEntry {
+    /// The base layer token contract
+    token: b256,
+    /// The bridge contract on Fuel
+    bridge: ContractId,
+    /// The proxy token minted by the bridge contract
+    asset_id: b256,
+    /// Decimals of the proxy token
+    decimals: u8,
+    /// Decimals of the base layer token
+    bridged_token_decimals: u8,
+}
+
+pub struct BytecodeRootApprovedEvent {
+    token: b256,
+    bytecode_root: b256,
+}
+
+pub struct TokenRegisteredEvent {
+    entry: TokenEntry,
+    bytecode_root: b256,
+}
+
+storage {
+    approved_roots: StorageMap<b256, b256> = StorageMap {},
+    entries: StorageMap<b256, TokenEntry> = StorageMap {},
+    tokens_by_bridge: StorageMap<ContractId, b256> = StorageMap {},
+}
+
+abi TokenRegistry {
+    /// Approve the bytecode root a bridge contract for the given base layer token must have,
+    /// replacing any earlier approval for the token
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - the base layer token
+    /// * `root` - the bytecode root of the approved bridge binary built with the configuration for `token`
+    ///
+    /// # Reverts
+    ///
+    /// * When not called by the owner
+    /// * When the token is already registered
+    #[storage(read, write)]
+    fn approve_bytecode_root(token: b256, root: b256);
+
+    /// Register a deployed bridge contract under the base layer token it bridges.
+    /// The token and decimals are read from the bridge contract itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `bridge` - the bridge contract to register
+    ///
+    /// # Reverts
+    ///
+    /// * When not called by the owner
+    /// * When no bytecode root was approved for the token
+    /// * When the bytecode root of `bridge` is not the approved one
+    /// * When the token is already registered
+    #[storage(read, write)]
+    fn register(bridge: ContractId);
+
+    /// Get the bytecode root approved for the given base layer token
+    #[storage(read)]
+    fn approved_bytecode_root(token: b256) -> Option<b256>;
+
+    /// Get the entry of the given base layer token
+    #[storage(read)]
+    fn entry_for_token(token: b256) -> Option<TokenEntry>;
+
+    /// Get the entry of the given bridge contract
+    #[storage(read)]
+    fn entry_for_bridge(bridge: ContractId) -> Option<TokenEntry>;
+
+    /// Get the address allowed to approve and register bridge contracts
+    fn owner() -> b256;
+}
+
+impl TokenRegistry for Contract {
+    #[storage(read, write)]
+    fn approve_bytecode_root(token: b256, root: b256) {
+        require_owner();
+        require(storage.entries.get(token).try_read().is_none(), TokenRegistryError::TokenAlreadyRegistered);
+
+        storage.approved_roots.insert(token, root);
+        log(BytecodeRootApprovedEvent {
+            token,
+            bytecode_root: root,
+        });
+    }
+
+    #[storage(read, write)]
+    fn register(bridge: ContractId) {
+        require_owner();
+
+        let token = abi(FungibleBridge, bridge.value).bridged_token();
+        require(storage.entries.get(token).try_read().is_none(), TokenRegistryError::TokenAlreadyRegistered);
+
+        // the token is only trusted to pick the approval, the bytecode root then pins the whole configuration
+        let approved_root = storage.approved_roots.get(token).try_read();
+        require(approved_root.is_some(), TokenRegistryError::BytecodeNotApproved);
+        let root = bytecode_root(bridge);
+        require(root == approved_root.unwrap(), TokenRegistryError::BytecodeMismatch);
+
+        let entry = TokenEntry {
+            token,
+            bridge,
+            // the proxy token of a bridge contract has the id of the contract
+            asset_id: bridge.value,
+            decimals: abi(FRC20, bridge.value).decimals(),
+            bridged_token_decimals: abi(FungibleBridge, bridge.value).bridged_token_decimals(),
+        };
+        storage.entries.insert(token, entry);
+        storage.tokens_by_bridge.insert(bridge, token);
+        log(TokenRegisteredEvent {
+            entry,
+            bytecode_root: root,
+        });
+    }
+
+    #[storage(read)]
+    fn approved_bytecode_root(token: b256) -> Option<b256> {
+        storage.approved_roots.get(token).try_read()
+    }
+
+    #[storage(read)]
+    fn entry_for_token(token: b256) -> Option<TokenEntry> {
+        storage.entries.get(token).try_read()
+    }
+
+    #[storage(read)]
+    fn entry_for_bridge(bridge: ContractId) -> Option<TokenEntry> {
+        match storage.tokens_by_bridge.get(bridge).try_read() {
+            Option::Some(token) => storage.entries.get(token).try_read(),
+            Option::None => Option::None,
+        }
+    }
+
+    fn owner() -> b256 {
+        OWNER
+    }
+}
+
+fn require_owner() {
+    require(msg_sender().unwrap() == Identity::Address(Address::from(OWNER)), TokenRegistryError::Unauthorized);
+}
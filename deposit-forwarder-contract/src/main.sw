@@ -0,0 +1,53 @@
+contract;
+
+use contract_message_receiver::MessageReceiver;
+use deposit_recipient_lib::{extra_data_address, read_deposit};
+use std::{
+    constants::ZERO_B256,
+    token::transfer,
+};
+
+configurable {
+    BRIDGE: b256 = ZERO_B256,
+}
+
+storage {
+    total_forwarded: u64 = 0,
+}
+
+pub struct DepositForwardedEvent {
+    from: b256,
+    to: Address,
+    amount: u64,
+}
+
+abi DepositForwarder {
+    /// Get the proxy tokens forwarded over all deposits
+    #[storage(read)]
+    fn total_forwarded() -> u64;
+}
+
+impl MessageReceiver for Contract {
+    /// Forward the deposited tokens to the address named in the extra data
+    #[payable]
+    #[storage(read, write)]
+    fn process_message(msg_idx: u8) {
+        let deposit = read_deposit(msg_idx, ContractId::from(BRIDGE));
+        let to = extra_data_address(deposit);
+
+        transfer(deposit.amount, ContractId::from(BRIDGE), Identity::Address(to));
+        storage.total_forwarded.write(storage.total_forwarded.read() + deposit.amount);
+        log(DepositForwardedEvent {
+            from: deposit.from,
+            to,
+            amount: deposit.amount,
+        });
+    }
+}
+
+impl DepositForwarder for Contract {
+    #[storage(read)]
+    fn total_forwarded() -> u64 {
+        storage.total_forwarded.read()
+    }
+}
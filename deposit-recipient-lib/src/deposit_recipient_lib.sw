@@ -0,0 +1,64 @@
+library;
+
+use std::{
+    bytes::Bytes,
+    call_frames::msg_asset_id,
+    context::msg_amount,
+    inputs::{
+        input_message_data,
+        input_message_data_length,
+    },
+};
+
+/// Offset of the extra data in a deposit to a contract: 32 + 32 + 32 + 32 + 32 + 1
+pub const EXTRA_DATA_OFFSET: u64 = 161;
+
+pub enum DepositRecipientError {
+    UnauthorizedBridge: (),
+    IncorrectAssetDeposited: (),
+    MissingExtraData: (),
+    InsufficientShares: (),
+    NoSharesCredited: (),
+    Overflow: (),
+}
+
+/// A deposit forwarded by the bridge to a recipient contract
+pub struct Deposit {
+    /// The base layer token that was deposited
+    token: b256,
+    /// The base layer address that made the deposit
+    from: b256,
+    /// The proxy tokens forwarded with the call
+    amount: u64,
+    /// The data following the deposit in the message
+    extra_data: Bytes,
+}
+
+/// Read the deposit being processed from message `msg_idx`.
+/// Reverts unless the call comes from `bridge` and forwards its proxy tokens.
+pub fn read_deposit(msg_idx: u8, bridge: ContractId) -> Deposit {
+    require(msg_sender().unwrap() == Identity::ContractId(bridge), DepositRecipientError::UnauthorizedBridge);
+    require(msg_asset_id() == bridge, DepositRecipientError::IncorrectAssetDeposited);
+
+    let len = asm(r1: input_message_data_length(msg_idx)) { r1: u64 };
+    let mut extra_data = Bytes::new();
+    if (len > EXTRA_DATA_OFFSET) {
+        // only keep the bytes belonging to the message
+        let (data, _) = input_message_data(msg_idx, EXTRA_DATA_OFFSET).split_at(len - EXTRA_DATA_OFFSET);
+        extra_data = data;
+    }
+
+    Deposit {
+        token: input_message_data(msg_idx, 32).into(),
+        from: input_message_data(msg_idx, 32 + 32).into(),
+        amount: msg_amount(),
+        extra_data,
+    }
+}
+
+/// Read the address at the start of the extra data of a deposit
+pub fn extra_data_address(deposit: Deposit) -> Address {
+    require(deposit.extra_data.len() >= 32, DepositRecipientError::MissingExtraData);
+    let value: b256 = deposit.extra_data.into();
+    Address::from(value)
+}
@@ -0,0 +1,91 @@
+contract;
+
+use contract_message_receiver::MessageReceiver;
+use deposit_recipient_lib::read_deposit;
+use std::{
+    bytes::Bytes,
+    constants::ZERO_B256,
+    storage::{
+        storage_bytes::*,
+        storage_vec::*,
+    },
+};
+
+configurable {
+    BRIDGE: b256 = ZERO_B256,
+}
+
+/// A deposit as recorded on arrival, its extra data is stored separately
+pub struct DepositRecord {
+    token: b256,
+    from: b256,
+    amount: u64,
+}
+
+storage {
+    deposits: StorageVec<DepositRecord> = StorageVec {},
+    extra_data: StorageMap<u64, StorageBytes> = StorageMap {},
+    total_received: u64 = 0,
+}
+
+abi DepositRecorder {
+    /// Get the number of deposits received
+    #[storage(read)]
+    fn deposit_count() -> u64;
+
+    /// Get the deposit at the given index, in order of arrival
+    #[storage(read)]
+    fn get_deposit(index: u64) -> Option<DepositRecord>;
+
+    /// Get the extra data of the deposit at the given index, empty if it had none
+    #[storage(read)]
+    fn get_extra_data(index: u64) -> Bytes;
+
+    /// Get the proxy tokens received over all deposits
+    #[storage(read)]
+    fn total_received() -> u64;
+}
+
+impl MessageReceiver for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn process_message(msg_idx: u8) {
+        let deposit = read_deposit(msg_idx, ContractId::from(BRIDGE));
+
+        let index = storage.deposits.len();
+        storage.deposits.push(DepositRecord {
+            token: deposit.token,
+            from: deposit.from,
+            amount: deposit.amount,
+        });
+        if (deposit.extra_data.len() > 0) {
+            storage.extra_data.get(index).write_slice(deposit.extra_data);
+        }
+        storage.total_received.write(storage.total_received.read() + deposit.amount);
+    }
+}
+
+impl DepositRecorder for Contract {
+    #[storage(read)]
+    fn deposit_count() -> u64 {
+        storage.deposits.len()
+    }
+
+    #[storage(read)]
+    fn get_deposit(index: u64) -> Option<DepositRecord> {
+        match storage.deposits.get(index) {
+            Option::Some(deposit) => Option::Some(deposit.read()),
+            Option::None => Option::None,
+        }
+    }
+
+    #[storage(read)]
+    fn get_extra_data(index: u64) -> Bytes {
+        storage.extra_data.get(index).read_slice().unwrap_or(Bytes::new())
+    }
+
+    #[storage(read)]
+    fn total_received() -> u64 {
+        storage.total_received.read()
+    }
+}
@@ -2,6 +2,18 @@ library;
 
 use std::vm::evm::evm_address::EvmAddress;
 
+/// The metadata of the bridged token as registered by the base layer gateway
+pub struct TokenMetadata {
+    /// The base layer token the metadata was read from
+    token: b256,
+    /// The ERC-20 name, right padded with spaces
+    name: str[64],
+    /// The ERC-20 symbol, right padded with spaces
+    symbol: str[32],
+    /// The ERC-20 decimals
+    decimals: u8,
+}
+
 abi FungibleBridge {
     /// Claim a refund for incorrectly bridged tokens if one has been registered.
     ///
@@ -35,4 +47,35 @@ abi FungibleBridge {
 
     /// Get the address of the gateway that holds the bridged tokens
     fn bridged_token_gateway() -> b256;
+
+    /// Get the name the contract was deployed with, `name` returns the registered one once the gateway sent the token metadata
+    fn configured_name() -> str[64];
+
+    /// Get the symbol the contract was deployed with, `symbol` returns the registered one once the gateway sent the token metadata
+    fn configured_symbol() -> str[32];
+
+    /// Get the metadata registered by the gateway, if it has sent it yet
+    #[storage(read)]
+    fn bridged_token_metadata() -> Option<TokenMetadata>;
+}
+
+abi NativeAssetBridge {
+    /// Lock Fuel-native coins in the contract and have the base layer gateway mint their wrapped token.
+    /// The amount is sent as is, the wrapped token has the decimals of the Fuel-native asset.
+    ///
+    /// # Arguments
+    ///
+    /// * `to` - the base layer address receiving the wrapped tokens
+    ///
+    /// # Reverts
+    ///
+    /// * When no coins were sent with call
+    /// * When the proxy token of this contract was sent with the call, it is withdrawn instead
+    #[payable]
+    #[storage(read, write)]
+    fn lock(to: b256);
+
+    /// Get the amount of a Fuel-native asset locked in the contract
+    #[storage(read)]
+    fn locked_balance(asset: ContractId) -> u64;
 }
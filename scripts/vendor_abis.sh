@@ -0,0 +1,11 @@
+#!/usr/bin/env bash
+# Copies the ABIs of the contracts bridge-fungible-token-sdk binds to into the
+# crate, which generates its bindings from them. Run from the repository root
+# after `forc build` whenever one of these contracts changes its ABI.
+set -euo pipefail
+
+sdk_abi_dir=bridge-fungible-token-sdk/abi
+mkdir -p "$sdk_abi_dir"
+
+cp bridge-fungible-token/out/debug/bridge_fungible_token-abi.json "$sdk_abi_dir/"
+cp bridge-token-registry/out/debug/bridge_token_registry-abi.json "$sdk_abi_dir/"
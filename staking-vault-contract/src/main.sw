@@ -0,0 +1,168 @@
+contract;
+
+use contract_message_receiver::MessageReceiver;
+use deposit_recipient_lib::{DepositRecipientError, extra_data_address, read_deposit};
+use std::{
+    call_frames::msg_asset_id,
+    constants::ZERO_B256,
+    context::msg_amount,
+    token::transfer,
+    u256::U256,
+};
+
+configurable {
+    BRIDGE: b256 = ZERO_B256,
+}
+
+/// Shares taken from the first deposit and never credited to anyone, the first depositor
+/// pays for them with `DEAD_SHARES` units of their deposit.
+/// Without them the first depositor could hold a single share and donate rewards until
+/// later deposits round down to no shares, taking those deposits for themselves.
+/// With them such a donation is almost entirely lost to the dead shares.
+const DEAD_SHARES: u64 = 1_000;
+
+storage {
+    shares: StorageMap<Identity, u64> = StorageMap {},
+    total_shares: u64 = 0,
+    total_assets: u64 = 0,
+}
+
+pub struct SharesCreditedEvent {
+    owner: Identity,
+    from: b256,
+    amount: u64,
+    shares: u64,
+}
+
+abi StakingVault {
+    /// Add staking rewards in proxy tokens, raising the value of every share
+    #[payable]
+    #[storage(read, write)]
+    fn add_rewards();
+
+    /// Burn shares of the caller and send them the proxy tokens the shares are worth
+    ///
+    /// # Reverts
+    ///
+    /// * When the caller owns fewer than `shares` shares, or `shares` is zero
+    /// * When the amount they are worth does not fit in a `u64`
+    #[storage(read, write)]
+    fn redeem(shares: u64);
+
+    /// Get the shares owned by the given identity
+    #[storage(read)]
+    fn shares_of(owner: Identity) -> u64;
+
+    /// Get the proxy tokens the given shares can be redeemed for
+    #[storage(read)]
+    fn preview_redeem(shares: u64) -> u64;
+
+    #[storage(read)]
+    fn total_shares() -> u64;
+
+    #[storage(read)]
+    fn total_assets() -> u64;
+}
+
+impl MessageReceiver for Contract {
+    /// Stake the deposited tokens, crediting shares to the address named in the extra data.
+    /// The first deposit is credited `DEAD_SHARES` fewer shares than its amount.
+    ///
+    /// # Reverts
+    ///
+    /// * When the deposit is worth no shares, including a first deposit of at most `DEAD_SHARES`.
+    ///   The message stays pending and can only be relayed once its amount is worth a share,
+    ///   since rewards only raise the share price a small deposit usually stays stuck with its
+    ///   tokens locked on the base layer
+    /// * When the shares do not fit in a `u64`
+    #[payable]
+    #[storage(read, write)]
+    fn process_message(msg_idx: u8) {
+        let deposit = read_deposit(msg_idx, ContractId::from(BRIDGE));
+        let owner = Identity::Address(extra_data_address(deposit));
+
+        let mut total_shares = storage.total_shares.read();
+        let total_assets = storage.total_assets.read();
+        let shares = if (total_shares == 0) {
+            total_shares = DEAD_SHARES;
+            if (deposit.amount > DEAD_SHARES) {
+                deposit.amount - DEAD_SHARES
+            } else {
+                0
+            }
+        } else if (total_assets == 0) {
+            deposit.amount
+        } else {
+            mul_div(deposit.amount, total_shares, total_assets)
+        };
+        // reverting leaves the deposit pending rather than giving it away to existing stakers
+        require(shares != 0, DepositRecipientError::NoSharesCredited);
+
+        storage.shares.insert(owner, storage.shares.get(owner).try_read().unwrap_or(0) + shares);
+        storage.total_shares.write(total_shares + shares);
+        storage.total_assets.write(total_assets + deposit.amount);
+        log(SharesCreditedEvent {
+            owner,
+            from: deposit.from,
+            amount: deposit.amount,
+            shares,
+        });
+    }
+}
+
+impl StakingVault for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn add_rewards() {
+        require(msg_asset_id() == ContractId::from(BRIDGE), DepositRecipientError::IncorrectAssetDeposited);
+        storage.total_assets.write(storage.total_assets.read() + msg_amount());
+    }
+
+    #[storage(read, write)]
+    fn redeem(shares: u64) {
+        let owner = msg_sender().unwrap();
+        let owned = storage.shares.get(owner).try_read().unwrap_or(0);
+        require(shares != 0 && shares <= owned, DepositRecipientError::InsufficientShares);
+
+        let total_shares = storage.total_shares.read();
+        let total_assets = storage.total_assets.read();
+        let amount = mul_div(shares, total_assets, total_shares);
+
+        storage.shares.insert(owner, owned - shares);
+        storage.total_shares.write(total_shares - shares);
+        storage.total_assets.write(total_assets - amount);
+        transfer(amount, ContractId::from(BRIDGE), owner);
+    }
+
+    #[storage(read)]
+    fn shares_of(owner: Identity) -> u64 {
+        storage.shares.get(owner).try_read().unwrap_or(0)
+    }
+
+    #[storage(read)]
+    fn preview_redeem(shares: u64) -> u64 {
+        let total_shares = storage.total_shares.read();
+        if (total_shares == 0) {
+            return 0;
+        }
+        mul_div(shares, storage.total_assets.read(), total_shares)
+    }
+
+    #[storage(read)]
+    fn total_shares() -> u64 {
+        storage.total_shares.read()
+    }
+
+    #[storage(read)]
+    fn total_assets() -> u64 {
+        storage.total_assets.read()
+    }
+}
+
+/// Compute `value * numerator / denominator` rounding down, without overflowing on the product
+fn mul_div(value: u64, numerator: u64, denominator: u64) -> u64 {
+    let product = U256::from((0, 0, 0, value)) * U256::from((0, 0, 0, numerator));
+    let result = (product / U256::from((0, 0, 0, denominator))).as_u64();
+    require(result.is_ok(), DepositRecipientError::Overflow);
+    result.unwrap()
+}
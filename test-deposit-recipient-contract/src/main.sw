@@ -17,6 +17,7 @@ impl MessageReceiver for Contract {
     #[storage(read, write)]
     fn process_message(msg_idx: u8) {
         assert(input_message_data_length(msg_idx) > 161);
+        storage.val.write(true);
     }
 }
 
@@ -0,0 +1,47 @@
+contract;
+
+use contract_message_receiver::MessageReceiver;
+use fungible_bridge_abi::FungibleBridge;
+use std::{
+    call_frames::msg_asset_id,
+    constants::ZERO_B256,
+    context::msg_amount,
+    inputs::input_message_data,
+};
+
+// the attacks made during the deposit callback, selected with the ATTACK configurable
+const REENTER: u64 = 0;
+const WITHDRAW: u64 = 1;
+const BURN_GAS: u64 = 2;
+const REVERT: u64 = 3;
+
+const REVERT_CODE: u64 = 42;
+
+configurable {
+    BRIDGE: b256 = ZERO_B256,
+    ATTACK: u64 = 0,
+}
+
+impl MessageReceiver for Contract {
+    #[payable]
+    #[storage(read, write)]
+    fn process_message(msg_idx: u8) {
+        if (ATTACK == REENTER) {
+            // try to mint the same deposit twice
+            let bridge = abi(MessageReceiver, BRIDGE);
+            bridge.process_message(msg_idx);
+        } else if (ATTACK == WITHDRAW) {
+            // send the freshly minted tokens straight back to the depositor
+            let from: b256 = input_message_data(msg_idx, 32 + 32).into();
+            let bridge = abi(FungibleBridge, BRIDGE);
+            bridge.withdraw {
+                coins: msg_amount(),
+                asset_id: msg_asset_id().value,
+            }(from);
+        } else if (ATTACK == BURN_GAS) {
+            while (true) {}
+        } else if (ATTACK == REVERT) {
+            revert(REVERT_CODE);
+        }
+    }
+}